@@ -1,25 +1,37 @@
 use std::borrow::BorrowMut;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 use proc_macro2::Ident;
 
 use proc_macro2::Span;
+use proc_macro2::TokenStream;
 use syn::parse_quote;
 use syn::parse_str;
-use syn::punctuated::Punctuated;
 use syn::token;
-use syn::visit_mut::visit_angle_bracketed_generic_arguments_mut;
+use syn::visit_mut::visit_path_segment_mut;
 use syn::visit_mut::visit_type_mut;
 use syn::visit_mut::VisitMut;
 use syn::Arm;
 use syn::Binding;
+use syn::FnArg;
 use syn::GenericArgument;
+use syn::GenericParam;
+use syn::Generics;
+use syn::ImplItem;
+use syn::ItemImpl;
 use syn::ItemTrait;
-use syn::Token;
+use syn::Pat;
+use syn::PatType;
+use syn::PathArguments;
+use syn::PathSegment;
+use syn::Receiver;
+use syn::Signature;
 use syn::TraitBound as SynTraitBound;
 use syn::TraitItem;
+use syn::TraitItemConst;
 use syn::TraitItemMethod;
 use syn::TraitItemType;
 use syn::Type;
@@ -27,11 +39,17 @@ use syn::TypeParam;
 
 use crate::factory::TraitBound;
 use crate::polym::UniqueHashId;
+use crate::utils::Stringify;
+use crate::utils::TypeUtils;
 
+use super::impl_key;
 use super::ret::return_default_ret_type;
 use super::ret::return_panic;
+use super::I_SHM;
 use super::T_SHM;
 
+use super::sig::get_self_params;
+use super::sig::is_forwardable;
 use super::sig::VariantSig;
 use super::standard::StandardTrait;
 use super::standard::TraitSchematic;
@@ -68,6 +86,10 @@ pub struct Blueprint<'bound> {
     /// ```
     pub ty: Option<Box<Type>>,
 
+    /// Every concrete type that is dispatched through this blueprint. Used to infer associated
+    /// types from the impls tagged with `#[penum]`.
+    pub tys: Vec<Type>,
+
     /// Trait bound
     pub bound: &'bound TraitBound,
 
@@ -76,11 +98,17 @@ pub struct Blueprint<'bound> {
 
     /// `method_name -> [Arm]`
     pub methods: BTreeMap<Ident, Vec<Arm>>,
+
+    /// The variants that have an arm in `methods`.
+    pub dispatched: BTreeSet<Ident>,
+
+    /// The position of the bound among the dispatched bounds of the pattern, so that the impls
+    /// are generated in the order they were declared.
+    pub position: usize,
 }
 // FIXME: Should be by Trait bound instead of by Type?
-// This will stop working when `impl Trait for {A, B}` because
-// they are interpreted as two different impls.
-// `_ where i32: ^Trait, usize: ^Trait`
+// `impl Trait for {A, B}` is interpreted as two different impls, i.e.
+// `_ where i32: ^Trait, usize: ^Trait`, that are merged again in `for_each_blueprint`.
 #[repr(transparent)]
 #[derive(Default, Hash, Debug)]
 pub struct BlueprintsMap<'bound>(BTreeMap<UniqueHashId<Type>, Vec<Blueprint<'bound>>>);
@@ -105,8 +133,7 @@ struct MonomorphizeFnSignature<'poly>(&'poly BTreeMap<Ident, &'poly Type>);
 /// |
 /// This one already gets replace during polymophic mapping step.
 /// ```
-#[allow(dead_code)]
-struct MonomorphizeTraitBound<'poly>(&'poly BTreeMap<Ident, &'poly Type>);
+pub struct MonomorphizeTraitBound<'poly>(pub &'poly BTreeMap<Ident, &'poly Type>);
 
 ///        
 /// ```text
@@ -116,24 +143,39 @@ struct MonomorphizeTraitBound<'poly>(&'poly BTreeMap<Ident, &'poly Type>);
 ///                   Remove bindings form trait bound.
 ///                                        
 /// ```
-struct RemoveBoundBindings;
+pub struct RemoveBoundBindings;
 
 /// FIXME: USE VISITER PATTERN INSTEAD.
 impl<'bound> Blueprint<'bound> {
     /// Should probably be using `visit_mut` more often......
-    pub fn get_associated_methods(&self) -> Vec<TraitItemMethod> {
+    ///
+    /// A struct only has one arm per method, so we call through it directly instead of matching.
+    ///
+    /// `variants` are the patterns of every variant in declaration order, e.g. `Enum::V1 { .. }`,
+    /// which are used to order variants that aren't comparable field by field.
+    ///
+    /// `fieldless` are the patterns of the variants without fields, e.g. `Enum::V2`. These have
+    /// nothing to dispatch through, so they return the default value instead. The catch-all arm
+    /// is left out when every variant has an arm of its own.
+    pub fn get_associated_methods(
+        &self,
+        is_struct: bool,
+        variants: &[Pat],
+        fieldless: &[Pat],
+    ) -> Vec<TraitItemMethod> {
         let mut method_items = vec![];
 
         // This polymap only contains TRAIT GENERIC PARAM MAPPINGS e.g.
         // A<i32>
-        let polymap = self.get_bound_generics().map(|types| {
-            self.get_schematic_generics()
-                .zip(types)
-                .map(|(gen, ty)| (gen.ident.clone(), ty))
-                .collect::<BTreeMap<_, _>>()
-        });
+        let polymap = self.get_generics_polymap();
 
         for method in self.get_schematic_methods() {
+            // Methods that can't be forwarded are either left to the trait or reported by
+            // `get_unforwardable_methods`.
+            if !is_forwardable(&method.sig) {
+                continue;
+            }
+
             if let Some(method_arms) = self.methods.get(&method.sig.ident) {
                 let TraitItemMethod { ref sig, .. } = method;
 
@@ -160,9 +202,49 @@ impl<'bound> Blueprint<'bound> {
                     }
                 };
 
+                // Values of different variants are ordered by the order of the variants instead.
+                let default_return = self
+                    .get_ordinal_ordering(&signature, variants)
+                    .unwrap_or(default_return);
+
+                if let Some(arm) = method_arms.first().filter(|_| is_struct) {
+                    let body = &arm.body;
+                    method_items.push(parse_quote!(#signature { #body }));
+                    continue;
+                }
+
+                // Parameters of type `Self` are matched together with `self`.
+                let self_params = get_self_params(&signature);
+                let scrutinee = if self_params.is_empty() {
+                    quote::quote!(self)
+                } else {
+                    quote::quote!((self, #(#self_params),*))
+                };
+
+                let prelude = self.get_method_prelude(&signature);
+
+                // Fieldless variants can't be matched together with other parameters of type
+                // `Self`, so these are left to the catch-all arm.
+                let fieldless = if self_params.is_empty() {
+                    fieldless
+                } else {
+                    &[]
+                };
+
+                let is_exhaustive = self_params.is_empty()
+                    && self.dispatched.len() + fieldless.len() == variants.len();
+                let fallback_arm = (!is_exhaustive).then(|| quote::quote!(_ => #default_return));
+
                 // A method item that is ready to be implemented
                 let item: TraitItemMethod = parse_quote!(
-                    #signature { match self { #(#method_arms,)* _ => #default_return } }
+                    #signature {
+                        #prelude
+                        match #scrutinee {
+                            #(#method_arms,)*
+                            #(#fieldless => #default_return,)*
+                            #fallback_arm
+                        }
+                    }
                 );
 
                 method_items.push(item);
@@ -171,6 +253,139 @@ impl<'bound> Blueprint<'bound> {
         method_items
     }
 
+    /// Returns the signatures of the methods that we can't forward to the inner value, e.g.
+    /// `fn make() -> Self`. Methods with a default body are left to the trait.
+    pub fn get_unforwardable_methods(&self) -> Vec<&Signature> {
+        self.schematic
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitItem::Method(method) if method.default.is_none() => Some(&method.sig),
+                _ => None,
+            })
+            .filter(|sig| !is_forwardable(sig))
+            .collect()
+    }
+
+    /// Returns the signatures of the forwarded methods that don't borrow `self`, e.g.
+    /// `fn into_inner(self)`, which can't be dispatched through the deref target of a field.
+    pub fn get_by_value_methods(&self) -> Vec<&Signature> {
+        self.schematic
+            .items
+            .iter()
+            .filter_map(|item| match item {
+                TraitItem::Method(method) if method.default.is_none() => Some(&method.sig),
+                _ => None,
+            })
+            .filter(|sig| is_forwardable(sig))
+            .filter(|sig| {
+                !matches!(
+                    sig.receiver(),
+                    Some(FnArg::Receiver(Receiver {
+                        reference: Some(_),
+                        ..
+                    }))
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the statements that should run before we match on `self`.
+    ///
+    /// Hashing only the inner value would make two variants that hold equal values collide, so
+    /// `Hash::hash` writes the discriminant of the variant to the hasher first.
+    fn get_method_prelude(&self, signature: &Signature) -> Option<TokenStream> {
+        if self.schematic.0.ident != "Hash" || signature.ident != "hash" {
+            return None;
+        }
+
+        let state = signature.inputs.iter().find_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, .. }) => match pat.deref() {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            _ => None,
+        })?;
+
+        Some(quote::quote!(
+            ::core::hash::Hash::hash(&::core::mem::discriminant(self), #state);
+        ))
+    }
+
+    /// Returns the fallback of `Ord::cmp`, `PartialOrd::partial_cmp` and `PartialEq::eq`, which
+    /// compares the position of the variants, e.g. `V1(_) < V2(_)` given that `V1` is declared
+    /// first. This also makes unit variants equal to themselves.
+    fn get_ordinal_ordering(&self, signature: &Signature, variants: &[Pat]) -> Option<TokenStream> {
+        let method = match (
+            self.schematic.0.ident.to_string().as_str(),
+            &signature.ident,
+        ) {
+            ("Ord", ident) if ident == "cmp" => ident,
+            ("PartialEq", ident) if ident == "eq" => ident,
+            ("PartialOrd", ident) if ident == "partial_cmp" => ident,
+            _ => return None,
+        };
+
+        let other = get_self_params(signature).into_iter().next()?;
+        let ordinals = 0..variants.len();
+
+        Some(quote::quote!({
+            let ordinal = |value: &Self| match value {
+                #(#variants => #ordinals,)*
+            };
+            ordinal(self).#method(&ordinal(#other))
+        }))
+    }
+
+    /// Associated consts can't be dispatched given that they don't take `self`. Instead we
+    /// forward the const of the first concrete type, just like we do with associated types.
+    /// Consts with a default value are left to the trait.
+    ///
+    /// ```rust
+    /// const ID: u32 = <i32 as Trait>::ID;
+    /// ```
+    ///
+    /// Returns the idents of the consts that we weren't able to forward.
+    pub fn get_mapped_consts(&self) -> Result<Vec<TraitItemConst>, Vec<Ident>> {
+        let polymap = self.get_generics_polymap();
+
+        let trait_path = self.get_sanatized_impl_path();
+        let mut consts = vec![];
+        let mut unresolved = vec![];
+
+        for item in self.schematic.items.iter() {
+            let TraitItem::Const(item_const) = item else {
+                continue;
+            };
+
+            if item_const.default.is_some() {
+                continue;
+            }
+
+            let Some(ty) = self.ty.as_ref() else {
+                unresolved.push(item_const.ident.clone());
+                continue;
+            };
+
+            let ident = &item_const.ident;
+            let mut const_ty = item_const.ty.clone();
+
+            if let Some(polymap) = polymap.as_ref() {
+                MonomorphizeFnSignature(polymap).visit_type_mut(&mut const_ty)
+            }
+
+            consts.push(parse_quote!(
+                const #ident: #const_ty = <#ty as #trait_path>::#ident;
+            ));
+        }
+
+        if unresolved.is_empty() {
+            Ok(consts)
+        } else {
+            Err(unresolved)
+        }
+    }
+
     /// Used to zip `get_bound_bindings` and `get_schematic_types`
     /// together.
     ///
@@ -193,62 +408,15 @@ impl<'bound> Blueprint<'bound> {
     /// //   |
     /// //   get_bound_bindings() <> get_schematic_types()
     /// ```
-    pub fn get_mapped_bindings(&self) -> Option<Vec<TraitItemType>> {
+    ///
+    /// Associated types that aren't bound are inferred from the impls tagged with `#[penum]`, and
+    /// otherwise forwarded from the first concrete type, i.e. `<T as Trait>::Type`.
+    ///
+    /// Returns the idents of the associated types whose impls don't agree on a type.
+    pub fn get_mapped_bindings(&self) -> Result<Vec<TraitItemType>, Vec<Ident>> {
         let mut types = self.get_schematic_types().collect::<Vec<_>>();
 
-        // OMG FIX THIS SHIT
-
-        // IMPORTANT: This can still return with Some(bindings) where
-        // bindings is an empty iterator.
-        //
-        // Assume that if get_bound_bindings = None, then we have a trait bound
-        // without any generic arguments.
-        let Some(bindings) = self.get_bound_bindings() else {
-            // FIXME: Should we fallback on `<T as Trait>::Type`
-            for matc in types.iter_mut() {
-                if matc.default.is_none() && self.ty.is_some() {
-                    let ty = &self.ty;
-                    let bound = &self.bound;
-
-                    let ident = &matc.ident;
-                    let generics = &matc.generics;
-
-                    matc.default = Some((
-                        token::Eq(Span::call_site()),
-                        parse_quote!(
-                            <#ty as #bound>::#ident #generics
-                        ),
-                    ));
-                }
-            }
-
-            return Some(types);
-        };
-
-        let mut bindings = bindings.peekable();
-        let is_none = bindings.peek().is_none();
-
-        if is_none {
-            let mut types = self.get_schematic_types().collect::<Vec<_>>();
-            for matc in types.iter_mut() {
-                if matc.default.is_none() && self.ty.is_some() {
-                    let ty = &self.ty;
-                    let bound = &self.bound;
-
-                    let ident = &matc.ident;
-                    let generics = &matc.generics;
-
-                    matc.default = Some((
-                        token::Eq(Span::call_site()),
-                        parse_quote!(
-                            <#ty as #bound>::#ident #generics
-                        ),
-                    ));
-                }
-            }
-
-            Some(types)
-        } else {
+        if let Some(bindings) = self.get_bound_bindings() {
             for binding in bindings {
                 let Some(matc) = types
                     .iter_mut()
@@ -261,15 +429,85 @@ impl<'bound> Blueprint<'bound> {
                     matc.default = Some((binding.eq_token, binding.ty.clone()));
                 }
             }
+        }
+
+        let trait_path = self.get_sanatized_impl_path();
+        let mut ambiguous = vec![];
+
+        for matc in types.iter_mut().filter(|matc| matc.default.is_none()) {
+            let ident = &matc.ident;
+            let generics = &matc.generics;
+
+            let ty = match self.infer_binding(ident) {
+                Ok(Some(ty)) => ty,
+                Ok(None) => match self.ty.as_ref() {
+                    Some(ty) => parse_quote!(<#ty as #trait_path>::#ident #generics),
+                    None => continue,
+                },
+                Err(()) => {
+                    ambiguous.push(ident.clone());
+                    continue;
+                }
+            };
+
+            matc.default = Some((token::Eq(Span::call_site()), ty));
+        }
+
+        // Bounds of associated types only belong in the trait, e.g. `type Input: ?Sized`.
+        for matc in types.iter_mut() {
+            matc.colon_token = None;
+            matc.bounds.clear();
+        }
+
+        if ambiguous.is_empty() {
+            Ok(types)
+        } else {
+            Err(ambiguous)
+        }
+    }
+
+    /// Looks up the associated type `ident` in the impls tagged with `#[penum]` for each concrete
+    /// type, e.g. `type Input = str;` in `impl Abc for String`. Types without a tagged impl are
+    /// skipped, and `Err` is returned if the impls that we find don't agree.
+    fn infer_binding(&self, ident: &Ident) -> Result<Option<Type>, ()> {
+        let trait_path = self.get_sanatized_impl_path().path;
+        let mut inferred: Option<Type> = None;
+
+        for ty in self.tys.iter() {
+            let Some(item_impl) = I_SHM
+                .find(&impl_key(&trait_path, ty))
+                .and_then(|result| parse_str::<ItemImpl>(&result).ok())
+            else {
+                continue;
+            };
+
+            let Some(found) = item_impl.items.into_iter().find_map(|item| match item {
+                ImplItem::Type(item_ty) if item_ty.ident == *ident => Some(item_ty.ty),
+                _ => None,
+            }) else {
+                continue;
+            };
 
-            Some(types)
+            match inferred.as_ref() {
+                Some(inferred) if inferred.get_unique_id() != found.get_unique_id() => {
+                    return Err(())
+                }
+                Some(_) => {}
+                None => inferred = Some(found),
+            }
         }
+
+        Ok(inferred)
     }
 
     /// Fill our blueprint with dispatchable variant arms that we later
     /// use to contruct an impl statement.
     pub fn attach(&mut self, variant_sig: &VariantSig) {
+        self.dispatched
+            .insert(variant_sig.get_variant_ident().clone());
+
         let mut arms: BTreeMap<Ident, Vec<Arm>> = Default::default();
+        let polymap = self.get_generics_polymap();
 
         for item in self.schematic.items.iter() {
             let TraitItem::Method(method) = item else {
@@ -278,7 +516,13 @@ impl<'bound> Blueprint<'bound> {
 
             // FIXME: FILTER RECEIVER METHODS.
 
-            let (method_name, parsed_arm) = variant_sig.parse_arm(method);
+            // Substitute trait generics first so that e.g. `other: &Rhs` is matched as `Self`.
+            let mut method = method.clone();
+            if let Some(polymap) = polymap.as_ref() {
+                MonomorphizeFnSignature(polymap).visit_signature_mut(&mut method.sig)
+            }
+
+            let (method_name, parsed_arm) = variant_sig.parse_arm(&method);
 
             if let Some(arm_vec) = arms.get_mut(method_name) {
                 arm_vec.push(parsed_arm)
@@ -296,12 +540,31 @@ impl<'bound> Blueprint<'bound> {
         })
     }
 
+    /// Higher-ranked lifetimes, e.g. `for<'a>` in `^for<'a> Trait<'a>`, are left out since they
+    /// are declared by the impl instead. See `get_impl_generics`.
     pub fn get_sanatized_impl_path(&self) -> SynTraitBound {
         let tb = self.bound.clone();
         let mut tb: SynTraitBound = parse_quote!(#tb);
         RemoveBoundBindings.visit_trait_bound_mut(&mut tb);
+        tb.lifetimes = None;
         tb
     }
+
+    /// Adds the higher-ranked lifetimes of our bound to `generics`, i.e. `^for<'a> Trait<'a>`
+    /// gives `impl<'a> Trait<'a> for ..`, which satisfies the bound for every `'a`.
+    pub fn get_impl_generics(&self, generics: &Generics) -> Generics {
+        let mut generics = generics.clone();
+
+        if let Some(lifetimes) = self.bound.lifetimes.as_ref() {
+            for (index, lifetime) in lifetimes.lifetimes.iter().enumerate() {
+                generics
+                    .params
+                    .insert(index, GenericParam::Lifetime(lifetime.clone()));
+            }
+        }
+
+        generics
+    }
 }
 
 impl<'bound> Blueprint<'bound> {
@@ -334,6 +597,28 @@ impl<'bound> Blueprint<'bound> {
         }
     }
 
+    /// Maps each trait generic to the type that substitutes it in our trait bound. Generics that
+    /// are left out of the bound fall back on their default, e.g. `Rhs` becomes `Self` for
+    /// `PartialEq<Rhs = Self>` given `T: ^PartialEq`.
+    fn get_generics_polymap(&self) -> Option<BTreeMap<Ident, &Type>> {
+        let types = self
+            .get_bound_generics()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let polymap = self
+            .get_schematic_generics()
+            .enumerate()
+            .filter_map(|(index, gen)| {
+                let ty = types.get(index).copied().or(gen.default.as_ref())?;
+                Some((gen.ident.clone(), ty))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        (!polymap.is_empty()).then_some(polymap)
+    }
+
     /// Used to extract all generics in a trait bound. Though, we are
     /// more picking out the concrete types that substitute the
     /// generics.
@@ -425,26 +710,38 @@ impl<'bound> TryFrom<&'bound TraitBound> for Blueprint<'bound> {
         // FIXME: get_ident can be "OMG"
         let b_name = bound.get_ident();
 
-        if let Ok(schematic) = StandardTrait::try_from(&b_name) {
-            Ok(Self {
-                ty: None,
-                schematic: schematic.into(),
-                bound,
-                methods: Default::default(),
-            })
-        } else if let Some(Ok(schematic)) = T_SHM
-            .find(&b_name.to_string())
-            .as_ref()
-            .map(|result| parse_str::<ItemTrait>(result))
-        {
-            Ok(Self {
+        let find_standard = || {
+            StandardTrait::try_from(&b_name)
+                .ok()
+                .map(TraitSchematic::from)
+        };
+        let find_shared = || {
+            T_SHM
+                .find(&b_name.to_string())
+                .and_then(|result| parse_str::<ItemTrait>(&result).ok())
+                .map(TraitSchematic)
+        };
+
+        // A bound like `crate::my::Add<i32>` or `Add<i32>` may point at a trait tagged with
+        // `#[penum]`, so we give our SHM map, which is also seeded with the std operator traits,
+        // precedence over the std trait store. Bounds like `std::ops::Add<i32>` point at std.
+        let schematic = if bound.is_local_path() || bound.is_unqualified() {
+            find_shared().or_else(find_standard)
+        } else {
+            find_standard().or_else(find_shared)
+        };
+
+        match schematic {
+            Some(schematic) => Ok(Self {
                 ty: None,
-                schematic: TraitSchematic(schematic),
+                tys: vec![],
+                schematic,
                 bound,
                 methods: Default::default(),
-            })
-        } else {
-            Err(syn::Error::new_spanned(bound, trait_not_found(bound)))
+                dispatched: Default::default(),
+                position: 0,
+            }),
+            None => Err(syn::Error::new_spanned(bound, trait_not_found(bound))),
         }
     }
 }
@@ -464,28 +761,48 @@ impl<'bound> BlueprintsMap<'bound> {
     /// FIXME: Change so that we can map on trait bounds instead of just concrete types. Each
     /// implementation needs to be unique, i.e. there can only be one trait implementation per type.
     /// Note, Trait<U> and Trait<T> are considered different, so we should support generic traits.
-    pub fn for_each_blueprint(&self, mut f: impl FnMut(&Blueprint)) {
+    pub fn for_each_blueprint(&self, f: impl FnMut(&Blueprint)) {
         // TODO: We could probably just use a HashSet instead and implement Hash for Blueprint->bound.
         let mut deduplicates: BTreeMap<UniqueHashId<Type>, Blueprint<'bound>> = Default::default();
 
         for item in self.0.iter() {
             for blueprint in item.1.iter() {
-                let bound = blueprint.bound;
-                let id: Type = parse_quote!(#bound);
-                let id_unique = UniqueHashId::new(&id);
+                let id_unique = UniqueHashId::new(&blueprint.bound.ty);
 
                 // FIXME: TEMP, should fix this copy mess
+                // Types that share a bound, e.g. `impl Trait for {A, B}`, add their arms to the
+                // same methods.
                 if let Some(unique_entry) = deduplicates.get_mut(&id_unique) {
+                    for ty in blueprint.tys.iter() {
+                        if !unique_entry.tys.contains(ty) {
+                            unique_entry.tys.push(ty.clone());
+                        }
+                    }
+
+                    unique_entry.position = unique_entry.position.min(blueprint.position);
+
                     unique_entry
-                        .methods
-                        .extend(blueprint.methods.clone().into_iter());
+                        .dispatched
+                        .extend(blueprint.dispatched.iter().cloned());
+
+                    for (method_name, arms) in blueprint.methods.iter() {
+                        let entry = unique_entry.methods.entry(method_name.clone()).or_default();
+
+                        for arm in arms {
+                            if !entry.iter().any(|a| a.get_string() == arm.get_string()) {
+                                entry.push(arm.clone());
+                            }
+                        }
+                    }
                 } else {
                     deduplicates.insert(id_unique, blueprint.clone());
                 }
             }
         }
 
-        deduplicates.iter().for_each(|m| f(m.1))
+        let mut blueprints = deduplicates.into_values().collect::<Vec<_>>();
+        blueprints.sort_by_key(|blueprint| blueprint.position);
+        blueprints.iter().for_each(f)
     }
 
     pub fn find_and_attach(
@@ -494,7 +811,17 @@ impl<'bound> BlueprintsMap<'bound> {
         variant_sig: &VariantSig,
         ty: Option<&Type>,
     ) -> bool {
-        if let Some(bp_list) = self.get_mut(id) {
+        // Fall back on a path that names the same type, e.g. `::std::string::String` for a
+        // `String` field, given that we can't resolve paths.
+        let bp_list = if self.contains_key(id) {
+            self.get_mut(id)
+        } else {
+            self.iter_mut()
+                .find(|(key, _)| key.is_same_path(id))
+                .map(|(_, bp_list)| bp_list)
+        };
+
+        if let Some(bp_list) = bp_list {
             for blueprint in bp_list.iter_mut() {
                 blueprint.attach(variant_sig);
 
@@ -503,27 +830,16 @@ impl<'bound> BlueprintsMap<'bound> {
                     // Ouff, a lot of copying. Maybe use a reference?
                     blueprint.ty = Some(Box::from(unsafe { ty.unwrap_unchecked() }.clone()))
                 }
+
+                if let Some(ty) = ty.filter(|ty| !blueprint.tys.contains(ty)) {
+                    blueprint.tys.push(ty.clone());
+                }
             }
             true
         } else {
             false
         }
     }
-
-    pub fn find_and_attach_variant_sig(
-        &mut self,
-        enum_ident: &Ident,
-        variant_ident: &Ident,
-        field_item: &syn::Field,
-        field_index: usize,
-        arity: usize,
-        item_ty_unique: &UniqueHashId<Type>,
-    ) {
-        let variant_sig =
-            VariantSig::new(enum_ident, variant_ident, field_item, field_index, arity);
-
-        self.find_and_attach(item_ty_unique, &variant_sig, Some(item_ty_unique));
-    }
 }
 
 impl<'bound> Deref for BlueprintsMap<'bound> {
@@ -573,35 +889,21 @@ impl VisitMut for MonomorphizeTraitBound<'_> {
 }
 
 impl VisitMut for RemoveBoundBindings {
-    fn visit_angle_bracketed_generic_arguments_mut(
-        &mut self,
-        node: &mut syn::AngleBracketedGenericArguments,
-    ) {
-        let mut rep_gas: Punctuated<GenericArgument, Token![,]> = Default::default();
-
-        let mut args = node.args.iter().peekable();
-
-        // Ugh, refactor this
-        loop {
-            let (Some(gen), s) = (args.next(), args.peek()) else {
-                break;
-            };
-
-            if !matches!(gen, GenericArgument::Binding(_)) {
-                rep_gas.push_value(gen.clone());
-
-                if let Some(GenericArgument::Binding(_)) = s {
-                    break;
-                } else {
-                    rep_gas.push_punct(parse_quote!(,));
-                }
-            };
-        }
-
-        if args.count() != 0 {
-            node.args = rep_gas;
+    fn visit_path_segment_mut(&mut self, node: &mut PathSegment) {
+        if let PathArguments::AngleBracketed(angle) = &mut node.arguments {
+            angle.args = angle
+                .args
+                .iter()
+                .filter(|arg| !matches!(arg, GenericArgument::Binding(_)))
+                .cloned()
+                .collect();
+
+            // A bound with only bindings, e.g. `Neg<Output = i32>`, ends up as `Neg`.
+            if angle.args.is_empty() {
+                node.arguments = PathArguments::None;
+            }
         }
 
-        visit_angle_bracketed_generic_arguments_mut(self, node);
+        visit_path_segment_mut(self, node);
     }
 }
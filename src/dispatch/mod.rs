@@ -1,7 +1,23 @@
 pub use self::blueprint::Blueprint;
 pub use self::blueprint::BlueprintsMap;
+pub use self::blueprint::MonomorphizeTraitBound;
+pub use self::blueprint::RemoveBoundBindings;
 pub use self::sig::VariantSig;
 
+use std::str::FromStr;
+
+use syn::parse_str;
+use syn::ItemTrait;
+use syn::Path;
+use syn::PathArguments;
+use syn::Type;
+use syn::TypeParamBound;
+
+use crate::utils::Stringify;
+
+use self::standard::StandardTrait;
+use self::standard::TraitSchematic;
+
 mod blueprint;
 mod ret;
 mod shm;
@@ -9,4 +25,68 @@ mod sig;
 mod standard;
 
 /// Storing token streams will cause "use after free" error, so we store them as Strings instead.
-pub static T_SHM: self::shm::SharedMemory<String, String> = self::shm::SharedMemory::new();
+///
+/// Seeded with the std operator traits, e.g. `Add`, so that they can be dispatched without being
+/// declared. Traits tagged with `#[penum]` override them by name.
+pub static T_SHM: self::shm::SharedMemory<String, String> =
+    self::shm::SharedMemory::with_seed(seed_operator_traits);
+
+/// Shapes claimed by `#[penum(..)]` tagged enums, keyed by enum name. Used by `penum::family!`.
+pub static E_SHM: self::shm::SharedMemory<String, String> = self::shm::SharedMemory::new();
+
+/// Patterns registered with `penum::pattern!`, keyed by name. Used by `#[penum(use = Name)]`.
+pub static P_SHM: self::shm::SharedMemory<String, String> = self::shm::SharedMemory::new();
+
+/// Shapes registered with `penum::shape!`, keyed by name. Used by bare names in `#[penum(..)]`,
+/// e.g. `#[penum(Pair | _)]`.
+pub static S_SHM: self::shm::SharedMemory<String, String> = self::shm::SharedMemory::new();
+
+/// Impls tagged with `#[penum]`, keyed by `impl_key`. Only their associated types are kept, which
+/// are used to infer the bindings that a dispatched bound leaves out, e.g. `Input` in `^Abc`.
+pub static I_SHM: self::shm::SharedMemory<String, String> = self::shm::SharedMemory::new();
+
+/// Returns the key of an impl in `I_SHM`, e.g. `Abc for String`. Only the last segment of the trait
+/// path is used, so that the impl is found regardless of how the trait is referred to.
+pub fn impl_key(trait_path: &Path, ty: &Type) -> String {
+    let segment = trait_path.segments.last();
+    format!("{} for {}", segment.get_string(), ty.get_string())
+}
+
+fn seed_operator_traits() -> Vec<(String, String)> {
+    self::standard::OPERATOR_TRAITS
+        .iter()
+        .map(|(name, definition)| (name.to_string(), definition.to_string()))
+        .collect()
+}
+
+/// Returns the names of the direct supertraits of a trait we know of, i.e. a std trait or one
+/// tagged with `#[penum]`, e.g. `Eq` and `PartialOrd` for `Ord`. Supertraits with arguments other
+/// than `Self` are left out, since `PartialEq<Rhs>` doesn't imply `PartialEq` on its own.
+pub fn get_supertraits(name: &str) -> Vec<String> {
+    let definition = T_SHM
+        .find(&name.to_string())
+        .and_then(|definition| parse_str::<ItemTrait>(&definition).ok())
+        .or_else(|| {
+            StandardTrait::from_str(name)
+                .ok()
+                .map(|standard| TraitSchematic::from(standard).0)
+        });
+
+    let Some(definition) = definition else {
+        return vec![];
+    };
+
+    definition
+        .supertraits
+        .iter()
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(trait_bound) => trait_bound.path.segments.last(),
+            TypeParamBound::Lifetime(_) => None,
+        })
+        .filter(|segment| match &segment.arguments {
+            PathArguments::None => true,
+            arguments => arguments.get_string() == "< Self >",
+        })
+        .map(|segment| segment.ident.to_string())
+        .collect()
+}
@@ -1,23 +1,52 @@
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
 use std::sync::Mutex;
+use std::sync::Once;
+
+type Seed<K, V> = fn() -> Vec<(K, V)>;
 
 /// Used to share data between macro invokations.
 ///
 /// We use BTreeMap because we're not storing a lot of data.
+///
+/// The memory can be seeded with entries that are lazily inserted on first access. Seeded
+/// entries can be overridden like any other entry.
 #[derive(Debug)]
-pub struct SharedMemory<K, V>(Mutex<BTreeMap<K, V>>);
+pub struct SharedMemory<K, V>(Mutex<BTreeMap<K, V>>, Once, Option<Seed<K, V>>);
 unsafe impl<K, V> Sync for SharedMemory<K, V> {}
 
 impl<K, V> SharedMemory<K, V> {
     pub const fn new() -> Self {
-        Self(Mutex::new(BTreeMap::new()))
+        Self(Mutex::new(BTreeMap::new()), Once::new(), None)
+    }
+
+    pub const fn with_seed(seed: Seed<K, V>) -> Self {
+        Self(Mutex::new(BTreeMap::new()), Once::new(), Some(seed))
+    }
+
+    fn seed(&self)
+    where
+        K: Ord,
+    {
+        let Some(seed) = self.2 else {
+            return;
+        };
+
+        self.1.call_once(|| {
+            if let Ok(ref mut s) = self.0.lock() {
+                for (key, val) in seed() {
+                    s.entry(key).or_insert(val);
+                }
+            }
+        });
     }
 
     pub fn insert(&self, key: K, val: V)
     where
         K: Ord,
     {
+        self.seed();
+
         if let Ok(ref mut s) = self.0.lock() {
             s.insert(key, val);
         }
@@ -29,6 +58,8 @@ impl<K, V> SharedMemory<K, V> {
         V: Clone,
         Q: Ord,
     {
+        self.seed();
+
         if let Ok(s) = self.0.lock() {
             s.get(key).map(Clone::clone)
         } else {
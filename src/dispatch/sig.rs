@@ -1,28 +1,43 @@
 use std::ops::Deref;
 
 use proc_macro2::Span;
+use proc_macro2::TokenStream;
 
 use syn::parse_quote;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token;
 use syn::token::Comma;
+use syn::visit;
+use syn::visit::Visit;
 use syn::Arm;
 use syn::Field;
 use syn::FnArg;
 use syn::Ident;
+use syn::Index;
+use syn::Member;
 use syn::Pat;
+use syn::PatType;
+use syn::Receiver;
 use syn::Signature;
 use syn::TraitItemMethod;
+use syn::Type;
+use syn::TypePath;
+use syn::TypeReference;
 
 use quote::ToTokens;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct VariantSig<'info> {
     enum_ident: &'info Ident,
     variant_ident: &'info Ident,
     caller: Ident,
     params: Composite,
+    member: Member,
+    projection: Vec<Index>,
+    deref: bool,
+    is_struct: bool,
+    attrs: TokenStream,
 }
 
 /// For each <Dispatchable> -> <{ position, ident, fields }> Used to
@@ -36,14 +51,18 @@ pub enum Position<'a> {
     Key(&'a Ident),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Param {
     Ident(Ident),
+    /// Binds a named field to another ident, e.g. `{ items: other, .. }`.
+    Rename(Ident, Ident),
+    /// Destructures a tuple field, e.g. `(_, val, ..)` or `{ pair: (val, ..), .. }`.
+    Nested(Option<Ident>, Composite),
     Placeholder,
     Rest,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Composite {
     Named(Punctuated<Param, Comma>, token::Brace),
     Unnamed(Punctuated<Param, Comma>, token::Paren),
@@ -61,6 +80,17 @@ impl<'a> Position<'a> {
             .unwrap_or(Position::Index(fallback, field))
     }
 
+    /// The field access used when dispatching through a struct, e.g. `0` in `self.0`.
+    pub fn get_member(&self) -> Member {
+        match self {
+            Position::Index(index, field) => Member::Unnamed(Index {
+                index: *index as u32,
+                span: field.span(),
+            }),
+            Position::Key(key) => Member::Named((*key).clone()),
+        }
+    }
+
     pub fn get_caller(&self) -> Ident {
         match self {
             Position::Index(_, _) => parse_quote! {val},
@@ -80,13 +110,85 @@ impl<'info> VariantSig<'info> {
         let position = Position::from_field(field, field_index);
         let caller = position.get_caller();
         let fields = position.format_fields_pattern(max_length);
+        let member = position.get_member();
 
         Self {
             enum_ident,
             variant_ident,
             caller,
             params: fields,
+            member,
+            projection: Vec::new(),
+            deref: false,
+            is_struct: false,
+            attrs: TokenStream::new(),
+        }
+    }
+
+    pub fn get_variant_ident(&self) -> &Ident {
+        self.variant_ident
+    }
+
+    /// Used to dispatch through the deref target of the field, i.e. `(&**val).method()`.
+    pub fn via_deref(mut self, deref: bool) -> Self {
+        self.deref = deref;
+        self
+    }
+
+    /// Used to dispatch through an element of a tuple field, e.g. `(_, (val, ..), ..)` for the
+    /// path `[0]`, or `self.1.0` for a struct.
+    pub fn project(mut self, path: &[usize]) -> Self {
+        if path.is_empty() {
+            return self;
+        }
+
+        let caller: Ident = parse_quote!(val);
+        let mut nested = Param::Ident(caller.clone());
+
+        for &index in path.iter().rev() {
+            let mut punc = Punctuated::<Param, Comma>::new();
+            for _ in 0..index {
+                punc.push(Param::Placeholder);
+            }
+            punc.push(nested);
+            punc.push(Param::Rest);
+
+            nested = Param::Nested(None, Composite::Unnamed(punc, token::Paren::default()));
         }
+
+        let Param::Nested(_, nested) = nested else {
+            unreachable!()
+        };
+
+        let params = match &mut self.params {
+            Composite::Named(params, _) | Composite::Unnamed(params, _) => params,
+        };
+
+        for param in params.iter_mut() {
+            if let Param::Ident(ident) | Param::Rename(ident, _) = param {
+                let key = matches!(self.member, Member::Named(_)).then(|| ident.clone());
+                *param = Param::Nested(key, nested);
+                break;
+            }
+        }
+
+        self.caller = caller;
+        self.projection = path.iter().map(|&index| Index::from(index)).collect();
+        self.deref = false;
+        self
+    }
+
+    /// Used to put the attributes of the variant on its arms, e.g. `#[cfg(..)]`, so that they
+    /// are configured away together with it. See `get_arm_attrs`.
+    pub fn with_attrs(mut self, attrs: TokenStream) -> Self {
+        self.attrs = attrs;
+        self
+    }
+
+    /// Used to dispatch directly through the field of a struct, i.e. `self.0.method()`.
+    pub fn for_struct(mut self, is_struct: bool) -> Self {
+        self.is_struct = is_struct;
+        self
     }
 
     /// To be able to construct a dispatch arm we would need two things,
@@ -98,18 +200,129 @@ impl<'info> VariantSig<'info> {
             variant_ident,
             caller,
             params: fields,
+            deref,
+            is_struct,
+            attrs,
             ..
         } = self;
 
+        if *is_struct {
+            return (&method.sig.ident, self.parse_struct_arm(method));
+        }
+
         let (method_ident, sanitized_input) = get_method_parts(method);
 
+        let caller = match (deref, get_receiver_mutability(&method.sig)) {
+            (true, true) => quote::quote!((&mut **#caller)),
+            (true, false) => quote::quote!((&**#caller)),
+            _ => caller.to_token_stream(),
+        };
+
+        // Parameters of type `Self` are matched against the same variant as `self`, so that the
+        // inner value can be forwarded instead, e.g. `(Foo::Bar(val), Foo::Bar(other))`.
+        let self_params = get_self_params(&method.sig);
+
+        let pattern = if self_params.is_empty() {
+            quote::quote!(#enum_ident :: #variant_ident #fields)
+        } else {
+            let others = self_params
+                .iter()
+                .map(|&param| fields.rebind(param))
+                .map(|fields| quote::quote!(#enum_ident :: #variant_ident #fields));
+
+            quote::quote!((#enum_ident :: #variant_ident #fields, #(#others),*))
+        };
+
         (
             method_ident,
-            parse_quote! {#enum_ident :: #variant_ident #fields => #caller . #method_ident (#sanitized_input)},
+            parse_quote! {#attrs #pattern => #caller . #method_ident (#sanitized_input)},
         )
     }
 }
 
+impl VariantSig<'_> {
+    /// A struct has no variants to match on, so the arm is a catch all that calls the field
+    /// directly, e.g. `_ => self.0.method(x)`. Parameters of type `Self` are forwarded through
+    /// the same field, e.g. `&other.0`.
+    fn parse_struct_arm(&self, method: &TraitItemMethod) -> Arm {
+        let Self {
+            member,
+            projection,
+            deref,
+            ..
+        } = self;
+        let method_ident = &method.sig.ident;
+        let member = quote::quote!(#member #(.#projection)*);
+
+        let caller = match (deref, get_receiver_mutability(&method.sig)) {
+            (true, true) => quote::quote!((&mut *self.#member)),
+            (true, false) => quote::quote!((&*self.#member)),
+            _ => quote::quote!(self.#member),
+        };
+
+        let inputs = method.sig.inputs.iter().filter_map(|arg| {
+            let FnArg::Typed(PatType { pat, ty, .. }) = arg else {
+                return None;
+            };
+
+            match pat.deref() {
+                Pat::Ident(pat) if pat.ident == "self" => None,
+                Pat::Ident(pat) => {
+                    let ident = &pat.ident;
+                    Some(match ty.deref() {
+                        Type::Reference(TypeReference {
+                            mutability, elem, ..
+                        }) if is_self_type(elem) => {
+                            quote::quote!(&#mutability #ident.#member)
+                        }
+                        ty if is_self_type(ty) => quote::quote!(#ident.#member),
+                        _ => ident.to_token_stream(),
+                    })
+                }
+                pat => Some(pat.to_token_stream()),
+            }
+        });
+
+        parse_quote! {_ => #caller . #method_ident (#(#inputs),*)}
+    }
+}
+
+impl Composite {
+    /// Used to bind the dispatched field to another ident, e.g. `(_, val, ..)` becomes
+    /// `(_, other, ..)` and `{ key, .. }` becomes `{ key: other, .. }`.
+    pub fn rebind(&self, ident: &Ident) -> Self {
+        let rebind_params = |params: &Punctuated<Param, Comma>, named: bool| {
+            params
+                .pairs()
+                .map(|pair| {
+                    let (param, punct) = pair.into_tuple();
+                    let param = match param {
+                        Param::Ident(key) | Param::Rename(key, _) if named => {
+                            Param::Rename(key.clone(), ident.clone())
+                        }
+                        Param::Ident(_) | Param::Rename(..) => Param::Ident(ident.clone()),
+                        Param::Nested(key, nested) => {
+                            Param::Nested(key.clone(), nested.rebind(ident))
+                        }
+                        Param::Placeholder => Param::Placeholder,
+                        Param::Rest => Param::Rest,
+                    };
+                    syn::punctuated::Pair::new(param, punct.cloned())
+                })
+                .collect()
+        };
+
+        match self {
+            Composite::Named(params, brace) => {
+                Composite::Named(rebind_params(params, true), *brace)
+            }
+            Composite::Unnamed(params, paren) => {
+                Composite::Unnamed(rebind_params(params, false), *paren)
+            }
+        }
+    }
+}
+
 impl<'a> Position<'a> {
     /// We use this to format the call signature of the variant. It
     /// basically picks the value that is being dispatch and excludes
@@ -168,6 +381,18 @@ impl ToTokens for Param {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         match self {
             Param::Ident(ident) => ident.to_tokens(tokens),
+            Param::Rename(key, ident) => {
+                key.to_tokens(tokens);
+                token::Colon(Span::call_site()).to_tokens(tokens);
+                ident.to_tokens(tokens);
+            }
+            Param::Nested(key, nested) => {
+                if let Some(key) = key {
+                    key.to_tokens(tokens);
+                    token::Colon(Span::call_site()).to_tokens(tokens);
+                }
+                nested.to_tokens(tokens);
+            }
             Param::Placeholder => token::Underscore(Span::call_site()).to_tokens(tokens),
             Param::Rest => token::Dot2(Span::call_site()).to_tokens(tokens),
         }
@@ -181,7 +406,17 @@ fn sanitize(inputs: &Punctuated<FnArg, Comma>) -> Punctuated<Pat, Comma> {
     inputs.iter().enumerate().for_each(|(i, arg)| match arg {
         syn::FnArg::Receiver(_) => (),
         syn::FnArg::Typed(typed) => {
-            san.push_value(typed.pat.deref().clone());
+            // Forward the binding only, e.g. `mut x: u8` is passed on as `x`.
+            let pat = match typed.pat.deref() {
+                Pat::Ident(pat) if pat.ident == "self" => return,
+                Pat::Ident(pat) => {
+                    let ident = &pat.ident;
+                    parse_quote!(#ident)
+                }
+                pat => pat.clone(),
+            };
+
+            san.push_value(pat);
             if i != max - 1 {
                 san.push_punct(Comma(Span::call_site()));
             }
@@ -190,6 +425,61 @@ fn sanitize(inputs: &Punctuated<FnArg, Comma>) -> Punctuated<Pat, Comma> {
     san
 }
 
+/// Returns the parameters that are of type `Self`, `&Self` or `&mut Self`, e.g. `other` in
+/// `fn cmp(&self, other: &Self)`.
+pub fn get_self_params(sig: &Signature) -> Vec<&Ident> {
+    sig.inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(PatType { pat, ty, .. }) => match (pat.deref(), ty.deref()) {
+                (Pat::Ident(pat), ty) if pat.ident != "self" && is_self_type(ty) => {
+                    Some(&pat.ident)
+                }
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// Returns true if a method can be forwarded to the inner value of a variant. Associated
+/// functions have no `self` to dispatch through, and a method that returns `Self`, e.g. `-> Self`
+/// or `-> Option<Self>`, would return the inner value instead of our enum.
+pub fn is_forwardable(sig: &Signature) -> bool {
+    struct FindSelf(bool);
+
+    impl<'ast> Visit<'ast> for FindSelf {
+        fn visit_type_path(&mut self, node: &'ast TypePath) {
+            self.0 |= node.qself.is_none() && node.path.is_ident("Self");
+            visit::visit_type_path(self, node);
+        }
+    }
+
+    let mut find_self = FindSelf(false);
+    find_self.visit_return_type(&sig.output);
+
+    sig.receiver().is_some() && !find_self.0
+}
+
+fn is_self_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(TypeReference { elem, .. }) => is_self_type(elem),
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("Self"),
+        _ => false,
+    }
+}
+
+fn get_receiver_mutability(sig: &Signature) -> bool {
+    matches!(
+        sig.receiver(),
+        Some(FnArg::Receiver(Receiver {
+            reference: Some(_),
+            mutability: Some(_),
+            ..
+        }))
+    )
+}
+
 fn get_method_parts(method: &TraitItemMethod) -> (&Ident, Punctuated<Pat, Comma>) {
     let TraitItemMethod { sig, .. } = method;
     let Signature { ident, inputs, .. } = sig;
@@ -1,3 +1,3 @@
 pub trait Hash {
-    fn hash<H: ~const Hasher>(&self, state: &mut H);
-}
\ No newline at end of file
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H);
+}
@@ -0,0 +1,3 @@
+pub trait PartialOrd<Rhs: ?Sized = Self>: PartialEq<Rhs> {
+    fn partial_cmp(&self, other: &Rhs) -> Option<std::cmp::Ordering>;
+}
@@ -10,6 +10,10 @@ pub enum StandardTrait {
     Borrow,
     BorrowMut,
     Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
     AsMut,
     AsRef,
     From,
@@ -73,6 +77,32 @@ pub enum StandardTrait {
     ToString,
 }
 
+/// The std operator traits that we seed our SHM map with, as `(name, definition)`.
+pub const OPERATOR_TRAITS: &[(&str, &str)] = &[
+    ("Add", include_str!("./Add.rs")),
+    ("AddAssign", include_str!("./AddAssign.rs")),
+    ("BitAnd", include_str!("./BitAnd.rs")),
+    ("BitAndAssign", include_str!("./BitAndAssign.rs")),
+    ("BitOr", include_str!("./BitOr.rs")),
+    ("BitOrAssign", include_str!("./BitOrAssign.rs")),
+    ("BitXor", include_str!("./BitXor.rs")),
+    ("BitXorAssign", include_str!("./BitXorAssign.rs")),
+    ("Div", include_str!("./Div.rs")),
+    ("DivAssign", include_str!("./DivAssign.rs")),
+    ("Mul", include_str!("./Mul.rs")),
+    ("MulAssign", include_str!("./MulAssign.rs")),
+    ("Neg", include_str!("./Neg.rs")),
+    ("Not", include_str!("./Not.rs")),
+    ("Rem", include_str!("./Rem.rs")),
+    ("RemAssign", include_str!("./RemAssign.rs")),
+    ("Shl", include_str!("./Shl.rs")),
+    ("ShlAssign", include_str!("./ShlAssign.rs")),
+    ("Shr", include_str!("./Shr.rs")),
+    ("ShrAssign", include_str!("./ShrAssign.rs")),
+    ("Sub", include_str!("./Sub.rs")),
+    ("SubAssign", include_str!("./SubAssign.rs")),
+];
+
 #[repr(transparent)]
 #[derive(Clone, Hash, Debug)]
 pub struct TraitSchematic(pub ItemTrait);
@@ -86,6 +116,10 @@ impl From<StandardTrait> for TraitSchematic {
                 StandardTrait::Borrow => parse_str(include_str!("./Borrow.rs")),
                 StandardTrait::BorrowMut => parse_str(include_str!("./BorrowMut.rs")),
                 StandardTrait::Eq => parse_str(include_str!("./Eq.rs")),
+                StandardTrait::PartialEq => parse_str(include_str!("./PartialEq.rs")),
+                StandardTrait::Ord => parse_str(include_str!("./Ord.rs")),
+                StandardTrait::PartialOrd => parse_str(include_str!("./PartialOrd.rs")),
+                StandardTrait::Hash => parse_str(include_str!("./Hash.rs")),
                 StandardTrait::AsMut => parse_str(include_str!("./AsMut.rs")),
                 StandardTrait::AsRef => parse_str(include_str!("./AsRef.rs")),
                 StandardTrait::From => parse_str(include_str!("./From.rs")),
@@ -162,6 +196,10 @@ impl FromStr for StandardTrait {
             "Borrow" => Ok(Self::Borrow),
             "BorrowMut" => Ok(Self::BorrowMut),
             "Eq" => Ok(Self::Eq),
+            "PartialEq" => Ok(Self::PartialEq),
+            "Ord" => Ok(Self::Ord),
+            "PartialOrd" => Ok(Self::PartialOrd),
+            "Hash" => Ok(Self::Hash),
             "AsMut" => Ok(Self::AsMut),
             "AsRef" => Ok(Self::AsRef),
             "From" => Ok(Self::From),
@@ -1,11 +1,13 @@
 use std::{cell::RefCell, fmt::Display};
 
 use proc_macro2::{Span, TokenStream};
-use quote::ToTokens;
+use quote::{format_ident, ToTokens};
 use syn::Error;
 
+/// Errors are combined into one `syn::Error`, while warnings are kept as span/message pairs
+/// given that we have to emit them ourselves.
 #[derive(Default)]
-pub struct Diagnostic(RefCell<Option<Error>>);
+pub struct Diagnostic(RefCell<Option<Error>>, RefCell<Vec<(Span, String)>>);
 
 impl Diagnostic {
     pub fn extend(&self, span: Span, error: impl Display) {
@@ -36,4 +38,53 @@ impl Diagnostic {
     pub fn has_error(&self) -> bool {
         self.0.borrow().is_some()
     }
+
+    /// Returns every error that has been accumulated, combined into one.
+    pub fn into_error(self) -> Option<Error> {
+        self.0.into_inner()
+    }
+
+    /// Returns the number of errors that have been accumulated.
+    pub fn error_count(&self) -> usize {
+        self.0
+            .borrow()
+            .as_ref()
+            .map_or(0, |error| error.clone().into_iter().count())
+    }
+
+    /// Emits one `compile_error!` per accumulated error instead of one combined error, so that
+    /// tooling shows each of them at its own span.
+    pub fn to_compile_errors(&self) -> TokenStream {
+        self.0
+            .borrow()
+            .iter()
+            .flat_map(|error| error.clone().into_iter())
+            .map(|error| error.to_compile_error())
+            .collect()
+    }
+
+    pub fn warn(&self, span: Span, warning: impl Display) {
+        self.1.borrow_mut().push((span, warning.to_string()));
+    }
+
+    /// There's no stable way for a proc-macro to emit warnings, so we instead generate a usage
+    /// of a deprecated item which makes the compiler print our message as a warning.
+    pub fn warnings_to_tokens(&self) -> TokenStream {
+        self.1
+            .borrow()
+            .iter()
+            .enumerate()
+            .map(|(index, (span, warning))| {
+                let name = format_ident!("penum_warning_{}", index, span = *span);
+                quote::quote_spanned!(*span=>
+                    const _: () = {
+                        #[deprecated(note = #warning)]
+                        #[allow(non_upper_case_globals)]
+                        const #name: () = ();
+                        #name
+                    };
+                )
+            })
+            .collect()
+    }
 }
@@ -4,6 +4,8 @@ use syn::{
     punctuated::Punctuated, token, BoundLifetimes, Lifetime, Token, TraitBoundModifier, Type,
 };
 
+use crate::utils::TypeAliases;
+
 mod parse;
 mod to_tokens;
 
@@ -16,10 +18,10 @@ pub struct WhereClause {
 #[derive(Debug)]
 pub enum WherePredicate {
     Type(PredicateType),
-    Lifetime(PredicateLifetime), // NOT SUPPORTED
+    Lifetime(PredicateLifetime),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct PredicateType {
     pub lifetimes: Option<BoundLifetimes>,
     pub bounded_ty: Type,
@@ -34,10 +36,9 @@ pub struct PredicateLifetime {
     pub bounds: Punctuated<Lifetime, Token![+]>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum TypeParamBound {
     Trait(TraitBound),
-    #[allow(dead_code)]
     Lifetime(Lifetime),
 }
 
@@ -50,6 +51,22 @@ pub struct TraitBound {
     pub ty: Type,
 }
 
+impl WhereClause {
+    /// Replaces every type alias within the bounded types and bounds of the clause with the type
+    /// it stands for, e.g. `Bytes: AsRef<Bytes>` becomes `Vec<u8>: AsRef<Vec<u8>>`.
+    pub fn resolve_type_aliases(&mut self, aliases: &TypeAliases) {
+        for predicate in self.predicates.iter_mut() {
+            if let WherePredicate::Type(pred) = predicate {
+                aliases.resolve(&mut pred.bounded_ty);
+
+                for bound in pred.bounds.iter_mut() {
+                    bound.resolve_type_aliases(aliases);
+                }
+            }
+        }
+    }
+}
+
 impl TypeParamBound {
     /// FIXME: Only get methods with receivers. `fn method()` vs `fn method(&self)`.
     pub fn get_dispatchable_trait_bound(&self) -> Option<&TraitBound> {
@@ -58,6 +75,13 @@ impl TypeParamBound {
             _ => None,
         }
     }
+
+    /// Only the arguments of a trait are resolved, since the trait itself is never an alias.
+    pub fn resolve_type_aliases(&mut self, aliases: &TypeAliases) {
+        if let TypeParamBound::Trait(tb) = self {
+            aliases.resolve_nested(&mut tb.ty);
+        }
+    }
 }
 
 impl TraitBound {
@@ -73,4 +97,21 @@ impl TraitBound {
             format_ident!("{}", "omg")
         }
     }
+
+    /// Used to check if the trait bound is a path that starts with `crate`, `self` or
+    /// `super`, e.g. `crate::my::Trait<i32>`.
+    pub fn is_local_path(&self) -> bool {
+        matches!(
+            &self.ty,
+            Type::Path(p) if p.qself.is_none() && p.path.segments.len() > 1 && matches!(
+                p.path.segments.first().map(|seg| seg.ident.to_string()).as_deref(),
+                Some("crate" | "self" | "super")
+            )
+        )
+    }
+
+    /// Used to check if the trait bound is a bare name, e.g. `Trait<i32>`.
+    pub fn is_unqualified(&self) -> bool {
+        matches!(&self.ty, Type::Path(p) if p.qself.is_none() && p.path.segments.len() == 1)
+    }
 }
@@ -7,6 +7,8 @@ use syn::{
     TraitBoundModifier,
 };
 
+use crate::utils::peek_trailing_option;
+
 use super::*;
 
 impl Parse for WhereClause {
@@ -37,7 +39,7 @@ impl Parse for WhereClause {
                     }
                     let value = input.parse()?;
                     predicates.push_value(value);
-                    if !input.peek(Token![,]) {
+                    if !input.peek(Token![,]) || peek_trailing_option(input) {
                         break;
                     }
                     let punct = input.parse()?;
@@ -150,7 +152,9 @@ impl Parse for TraitBound {
         let modifier: TraitBoundModifier = input.parse()?;
         let lifetimes: Option<BoundLifetimes> = input.parse()?;
 
-        let mut ty: Type = input.parse()?;
+        // Each bound is parsed on its own, otherwise `^AsRef<str> + Display` would be read as
+        // a single bound.
+        let mut ty: Type = input.call(Type::without_plus)?;
 
         // FIXME: Should probably look over this again
         if let Type::Path(ref mut path) = ty {
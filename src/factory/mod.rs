@@ -1,9 +1,16 @@
 #![allow(dead_code)]
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::iter::repeat;
 use std::iter::zip;
 
 use syn::Field;
 use syn::Fields;
+use syn::Ident;
+
+use crate::utils::unify_types;
+use crate::utils::TypeUtils;
 
 mod clause;
 mod pattern;
@@ -34,12 +41,74 @@ pub struct Comparable<'disc, T> {
     /// Some(usize) implies it has variadic at position `usize`.
     variadic: Option<usize>,
 
+    /// The minimum and maximum number of fields a variadic can take, e.g. `(1, Some(3))` for
+    /// `1..3`. An unbounded variadic is `(0, None)`.
+    residual: (usize, Option<usize>),
+
     /// The number of arguments in the group.
     arity: usize,
+
+    /// The number of named fields that an item can leave out, e.g. `age` in `{ age?: usize }`.
+    optional: usize,
+
+    /// The name of the variant of an item.
+    ident: Option<&'disc Ident>,
+
+    /// The variants that a pattern is limited to, e.g. `Foo` in `Foo(T, ..)` or `Net*`.
+    selector: Option<&'disc VariantSelector>,
+
+    /// The clause that is only asserted for the variants a pattern matches.
+    clause: Option<&'disc WhereClause>,
+
+    /// The range that the discriminant of the variants a pattern matches has to be in.
+    discriminant: Option<&'disc DiscriminantRange>,
 }
 
 /// This is just an intermediate struct to hide some logic behind.
-pub struct ComparablePats<'disc>(Vec<Comparable<'disc, PatComposite>>);
+///
+/// Patterns are indexed by the composite kind they are able to match, so that we only have to
+/// try compatible fragments for each variant. Note that an `Inferred` fragment is indexed under
+/// every kind.
+pub struct ComparablePats<'disc> {
+    patterns: Vec<Comparable<'disc, PatComposite>>,
+    index: BTreeMap<CompositeKind, Vec<usize>>,
+
+    /// The position of every negated pattern, e.g. `!(String, ..)`. These are never matched, but
+    /// reject the variants they would have matched.
+    rejects: Vec<usize>,
+
+    /// The number of variants each pattern has been matched with.
+    hits: Vec<Cell<usize>>,
+}
+
+/// Used to explain why an item didn't satisfy the arity of a variadic pattern.
+pub enum ArityMismatch {
+    /// The item needs at least this many fields.
+    TooFew(usize),
+
+    /// The item can have at most this many fields.
+    TooMany(usize),
+}
+
+/// Used to point at the field that keeps an item from matching its closest pattern.
+pub enum FieldMismatch<'disc> {
+    /// The field at this position has another type than the pattern expects.
+    Type(usize, &'disc Field, &'disc Field),
+
+    /// The item is missing the field at this position.
+    Missing(usize, &'disc PatFieldKind),
+
+    /// The item has a field at this position that the pattern doesn't.
+    Unexpected(usize, &'disc Field),
+}
+
+/// The three shapes a variant (and pattern fragment) can come in.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum CompositeKind {
+    Unit,
+    Unnamed,
+    Named,
+}
 
 /// We use this to identify what kind of pair we have matched.
 ///
@@ -73,31 +142,236 @@ impl<'disc> ComparablePair<'disc> {
         self.0.inner
     }
 
-    /// Given that we only allow variadic at the end lets us always be able to zip these together.
+    /// Pairs each pattern field with the item field at the same position, together with the
+    /// position of the item field. Fields in front of a variadic are aligned from the start and
+    /// fields after it from the end, e.g. `(T, .., U)` pairs `T` with the first field and `U`
+    /// with the last field. The variadic itself is left out.
     ///
-    pub fn zip(&self) -> impl Iterator<Item = (&PatFieldKind, &Field)> {
-        if self.contains_residual() {
-            // Might be better to emit this as a compile error instead.
-            debug_assert!(self.has_variadic_last());
+    /// Named fields are paired by name instead, e.g. `{ age: usize, name: T }` pairs `age` with
+    /// the `age` field of the item wherever it's declared. Pattern fields that the item lacks are
+    /// left out, see `find_name_mismatches`, and wildcards, e.g. `_: T`, are paired with whichever
+    /// field fits, see `pair_named_fields`.
+    ///
+    /// NOTE: We only allow one variadic per pattern, and expect the pair to already satisfy the
+    /// minimum arity.
+    pub fn zip(&self) -> impl Iterator<Item = (usize, &'disc PatFieldKind, &'disc Field)> {
+        if self.is_named() {
+            return self
+                .pair_named_fields()
+                .into_iter()
+                .filter_map(|(param, paired)| paired.map(|(index, field)| (index, param, field)))
+                .collect::<Vec<_>>()
+                .into_iter();
         }
 
         // FIXME: We could probably use a different strategy than this one.
-        if let PatComposite::Inferred = self.0.inner {
-            zip(repeat(&PatFieldKind::Infer), self.1.inner)
-                .collect::<Vec<(&PatFieldKind, &Field)>>()
-                .into_iter()
+        if let PatComposite::Inferred(_) = self.0.inner {
+            return zip(repeat(&PatFieldKind::Infer), self.1.inner)
+                .enumerate()
+                .map(|(index, (param, field))| (index, param, field))
+                .collect::<Vec<_>>()
+                .into_iter();
+        }
+
+        let Some(position) = self.0.variadic else {
+            return zip(self.0.inner, self.1.inner)
+                .enumerate()
+                .map(|(index, (param, field))| (index, param, field))
+                .collect::<Vec<_>>()
+                .into_iter();
+        };
+
+        let params: Vec<&PatFieldKind> = self.0.inner.iter().collect();
+        let fields: Vec<&Field> = self.1.inner.iter().collect();
+        let suffix = &params[position + 1..];
+        let offset = fields.len().saturating_sub(suffix.len());
+
+        let head = zip(&params[..position], &fields)
+            .enumerate()
+            .map(|(index, (param, field))| (index, *param, *field));
+
+        let tail = zip(suffix, &fields[offset..])
+            .enumerate()
+            .map(|(index, (param, field))| (offset + index, *param, *field));
+
+        head.chain(tail).collect::<Vec<_>>().into_iter()
+    }
+
+    /// Returns the variadic of our pattern together with the item fields that it covers, e.g.
+    /// `[i32, u8]` for `(String, ..)` given `V(String, i32, u8)`. A named item is covered by
+    /// every field that the pattern doesn't mention.
+    pub fn get_residual(&self) -> Option<(&'disc PatFieldKind, Vec<&'disc Field>)> {
+        let position = self.0.variadic?;
+        let params: Vec<&PatFieldKind> = self.0.inner.iter().collect();
+
+        let fields: Vec<&Field> = if self.is_named() {
+            let paired = self.get_paired_positions();
+
+            self.1
+                .inner
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !paired.contains(index))
+                .map(|(_, field)| field)
+                .collect()
         } else {
-            zip(self.0.inner, self.1.inner)
-                .collect::<Vec<(&PatFieldKind, &Field)>>()
-                .into_iter()
+            let fields: Vec<&Field> = self.1.inner.iter().collect();
+            let end = fields.len().saturating_sub(params.len() - position - 1);
+            fields[position.min(end)..end].to_vec()
+        };
+
+        Some((params[position], fields))
+    }
+
+    /// Used to check if every concrete type in our pattern is equal to the item type at the same
+    /// position, or not equal if it's negated. Generics, placeholders, impl expressions and
+    /// inferred fields are always satisfied.
+    ///
+    /// NOTE: Nothing is recorded here, so it's fine to call this on pairs that we end up
+    /// discarding.
+    pub fn is_structurally_satisfied(&self) -> bool {
+        self.find_name_mismatches().is_empty()
+            && self.zip().all(|(_, param_pattern, field_item)| {
+                param_pattern.get_field().is_none_or(|pat_field| {
+                    is_type_satisfied(pat_field, field_item) != param_pattern.is_negated()
+                })
+            })
+    }
+
+    /// Counts the fields that keep the pair from matching in structure, i.e. every concrete type
+    /// that isn't satisfied and every name mismatch. It's zero only if the pair is structurally
+    /// satisfied, and is used to pick which pair to report errors for when none is.
+    pub fn count_mismatches(&self) -> usize {
+        self.find_name_mismatches().len()
+            + self
+                .zip()
+                .filter(|(_, param_pattern, field_item)| {
+                    param_pattern.get_field().is_some_and(|pat_field| {
+                        is_type_satisfied(pat_field, field_item) == param_pattern.is_negated()
+                    })
+                })
+                .count()
+    }
+
+    /// Returns every named pattern field that the item lacks, and every named item field that
+    /// the pattern doesn't mention, e.g. `Missing(age)` and `Unexpected(id)` for `{ age: usize }`
+    /// given `V { id: usize }`. Unexpected fields are allowed if the pattern has a variadic, and
+    /// optional fields, e.g. `{ age?: usize }`, are never missing.
+    ///
+    /// NOTE: Pairs that aren't named on both sides never have name mismatches.
+    pub fn find_name_mismatches(&self) -> Vec<FieldMismatch<'disc>> {
+        if !self.is_named() {
+            return vec![];
         }
+
+        let missing = self
+            .pair_named_fields()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (param, paired))| {
+                !param.is_optional() && param.get_field().is_some() && paired.is_none()
+            })
+            .map(|(index, (param, _))| FieldMismatch::Missing(index, param));
+
+        let paired = self.get_paired_positions();
+        let unexpected = self
+            .1
+            .inner
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.contains_residual() && !paired.contains(index))
+            .map(|(index, field)| FieldMismatch::Unexpected(index, field));
+
+        missing.chain(unexpected).collect()
+    }
+
+    /// Pairs every field of a named pattern with the item field of the same name. A wildcard,
+    /// e.g. `_: T`, is paired with the first unpaired item field that satisfies its type, or with
+    /// the first unpaired item field if none does, so that the mismatch can be reported.
+    fn pair_named_fields(&self) -> Vec<(&'disc PatFieldKind, Option<(usize, &'disc Field)>)> {
+        let mut pairs: Vec<_> = self
+            .0
+            .inner
+            .iter()
+            .map(|param| {
+                let paired = param
+                    .get_field()
+                    .and_then(|pat_field| pat_field.ident.as_ref())
+                    .and_then(|ident| self.find_item_field(ident));
+
+                (param, paired)
+            })
+            .collect();
+
+        for position in 0..pairs.len() {
+            let (param, _) = pairs[position];
+            let Some(pat_field) = param.get_field().filter(|_| param.is_wildcard()) else {
+                continue;
+            };
+
+            let unpaired: Vec<(usize, &Field)> = self
+                .1
+                .inner
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| {
+                    !pairs
+                        .iter()
+                        .any(|(_, paired)| paired.is_some_and(|(taken, _)| taken == *index))
+                })
+                .collect();
+
+            pairs[position].1 = unpaired
+                .iter()
+                .find(|(_, field)| is_type_satisfied(pat_field, field))
+                .or(unpaired.first())
+                .copied();
+        }
+
+        pairs
+    }
+
+    /// Returns the positions of the item fields that our pattern pairs with.
+    fn get_paired_positions(&self) -> Vec<usize> {
+        self.pair_named_fields()
+            .into_iter()
+            .filter_map(|(_, paired)| paired.map(|(index, _)| index))
+            .collect()
+    }
+
+    /// Returns the clause of the matched fragment, e.g. `where T: Copy` in `(T) where T: Copy | (U)`.
+    pub fn get_clause(&self) -> Option<&'disc WhereClause> {
+        self.0.clause
+    }
+
+    /// Returns the discriminant range of the matched fragment, e.g. `0..=255` in `V = 0..=255`.
+    pub fn get_discriminant(&self) -> Option<&'disc DiscriminantRange> {
+        self.0.discriminant
+    }
+
+    /// Used to check if both the pattern and the item have named fields.
+    fn is_named(&self) -> bool {
+        matches!(
+            (self.0.inner, self.1.inner),
+            (PatComposite::Named { .. }, Fields::Named(..))
+        )
+    }
+
+    /// Returns the item field with the given name, together with its position.
+    fn find_item_field(&self, ident: &syn::Ident) -> Option<(usize, &'disc Field)> {
+        self.1
+            .inner
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.ident.as_ref() == Some(ident))
     }
 
-    /// Used to ensure that a matched pair have the same arity.
+    /// Used to ensure that a matched pair have the same arity, where optional fields may be
+    /// left out by the item.
     ///
     /// If they do not we deduce that the item doesn't match our pattern.
     fn check_arity_equality(&self) -> bool {
-        matches!(self, ComparablePair(p, i) if p.arity == i.arity)
+        matches!(self, ComparablePair(p, i) if (p.minimum_arity()..=p.arity).contains(&i.arity))
     }
 
     /// Use to check if our pattern contains a variadic field.
@@ -118,10 +392,33 @@ impl<'disc> ComparablePair<'disc> {
 
     /// Use this only when you know that our pattern contains a variadic field.
     ///  
-    /// Check if the item satisfies the minimum parameter length required.
+    /// Check if the item satisfies the minimum parameter length required, e.g. `(T, 1..)`
+    /// requires at least two fields.
     fn check_minimum_arity_satisfaction(&self) -> bool {
-        // NOTE: Change this if we every choose to accept variadic at positions other than last. e.g (T, .., T) | (.., T)
-        matches!(self, ComparablePair(p, i) if p.variadic.map(|_| p.arity - 1).unwrap_or_else(|| p.arity) <= i.arity )
+        // NOTE: The variadic can be at any position, e.g (T, .., T) | (.., T), but only once.
+        matches!(self, ComparablePair(p, i) if p.minimum_arity() <= i.arity )
+    }
+
+    /// Use this only when you know that our pattern contains a variadic field.
+    ///
+    /// Check if the item stays within the maximum parameter length of a bounded variadic, e.g.
+    /// `(T, ..2)` accepts at most three fields.
+    fn check_maximum_arity_satisfaction(&self) -> bool {
+        matches!(self, ComparablePair(p, i) if p.maximum_arity().is_none_or(|max| i.arity <= max))
+    }
+
+    /// Use this only when you know that our pattern contains a variadic field.
+    ///
+    /// The fields that a named variadic covers are the ones that our pattern doesn't pair with,
+    /// so optional fields and wildcards can shift how many it takes, e.g. `{ id: u64, meta?: u8,
+    /// ..=1 }` takes two fields given `V { id: u64, a: u8, b: u8 }`. Those are counted directly.
+    fn check_residual_satisfaction(&self) -> bool {
+        let (min, max) = self.0.residual;
+
+        !self.is_named()
+            || self.get_residual().is_none_or(|(_, fields)| {
+                min <= fields.len() && max.is_none_or(|max| fields.len() <= max)
+            })
     }
 
     fn match_kind(&self) -> MatchKind {
@@ -131,19 +428,257 @@ impl<'disc> ComparablePair<'disc> {
 
             (PatComposite::Unit, Fields::Unit) => MatchKind::Empty,
 
-            (PatComposite::Inferred, _) => MatchKind::Inferred,
+            (PatComposite::Inferred(Some(constraint)), fields)
+                if constraint.kind != CompositeKind::from(fields) =>
+            {
+                MatchKind::None
+            }
+            (PatComposite::Inferred(_), _) => MatchKind::Inferred,
             _ => MatchKind::None,
         }
     }
 }
 
 impl<'disc> ComparablePats<'disc> {
-    /// Each compare creates a new Iter where we then compare incoming field with each pattern
-    pub fn compare(&'disc self, comp_item: &'disc Comparable<Fields>) -> Option<ComparablePair> {
-        self.iter().find_map(into_comparable_pair(comp_item))
+    /// Returns every pattern that matches the item in shape, in declaration order.
+    pub fn compare(&'disc self, comp_item: &'disc Comparable<Fields>) -> Vec<ComparablePair> {
+        self.candidates(comp_item)
+            .filter_map(into_comparable_pair(comp_item))
+            .collect()
+    }
+
+    /// Counts the pattern of the pair as used by a variant.
+    pub fn mark_matched(&self, pair: &ComparablePair) {
+        if let Some(position) = self
+            .patterns
+            .iter()
+            .position(|pattern| std::ptr::eq(pattern, pair.0))
+        {
+            self.hits[position].set(self.hits[position].get() + 1);
+        }
+    }
+
+    /// Returns the position of every pattern that hasn't been matched with any variant.
+    pub fn unmatched(&self) -> impl Iterator<Item = usize> + '_ {
+        self.hits
+            .iter()
+            .enumerate()
+            .filter(|(_, hits)| hits.get() == 0)
+            .map(|(position, _)| position)
+            .filter(|position| !self.rejects.contains(position))
+    }
+
+    /// Returns the position of every pattern that can never be selected, together with the
+    /// position of the earlier pattern that shadows it, e.g. `(i32, ..)` is shadowed by `(..)` in
+    /// `(..) | (i32, ..)`. Negated patterns and a bare `_` are never considered.
+    pub fn find_unreachable(&self) -> Vec<(usize, usize)> {
+        let reachable = |position: &usize| {
+            !self.rejects.contains(position) && !self.patterns[*position].inner.is_catch_all()
+        };
+
+        (0..self.patterns.len())
+            .filter(reachable)
+            .filter_map(|position| {
+                (0..position)
+                    .filter(reachable)
+                    .find(|&earlier| self.patterns[earlier].shadows(&self.patterns[position]))
+                    .map(|earlier| (position, earlier))
+            })
+            .collect()
+    }
+
+    /// Returns the position of the first negated pattern that matches the item in both shape and
+    /// structure, e.g. `!(String, ..)` given `V(String, i32)`.
+    pub fn find_rejection(&'disc self, comp_item: &'disc Comparable<Fields>) -> Option<usize> {
+        let kind = CompositeKind::from(comp_item.inner);
+
+        self.rejects.iter().copied().find(|&position| {
+            let pattern = &self.patterns[position];
+
+            pattern
+                .inner
+                .get_kind()
+                .is_none_or(|pattern_kind| pattern_kind == kind)
+                && pattern.selects(comp_item.ident)
+                && into_comparable_pair(comp_item)(pattern)
+                    .is_some_and(|pair| pair.is_structurally_satisfied())
+        })
+    }
+
+    /// Returns the first variadic pattern whose arity the item didn't satisfy together with why,
+    /// e.g. `TooFew(2)` for `(T, .., U)` given `V(i32)`, or `TooMany(2)` for `(..2)` given
+    /// `V(i32, i32, i32)`.
+    pub fn find_arity_mismatch(
+        &'disc self,
+        comp_item: &Comparable<Fields>,
+    ) -> Option<(&'disc Comparable<'disc, PatComposite>, ArityMismatch)> {
+        self.candidates(comp_item)
+            .filter(|pattern| pattern.variadic.is_some())
+            .find_map(|pattern| {
+                let minimum = pattern.minimum_arity();
+
+                if comp_item.arity < minimum {
+                    return Some((pattern, ArityMismatch::TooFew(minimum)));
+                }
+
+                pattern
+                    .maximum_arity()
+                    .filter(|&maximum| comp_item.arity > maximum)
+                    .map(|maximum| (pattern, ArityMismatch::TooMany(maximum)))
+            })
+    }
+
+    /// Returns the pattern closest to an item that didn't match any of them, together with the
+    /// first field that keeps it from matching. The distance is the arity difference plus the
+    /// number of fields with mismatched concrete types, and patterns further away than
+    /// `max_distance` are ignored. Named fields are compared by name, so the distance is the
+    /// number of mismatched, missing and unexpected fields instead.
+    ///
+    /// NOTE: This should only be used when reporting errors.
+    pub fn find_closest(
+        &'disc self,
+        comp_item: &'disc Comparable<Fields>,
+        max_distance: usize,
+    ) -> Option<(&'disc Comparable<'disc, PatComposite>, FieldMismatch<'disc>)> {
+        let fields: Vec<&Field> = comp_item.inner.iter().collect();
+
+        self.candidates(comp_item)
+            .filter(|pattern| pattern.variadic.is_none() && pattern.inner.is_compound())
+            .filter_map(|pattern| {
+                let pair = ComparablePair(pattern, comp_item);
+
+                if pair.is_named() {
+                    let mut mismatches = pair
+                        .zip()
+                        .filter_map(|(index, param, field)| {
+                            param
+                                .get_field()
+                                .filter(|pat_field| {
+                                    is_type_satisfied(pat_field, field) == param.is_negated()
+                                })
+                                .map(|pat_field| FieldMismatch::Type(index, pat_field, field))
+                        })
+                        .chain(pair.find_name_mismatches())
+                        .collect::<Vec<_>>();
+
+                    let distance = mismatches.len();
+
+                    return (distance > 0 && distance <= max_distance)
+                        .then(|| (distance, pattern, mismatches.remove(0)));
+                }
+
+                let params: Vec<&PatFieldKind> = pattern.inner.iter().collect();
+
+                let mut mismatches = zip(&params, &fields)
+                    .enumerate()
+                    .filter_map(|(index, (param, field))| {
+                        param
+                            .get_field()
+                            .filter(|pat_field| {
+                                is_type_satisfied(pat_field, field) == param.is_negated()
+                            })
+                            .map(|pat_field| FieldMismatch::Type(index, pat_field, field))
+                    })
+                    .collect::<Vec<_>>();
+
+                let distance = params.len().abs_diff(fields.len()) + mismatches.len();
+
+                if distance > max_distance {
+                    return None;
+                }
+
+                let mismatch = if mismatches.is_empty() {
+                    match params.len().cmp(&fields.len()) {
+                        Ordering::Greater => {
+                            FieldMismatch::Missing(fields.len(), params[fields.len()])
+                        }
+                        Ordering::Less => {
+                            FieldMismatch::Unexpected(params.len(), fields[params.len()])
+                        }
+                        Ordering::Equal => return None,
+                    }
+                } else {
+                    mismatches.remove(0)
+                };
+
+                Some((distance, pattern, mismatch))
+            })
+            .min_by_key(|(distance, ..)| *distance)
+            .map(|(_, pattern, mismatch)| (pattern, mismatch))
+    }
+
+    /// Returns the pattern that an item, which didn't match any pattern, is one field short of or
+    /// one field over, e.g. `(i32, i32)` given `V(u8)`. If there are several, the one with the
+    /// fewest mismatched field types is picked.
+    ///
+    /// NOTE: This should only be used when reporting errors.
+    pub fn find_near_arity(
+        &'disc self,
+        comp_item: &'disc Comparable<Fields>,
+    ) -> Option<&'disc Comparable<'disc, PatComposite>> {
+        self.candidates(comp_item)
+            .filter(|pattern| pattern.variadic.is_none() && pattern.inner.is_compound())
+            .filter(|pattern| pattern.arity.abs_diff(comp_item.arity) == 1)
+            .min_by_key(|pattern| {
+                ComparablePair(pattern, comp_item)
+                    .zip()
+                    .filter(|(_, param, field)| {
+                        param.get_field().is_some_and(|pat_field| {
+                            is_type_satisfied(pat_field, field) == param.is_negated()
+                        })
+                    })
+                    .count()
+            })
+    }
+
+    /// Returns the patterns that could match the item's composite kind, in declaration order.
+    pub fn candidates(
+        &'disc self,
+        comp_item: &Comparable<Fields>,
+    ) -> impl Iterator<Item = &'disc Comparable<'disc, PatComposite>> {
+        let variant_ident = comp_item.ident.cloned();
+
+        self.index
+            .get(&CompositeKind::from(comp_item.inner))
+            .into_iter()
+            .flatten()
+            .map(|&position| &self.patterns[position])
+            .filter(move |pattern| pattern.selects(variant_ident.as_ref()))
     }
 }
 
+/// Checks if a field satisfies the type of a pattern field, i.e. if the pattern type is generic,
+/// a placeholder, an `impl Trait`, the same concrete type or a container that unifies with it,
+/// e.g. `Vec<T>` and `Vec<i32>`.
+fn is_type_satisfied(param: &Field, field: &Field) -> bool {
+    param.ty.get_type_impl_trait().is_some()
+        || param.ty.is_generic()
+        || param.ty.is_placeholder()
+        || unify_types(&param.ty, &field.ty, &mut BTreeMap::new())
+}
+
+/// Checks if a pattern field accepts every type that another pattern field accepts, i.e. if it's
+/// inferred, generic, a placeholder or an `impl Trait`, or if both are concrete types that unify,
+/// e.g. `Vec<T>` and `Vec<i32>`. Residuals are never covered, since they can shift the fields.
+fn param_covers(param: &PatFieldKind, other: &PatFieldKind) -> bool {
+    if other.is_residual() || param.is_negated() {
+        return false;
+    }
+
+    let Some(pat_field) = param.get_field() else {
+        return param.is_infer();
+    };
+
+    let ty = &pat_field.ty;
+
+    ty.get_type_impl_trait().is_some()
+        || ty.is_generic()
+        || ty.is_placeholder()
+        || other.get_field().is_some_and(|field| {
+            !other.is_negated() && unify_types(ty, &field.ty, &mut BTreeMap::new())
+        })
+}
+
 /// This is a very expensive way of finding a match. We should convert both into ComparableItems before looping over them.
 pub fn into_comparable_pair<'a>(
     fields: &'a Comparable<Fields>,
@@ -152,12 +687,15 @@ pub fn into_comparable_pair<'a>(
         let cmp_pair = ComparablePair::from((shape, fields));
 
         match cmp_pair.match_kind() {
-            MatchKind::Inferred => Some(cmp_pair),
+            MatchKind::Inferred => (!cmp_pair.as_composite().has_arity_constraint()
+                || cmp_pair.check_arity_equality())
+            .then_some(cmp_pair),
             MatchKind::Compound => {
-                if cmp_pair.has_variadic_last() {
-                    cmp_pair
-                        .check_minimum_arity_satisfaction()
-                        .then_some(cmp_pair)
+                if cmp_pair.contains_residual() {
+                    (cmp_pair.check_minimum_arity_satisfaction()
+                        && cmp_pair.check_maximum_arity_satisfaction()
+                        && cmp_pair.check_residual_satisfaction())
+                    .then_some(cmp_pair)
                 } else {
                     cmp_pair.check_arity_equality().then_some(cmp_pair)
                 }
@@ -177,7 +715,17 @@ mod boilerplate {
         type Target = Vec<Comparable<'disc, PatComposite>>;
 
         fn deref(&self) -> &Self::Target {
-            &self.0
+            &self.patterns
+        }
+    }
+
+    impl From<&Fields> for CompositeKind {
+        fn from(value: &Fields) -> Self {
+            match value {
+                Fields::Named(_) => CompositeKind::Named,
+                Fields::Unnamed(_) => CompositeKind::Unnamed,
+                Fields::Unit => CompositeKind::Unit,
+            }
         }
     }
 
@@ -189,13 +737,60 @@ mod boilerplate {
 
     impl<'disc> From<&'disc PenumExpr> for ComparablePats<'disc> {
         fn from(value: &'disc PenumExpr) -> Self {
-            Self(
-                value
-                    .pattern
-                    .iter()
-                    .map(|pattern| Comparable::from(&pattern.group))
-                    .collect(),
-            )
+            let patterns: Vec<_> = value
+                .pattern
+                .iter()
+                .map(|pattern| {
+                    Comparable::from(&pattern.group)
+                        .with_selector(pattern.get_selector())
+                        .with_clause(pattern.clause.as_ref())
+                        .with_discriminant(pattern.discriminant.as_ref())
+                })
+                .collect();
+
+            let mut index: BTreeMap<CompositeKind, Vec<usize>> = Default::default();
+
+            let rejects: Vec<usize> = value
+                .pattern
+                .iter()
+                .enumerate()
+                .filter(|(_, fragment)| fragment.is_negated())
+                .map(|(position, _)| position)
+                .collect();
+
+            for (position, pattern) in patterns.iter().enumerate() {
+                if rejects.contains(&position) {
+                    continue;
+                }
+
+                let kinds = match pattern.inner.get_kind() {
+                    Some(kind) => vec![kind],
+                    None => vec![
+                        CompositeKind::Unit,
+                        CompositeKind::Unnamed,
+                        CompositeKind::Named,
+                    ],
+                };
+
+                for kind in kinds {
+                    index.entry(kind).or_default().push(position);
+                }
+            }
+
+            // A bare `_` is a fallback, so it's tried after every other pattern no matter where
+            // it's placed, e.g. `_ | (i32)` behaves like `(i32) | _`.
+            for positions in index.values_mut() {
+                positions.sort_by_key(|&position| patterns[position].inner.is_catch_all());
+            }
+
+            let hits = patterns.iter().map(|_| Cell::new(0)).collect();
+
+            Self {
+                patterns,
+                index,
+                rejects,
+                hits,
+            }
         }
     }
 
@@ -212,17 +807,143 @@ mod boilerplate {
             Self {
                 inner: value,
                 variadic: value.get_variadic_position(),
+                residual: value.get_residual_bounds(),
                 arity: value.len(),
+                optional: value.count_with(PatFieldKind::is_optional),
+                ident: None,
+                selector: None,
+                clause: None,
+                discriminant: None,
             }
         }
     }
 
+    impl<'disc> Comparable<'disc, Fields> {
+        /// Names the variant of an item, so that it can be matched by selectors.
+        pub fn with_ident(mut self, ident: &'disc Ident) -> Self {
+            self.ident = Some(ident);
+            self
+        }
+    }
+
     impl<'disc> Comparable<'disc, PatComposite> {
+        /// Limits a pattern to the variants that the selector matches.
+        pub fn with_selector(mut self, selector: Option<&'disc VariantSelector>) -> Self {
+            self.selector = selector;
+            self
+        }
+
+        /// Used to check if a pattern can be compared with a variant, i.e. if it has no selector
+        /// or if the selector matches the name of the variant.
+        pub fn selects(&self, variant_ident: Option<&Ident>) -> bool {
+            self.selector
+                .is_none_or(|selector| variant_ident.is_some_and(|ident| selector.matches(ident)))
+        }
+
+        /// Scopes a clause to the variants that the pattern matches, e.g. `where T: Copy` in
+        /// `(T) where T: Copy | (U)`.
+        pub fn with_clause(mut self, clause: Option<&'disc WhereClause>) -> Self {
+            self.clause = clause;
+            self
+        }
+
+        /// Limits the discriminant of the variants that the pattern matches, e.g. `V = 0..=255`.
+        pub fn with_discriminant(mut self, discriminant: Option<&'disc DiscriminantRange>) -> Self {
+            self.discriminant = discriminant;
+            self
+        }
+
+        /// The number of fields an item needs, not counting the variadic itself.
+        pub fn minimum_arity(&self) -> usize {
+            let arity = self.arity - self.optional;
+
+            match self.variadic {
+                Some(_) => arity - 1 + self.residual.0,
+                None => arity,
+            }
+        }
+
+        /// The number of fields an item can have at most, where `None` means unbounded.
+        pub fn maximum_arity(&self) -> Option<usize> {
+            match self.variadic {
+                Some(_) => self.residual.1.map(|max| self.arity - 1 + max),
+                None => Some(self.arity),
+            }
+        }
+
+        /// Used to check if every variant that the other pattern matches in structure is matched
+        /// by this one as well, in which case the other pattern can't be selected if it comes
+        /// after this one. This is conservative, so selectors, optional fields and wildcard names
+        /// are never considered to shadow anything.
+        pub fn shadows(&self, other: &Comparable<PatComposite>) -> bool {
+            if self.selector.is_some()
+                || self.optional > 0
+                || other.optional > 0
+                || self.inner.count_with(PatFieldKind::is_wildcard) > 0
+                || other.inner.count_with(PatFieldKind::is_wildcard) > 0
+            {
+                return false;
+            }
+
+            let covers_arity = self.minimum_arity() <= other.minimum_arity()
+                && match (self.maximum_arity(), other.maximum_arity()) {
+                    (None, _) => true,
+                    (Some(max), Some(other_max)) => other_max <= max,
+                    (Some(_), None) => false,
+                };
+
+            match (self.inner, other.inner) {
+                (PatComposite::Unit, PatComposite::Unit) => true,
+                (PatComposite::Inferred(Some(constraint)), composite) => {
+                    composite.get_kind() == Some(constraint.kind)
+                        && !composite.has_arity_constraint()
+                        && covers_arity
+                }
+                (PatComposite::Unnamed { parameters, .. }, PatComposite::Unnamed { .. }) => {
+                    let others: Vec<_> = other.inner.iter().collect();
+                    let position = self.variadic.unwrap_or(parameters.len());
+                    let suffix = parameters.iter().skip(position + 1);
+
+                    covers_arity
+                        && parameters
+                            .iter()
+                            .take(position)
+                            .zip(others.iter())
+                            .chain(suffix.rev().zip(others.iter().rev()))
+                            .all(|(param, other_param)| param_covers(param, other_param))
+                }
+                (PatComposite::Named { .. }, PatComposite::Named { .. }) => {
+                    let name_of = |param: &PatFieldKind| {
+                        param.get_field().and_then(|field| field.ident.clone())
+                    };
+
+                    covers_arity
+                        && self
+                            .inner
+                            .iter()
+                            .filter(|param| param.is_field())
+                            .all(|param| {
+                                other.inner.iter().any(|other_param| {
+                                    name_of(param) == name_of(other_param)
+                                        && param_covers(param, other_param)
+                                })
+                            })
+                }
+                _ => false,
+            }
+        }
+
         pub fn new(value: &'disc PatComposite) -> Self {
             Self {
                 inner: value,
                 variadic: value.get_variadic_position(),
+                residual: value.get_residual_bounds(),
                 arity: value.len(),
+                optional: value.count_with(PatFieldKind::is_optional),
+                ident: None,
+                selector: None,
+                clause: None,
+                discriminant: None,
             }
         }
     }
@@ -232,8 +953,830 @@ mod boilerplate {
             Self {
                 inner: value,
                 variadic: None,
+                residual: (0, None),
                 arity: value.len(),
+                optional: 0,
+                ident: None,
+                selector: None,
+                clause: None,
+                discriminant: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::ToTokens;
+    use syn::{parse_quote, Fields, ItemEnum};
+
+    use crate::utils::{TypeUtils, DEFAULT_FRAGMENT_LIMIT};
+
+    use super::{Comparable, CompositeKind, FieldMismatch, PatFieldKind, PenumExpr, Subject};
+
+    #[test]
+    fn only_compatible_kinds_are_tried() {
+        let tuples = (0..100).map(|_| quote::quote!((T, ..)));
+        let structs = (0..100).map(|_| quote::quote!({ name: T, .. }));
+        let fragments = itertools::interleave(tuples, structs);
+        let fragments = itertools::intersperse(fragments, quote::quote!(|));
+
+        let expr: PenumExpr = parse_quote!(#(#fragments)* | _ where T: Trait);
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32),
+                V2 { name: i32 },
+                V3,
             }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let variants: Vec<&Fields> = item.variants.iter().map(|v| &v.fields).collect();
+
+        let tuple = Comparable::from(variants[0]);
+        let named = Comparable::from(variants[1]);
+        let unit = Comparable::from(variants[2]);
+
+        // The trailing `_` is compatible with every kind.
+        assert_eq!(101, comparable_pats.candidates(&tuple).count());
+        assert_eq!(101, comparable_pats.candidates(&named).count());
+        assert_eq!(1, comparable_pats.candidates(&unit).count());
+
+        assert!(comparable_pats
+            .candidates(&tuple)
+            .all(|pat| matches!(pat.inner.get_kind(), Some(CompositeKind::Unnamed) | None)));
+        assert!(comparable_pats
+            .candidates(&named)
+            .all(|pat| matches!(pat.inner.get_kind(), Some(CompositeKind::Named) | None)));
+
+        assert!(!comparable_pats.compare(&tuple).is_empty());
+        assert!(!comparable_pats.compare(&named).is_empty());
+    }
+
+    #[test]
+    fn find_fragments_shadowed_by_earlier_ones() {
+        let unreachable = |expr: PenumExpr| expr.get_comparable_patterns().find_unreachable();
+
+        assert_eq!(vec![(1, 0)], unreachable(parse_quote!((..) | (i32, ..))));
+        assert_eq!(
+            vec![(1, 0)],
+            unreachable(parse_quote!((T, ..) | (i32, String)))
+        );
+        assert_eq!(
+            vec![(1, 0)],
+            unreachable(parse_quote!((i32, ..) | (i32, U)))
+        );
+        assert_eq!(vec![(1, 0)], unreachable(parse_quote!((.., T) | (u8, i32))));
+        assert_eq!(
+            vec![(2, 0)],
+            unreachable(parse_quote!(_(2) | { .. } | (i32, u8)))
+        );
+        assert_eq!(
+            vec![(1, 0)],
+            unreachable(parse_quote!({ name: T, .. } | { name: String, id: u8 }))
+        );
+        assert_eq!(
+            vec![(1, 0)],
+            unreachable(parse_quote!((Vec<T>) | (Vec<i32>)))
+        );
+        assert_eq!(vec![(1, 0)], unreachable(parse_quote!(() | Foo())));
+
+        // Fragments that match variants the earlier ones don't are reachable.
+        for expr in [
+            parse_quote!((i32, ..) | (..)),
+            parse_quote!((T) | (T, ..)),
+            parse_quote!((i32, ..) | (U, i32)),
+            parse_quote!((..2) | (T, T, T)),
+            parse_quote!({ name: T } | { name: T, id: u8 }),
+            parse_quote!({ name: T, age?: u8 } | { name: T }),
+            parse_quote!(Foo(..) | (i32)),
+            parse_quote!(!(..) | (i32)),
+            parse_quote!(_ | (i32)),
+            parse_quote!((!String) | (i32)),
+            parse_quote!((i32) | (T)),
+        ] {
+            assert!(unreachable(expr).is_empty());
+        }
+    }
+
+    #[test]
+    fn string_patterns_parse_like_token_patterns() {
+        let from_str: PenumExpr = parse_quote!("(T, ..) | { name: String } where T: Trait");
+        let from_tokens: PenumExpr = parse_quote!((T, ..) | { name: String } where T: Trait);
+
+        assert_eq!(
+            from_tokens.pattern_to_string(),
+            from_str.pattern_to_string()
+        );
+        assert!(from_str.clause.is_some());
+
+        let error = syn::parse_str::<PenumExpr>(r#""(T,, U)""#).err();
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn catch_all_is_tried_last() {
+        let expr: PenumExpr = parse_quote!(_ | (i32, ..) | { name: String });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(String),
+                V3 { name: String },
+                V4,
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let satisfied_by: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| Comparable::from(&variant.fields))
+            .map(|item| {
+                comparable_pats
+                    .compare(&item)
+                    .iter()
+                    .find(|pair| pair.is_structurally_satisfied())
+                    .is_some_and(|pair| pair.as_composite().is_catch_all())
+            })
+            .collect();
+
+        // Only the variants that the other patterns reject fall through to `_`.
+        assert_eq!(vec![false, true, false, true], satisfied_by);
+    }
+
+    #[test]
+    fn catch_all_only_catches_rejected_variants() {
+        let expr: PenumExpr = parse_quote!((i32, ..) | _ where String: Trait);
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(String),
+                V3 { name: String },
+                V4,
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let items: Vec<_> = item
+            .variants
+            .iter()
+            .map(|variant| Comparable::from(&variant.fields))
+            .collect();
+
+        let matched: Vec<bool> = items
+            .iter()
+            .map(|item| {
+                comparable_pats
+                    .compare(item)
+                    .iter()
+                    .find(|pair| pair.is_structurally_satisfied())
+                    .map(|pair| pair.as_composite().get_kind().is_none())
+                    .unwrap()
+            })
+            .collect();
+
+        // Only `V1` is matched by `(i32, ..)`, the rest fall through to `_`.
+        assert_eq!(vec![false, true, true, true], matched);
+    }
+
+    #[test]
+    fn negated_fields_reject_their_own_type() {
+        let expr: PenumExpr = parse_quote!((!String, ..) | { name: !String });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(String),
+                V3 { name: u8 },
+                V4 { name: String },
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let satisfied: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| Comparable::from(&variant.fields))
+            .map(|item| {
+                comparable_pats
+                    .compare(&item)
+                    .iter()
+                    .any(|pair| pair.is_structurally_satisfied())
+            })
+            .collect();
+
+        assert_eq!(vec![true, false, true, false], satisfied);
+        assert_eq!(
+            "(! String , ..) | { name : ! String }",
+            expr.pattern_to_string()
+        );
+    }
+
+    #[test]
+    fn options_can_be_followed_by_a_semicolon() {
+        let expr: PenumExpr = parse_quote!(strict; (T) | { .. });
+        assert!(expr.is_strict());
+        assert_eq!(2, expr.pattern.len());
+
+        let expr: PenumExpr = parse_quote!(strict; via_deref, (T));
+        assert!(expr.is_strict() && expr.has_via_deref());
+
+        assert!(syn::parse_str::<PenumExpr>("strict (T)").is_err());
+    }
+
+    #[test]
+    fn type_aliases_can_follow_the_pattern() {
+        let expr: PenumExpr = parse_quote!((T) | { name: T }, alias(Bytes = Vec<u8>, Id = u64));
+        assert_eq!(2, expr.pattern.len());
+        assert_eq!(2, expr.get_type_aliases().len());
+
+        let expr: PenumExpr = parse_quote!((T) where T: Copy, alias(Bytes = Vec<u8>));
+        assert_eq!(1, expr.clause.as_ref().unwrap().predicates.len());
+        assert_eq!(1, expr.get_type_aliases().len());
+
+        let expr: PenumExpr = parse_quote!(impl Trait for Bytes, i32, alias(Bytes = Vec<u8>));
+        assert_eq!(2, expr.clause.as_ref().unwrap().predicates.len());
+        assert_eq!(1, expr.get_type_aliases().len());
+
+        let expr: PenumExpr = parse_quote!(alias(Bytes = Vec<u8>), (T));
+        assert_eq!(1, expr.get_type_aliases().len());
+
+        assert!(syn::parse_str::<PenumExpr>("(T), alias(Bytes)").is_err());
+    }
+
+    #[test]
+    fn fragment_limit_can_be_configured() {
+        let expr: PenumExpr = parse_quote!((T) | { name: T });
+        assert_eq!(DEFAULT_FRAGMENT_LIMIT, expr.get_fragment_limit());
+
+        let expr: PenumExpr = parse_quote!(fragment_limit(128), (T) | { name: T });
+        assert_eq!(128, expr.get_fragment_limit());
+        assert_eq!(2, expr.pattern.len());
+
+        let expr: PenumExpr = parse_quote!((T), fragment_limit(1));
+        assert_eq!(1, expr.get_fragment_limit());
+
+        assert!(syn::parse_str::<PenumExpr>("fragment_limit(many), (T)").is_err());
+    }
+
+    #[test]
+    fn registered_patterns_are_parsed_where_used() {
+        crate::dispatch::P_SHM.insert("SharedPair".to_string(), "(T, U) where T: Copy".to_string());
+
+        let expr: PenumExpr = parse_quote!(strict, use = SharedPair);
+        assert_eq!("(T , U)", expr.pattern_to_string());
+        assert!(expr.is_strict());
+        assert!(expr.clause.is_some());
+
+        let error = syn::parse_str::<PenumExpr>("use = UnknownPair").err();
+        assert!(error.is_some());
+
+        // Only shapes can be used by name alone, while other names still select a variant.
+        let expr: PenumExpr = parse_quote!(SharedPair);
+        assert!(expr.pattern[0].get_selector().is_some());
+        assert!(expr.clause.is_none());
+    }
+
+    #[test]
+    fn registered_shapes_are_used_by_name() {
+        crate::dispatch::S_SHM.insert("NamedPair".to_string(), "(T, U) where T: Copy".to_string());
+
+        let expr: PenumExpr = parse_quote!(NamedPair);
+        assert_eq!("(T , U)", expr.pattern_to_string());
+
+        // A shape can't be used as a pattern, and the other way around.
+        assert!(syn::parse_str::<PenumExpr>("use = NamedPair").is_err());
+
+        let expr: PenumExpr = parse_quote!(UnknownPair);
+        assert!(expr.pattern[0].get_selector().is_some());
+        assert!(expr.clause.is_none());
+    }
+
+    #[test]
+    fn registered_patterns_compose_with_fragments() {
+        crate::dispatch::S_SHM.insert(
+            "ComposedPair".to_string(),
+            "(T, U) where T: Copy".to_string(),
+        );
+
+        let expr: PenumExpr = parse_quote!(ComposedPair | (T, ..) | _ where U: Clone);
+        assert_eq!(3, expr.pattern.len());
+        assert_eq!(1, expr.clause.as_ref().unwrap().predicates.len());
+
+        // The clause of the shape is scoped to its own fragment.
+        let clause = expr.pattern[0].clause.as_ref().unwrap();
+        assert_eq!(1, clause.predicates.len());
+        assert!(expr.pattern[1].clause.is_none());
+
+        // Only names in the place of a fragment are looked up.
+        let expr: PenumExpr = parse_quote!(ComposedPair(T) | _);
+        assert!(expr.pattern[0].get_selector().is_some());
+        assert!(expr.clause.is_none());
+    }
+
+    #[test]
+    fn negated_fragments_reject_instead_of_match() {
+        let expr: PenumExpr = parse_quote!(!(String, ..) | (T, ..));
+        let comparable_pats = expr.get_comparable_patterns();
+
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(String, i32),
+                V2(i32, String),
+            }
+        );
+
+        let rejections: Vec<Option<usize>> = item
+            .variants
+            .iter()
+            .map(|variant| comparable_pats.find_rejection(&Comparable::from(&variant.fields)))
+            .collect();
+
+        assert_eq!(vec![Some(0), None], rejections);
+
+        // Without any fragment to match, every variant that isn't rejected is accepted.
+        let expr: PenumExpr = parse_quote!(!(String, ..));
+        assert_eq!(2, expr.pattern.len());
+        assert!(expr.pattern[1].group.is_catch_all());
+
+        let error = syn::parse_str::<PenumExpr>("!(T) where T: Copy | (U)").err();
+        assert!(error.is_some());
+    }
+
+    #[test]
+    fn generics_can_have_a_default_type() {
+        let expr: PenumExpr = parse_quote!((T = i32, U) | { name: T, age: U = u8 });
+        let defaults: Vec<(String, String)> = expr
+            .get_generic_defaults()
+            .into_iter()
+            .map(|(generic, ty)| (generic.to_string(), ty.to_token_stream().to_string()))
+            .collect();
+
+        assert_eq!(
+            vec![
+                ("T".to_string(), "i32".to_string()),
+                ("U".to_string(), "u8".to_string())
+            ],
+            defaults
+        );
+        assert_eq!(
+            2,
+            expr.pattern[0]
+                .group
+                .iter()
+                .filter(|param| param.is_field())
+                .count()
+        );
+
+        for pattern in ["(i32 = u8)", "(T = U)", "(T = _)", "(T = i32) | (T = u8)"] {
+            assert!(syn::parse_str::<PenumExpr>(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn impl_expressions_can_be_listed() {
+        let expr: PenumExpr = parse_quote!(impl Abc for String, impl Abc for i32);
+        assert_eq!(2, expr.clause.as_ref().unwrap().predicates.len());
+
+        let expr: PenumExpr = parse_quote!(impl Abc for String, u8 + impl Def for { i32, i64 });
+        assert_eq!(4, expr.clause.unwrap().predicates.len());
+        assert!(expr.pattern[0].group.is_catch_all());
+    }
+
+    #[test]
+    fn discriminant_ranges_are_inclusive() {
+        let expr: PenumExpr = parse_quote!(A = 3 | B = -1..1 | C = ..=7 | D = 2.. | * = _ as u8);
+        let ranges: Vec<(i128, i128)> = expr
+            .pattern
+            .iter()
+            .filter_map(|fragment| fragment.discriminant.as_ref())
+            .map(|range| (range.min, range.max))
+            .collect();
+
+        assert_eq!(
+            vec![(3, 3), (-1, 0), (i128::MIN, 7), (2, i128::MAX), (0, 255)],
+            ranges
+        );
+
+        for pattern in ["A = 3..3", "A = 1..=", "A = _ as str"] {
+            assert!(syn::parse_str::<PenumExpr>(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn generics_can_be_declared_up_front() {
+        let expr: PenumExpr = parse_quote!(strict, for<T: ^Copy, U> (T, U) | (U, T) where U: Clone);
+        assert!(expr.is_strict());
+        assert_eq!(2, expr.pattern.len());
+        assert_eq!(2, expr.clause.as_ref().unwrap().predicates.len());
+
+        let expr: PenumExpr = parse_quote!(for<T> { items: Vec<T>, .. });
+        assert!(expr.clause.is_none());
+
+        for pattern in [
+            "for<T, T> (T)",
+            "for<T> (T, U)",
+            "for<T, U> (T)",
+            "for<T> (T) where U: Copy",
+            "for<T> (T: Into<U>)",
+            "for<Foo> (Foo)",
+        ] {
+            assert!(syn::parse_str::<PenumExpr>(pattern).is_err(), "{pattern}");
         }
     }
+
+    #[test]
+    fn inferred_arity_constrains_kind_and_arity() {
+        let expr: PenumExpr = parse_quote!(_(3) | _{ 2 });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32, i32, i32),
+                V2(i32, i32),
+                V3 { a: i32, b: i32 },
+                V4 { a: i32, b: i32, c: i32 },
+                V5,
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let matches: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                !comparable_pats
+                    .compare(&Comparable::from(&variant.fields))
+                    .is_empty()
+            })
+            .collect();
+
+        assert_eq!(vec![true, false, true, false, false], matches);
+    }
+
+    #[test]
+    fn bounded_variadic_limits_arity() {
+        let expr: PenumExpr = parse_quote!((T, ..2) | { name: T, ..0 } where T: Trait);
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32),
+                V2(i32, u8),
+                V3(i32, u8, u16),
+                V4(i32, u8, u16, u32),
+                V5(),
+                V6 { name: i32 },
+                V7 { name: i32, age: u8 },
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let matches: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                !comparable_pats
+                    .compare(&Comparable::from(&variant.fields))
+                    .is_empty()
+            })
+            .collect();
+
+        assert_eq!(vec![true, true, true, false, false, true, false], matches);
+    }
+
+    #[test]
+    fn inclusive_and_min_max_ranges_limit_arity() {
+        let expr: PenumExpr = parse_quote!((T, 1..2) | (..=0) | { name: T, 0..0 } where T: Trait);
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32),
+                V2(i32, u8),
+                V3(i32, u8, u16),
+                V4(i32, u8, u16, u32),
+                V5(),
+                V6 { name: i32 },
+                V7 { name: i32, age: u8 },
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let matches: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                !comparable_pats
+                    .compare(&Comparable::from(&variant.fields))
+                    .is_empty()
+            })
+            .collect();
+
+        assert_eq!(vec![false, true, true, false, true, true, false], matches);
+    }
+
+    #[test]
+    fn find_closest_pattern_by_distance() {
+        let expr: PenumExpr = parse_quote!((i32, T) | (String, T, T) where T: Trait);
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(i32, u8, u8),
+                V2(String, u8),
+                V3(u8, u8, u8, u8, u8),
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let closest: Vec<Option<(usize, usize)>> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let comparable_item = Comparable::from(&variant.fields);
+                comparable_pats
+                    .find_closest(&comparable_item, 2)
+                    .map(|(pattern, mismatch)| {
+                        let position = match mismatch {
+                            FieldMismatch::Type(index, ..)
+                            | FieldMismatch::Missing(index, _)
+                            | FieldMismatch::Unexpected(index, _) => index,
+                        };
+                        (pattern.arity, position)
+                    })
+            })
+            .collect();
+
+        assert_eq!(vec![Some((2, 2)), Some((2, 0)), None], closest);
+    }
+
+    #[test]
+    fn find_pattern_of_near_arity() {
+        let expr: PenumExpr =
+            parse_quote!((i32, i32) | (String, String, String, String) | (T, .., T));
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(u8, u8, u8),
+                V2(String, String, u8),
+                V3(u8),
+                V4 { a: u8 },
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let near: Vec<Option<usize>> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let comparable_item = Comparable::from(&variant.fields);
+                comparable_pats
+                    .find_near_arity(&comparable_item)
+                    .map(|pattern| pattern.arity)
+            })
+            .collect();
+
+        assert_eq!(vec![Some(2), Some(4), Some(2), None], near);
+    }
+
+    #[test]
+    fn attributes_survive_censoring() {
+        let subject: Subject = parse_quote!(
+            enum Enum {
+                /// A documented variant.
+                #[deprecated]
+                V1 = "v1",
+                #[cfg(any())]
+                V2(#[allow(unused)] i32) = "v2",
+                V3,
+            }
+        );
+
+        let arms = subject
+            .variants_to_arms(|expr| quote::quote!(#expr))
+            .to_string();
+        let (subject, _) = subject.get_censored_subject_and_default_arm(None);
+        let variants: Vec<_> = subject.get_variants().iter().collect();
+
+        // Arms of a configured variant are configured as well, and we don't warn about our own
+        // use of a deprecated variant.
+        assert!(
+            arms.contains(&quote::quote!(#[allow(deprecated)] Self::V1 => { "v1" },).to_string())
+        );
+        assert!(arms.contains(&quote::quote!(#[cfg(any())] Self::V2(f0) => { "v2" },).to_string()));
+
+        assert!(variants
+            .iter()
+            .all(|variant| variant.discriminant.is_none()));
+        assert!(variants[0]
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("deprecated")));
+        assert!(variants[0]
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("doc")));
+        assert!(variants[1]
+            .attrs
+            .iter()
+            .any(|attr| attr.path.is_ident("cfg")));
+        assert!(matches!(
+            &variants[1].fields,
+            Fields::Unnamed(fields) if fields.unnamed[0].attrs[0].path.is_ident("allow")
+        ));
+    }
+
+    #[test]
+    fn bounded_generics_and_named_fields_parse_distinctly() {
+        let expr: PenumExpr = parse_quote!((T: Trait, U) | { name: T });
+
+        let unnamed: Vec<&PatFieldKind> = expr.pattern[0].group.iter().collect();
+        let named: Vec<&PatFieldKind> = expr.pattern[1].group.iter().collect();
+
+        assert!(matches!(
+            unnamed[0],
+            PatFieldKind::Bounded { field, .. } if field.ident.is_none() && field.ty.is_generic()
+        ));
+        assert!(matches!(unnamed[1], PatFieldKind::Field(field) if field.ident.is_none()));
+        assert!(matches!(
+            named[0],
+            PatFieldKind::Field(field) if field.ident.as_ref().unwrap() == "name"
+        ));
+
+        // Uppercase-only idents are generics, so they can't be used as field names.
+        assert!(syn::parse_str::<PenumExpr>("{ T: Trait }").is_err());
+        assert!(syn::parse_str::<PenumExpr>("{ name: T: Trait }").is_ok());
+
+        // Placeholders and impl expressions can't dispatch their inline bounds.
+        assert!(syn::parse_str::<PenumExpr>("(T: ^Trait)").is_ok());
+        assert!(syn::parse_str::<PenumExpr>("(i32: ^Trait)").is_ok());
+        assert!(syn::parse_str::<PenumExpr>("(_: ^Trait)").is_err());
+        assert!(syn::parse_str::<PenumExpr>("(impl Copy: ^Trait)").is_err());
+    }
+
+    #[test]
+    fn variadic_bounds_cover_the_rest_of_the_fields() {
+        let expr: PenumExpr = parse_quote!((String, ..rest: Copy, u8));
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1(String, i32, bool, u8),
+            }
+        );
+
+        assert_eq!("(String , .. rest : Copy , u8)", expr.pattern_to_string());
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let item = Comparable::from(&item.variants[0].fields);
+        let pairs = comparable_pats.compare(&item);
+        let (residual, fields) = pairs[0].get_residual().unwrap();
+
+        assert!(residual.get_bounds().is_some());
+        assert_eq!(2, fields.len());
+
+        // Ranges carry their bounds in the same way.
+        let expr: PenumExpr = parse_quote!((T, 1..3: Copy + Clone));
+        assert_eq!("(T , 1 .. 3 : Copy + Clone)", expr.pattern_to_string());
+
+        // The bounds of a variadic are only asserted, never dispatched.
+        assert!(syn::parse_str::<PenumExpr>("(T, ..: ^Trait)").is_err());
+        assert!(syn::parse_str::<PenumExpr>("(T, ..=2: ^Trait)").is_err());
+    }
+
+    #[test]
+    fn selectors_match_variant_names() {
+        let expr: PenumExpr = parse_quote!(Foo(T) | Net * (T) | *Error(T) | Io * Err * (T) | unit);
+        let names = |position: usize| -> Vec<bool> {
+            let selector = expr.pattern[position].get_selector().unwrap();
+            [
+                "Foo",
+                "Net",
+                "NetRead",
+                "IoError",
+                "ParseError",
+                "IoReadErr",
+                "Bar",
+            ]
+            .iter()
+            .map(|name| selector.matches(&quote::format_ident!("{name}")))
+            .collect()
+        };
+
+        assert_eq!(
+            vec![true, false, false, false, false, false, false],
+            names(0)
+        );
+        assert_eq!(
+            vec![false, true, true, false, false, false, false],
+            names(1)
+        );
+        assert_eq!(
+            vec![false, false, false, true, true, false, false],
+            names(2)
+        );
+        assert_eq!(
+            vec![false, false, false, true, false, true, false],
+            names(3)
+        );
+
+        // Lowercase names only mark unit variants.
+        assert!(expr.pattern[4].get_selector().is_none());
+        assert_eq!(
+            "Foo (T) | Net * (T) | * Error (T) | Io * Err * (T) | unit",
+            expr.pattern_to_string()
+        );
+    }
+
+    #[test]
+    fn optional_fields_can_be_left_out() {
+        let expr: PenumExpr = parse_quote!({ name: T, age?: usize });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1 { name: String },
+                V2 { age: usize, name: String },
+                V3 { age: usize },
+                V4 { name: String, age: usize, id: u8 },
+            }
+        );
+
+        assert_eq!("{ name : T , age ? : usize }", expr.pattern_to_string());
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let satisfied: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let item = Comparable::from(&variant.fields);
+                comparable_pats
+                    .compare(&item)
+                    .iter()
+                    .any(|pair| pair.is_structurally_satisfied())
+            })
+            .collect();
+
+        assert_eq!(vec![true, true, false, false], satisfied);
+
+        // Only named fields can be optional.
+        assert!(syn::parse_str::<PenumExpr>("(T, U?)").is_err());
+    }
+
+    #[test]
+    fn wildcards_pair_fields_by_type() {
+        let expr: PenumExpr = parse_quote!({ _: String, id: u8, .. });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1 { size: u64, id: u8, path: String },
+            }
+        );
+
+        assert_eq!("{ _ : String , id : u8 , .. }", expr.pattern_to_string());
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let item = Comparable::from(&item.variants[0].fields);
+        let pairs = comparable_pats.compare(&item);
+        let paired: Vec<String> = pairs[0]
+            .zip()
+            .map(|(_, _, field)| field.ident.as_ref().unwrap().to_string())
+            .collect();
+
+        assert_eq!(vec!["path", "id"], paired);
+        assert!(pairs[0].is_structurally_satisfied());
+
+        let (_, fields) = pairs[0].get_residual().unwrap();
+        assert_eq!(1, fields.len());
+    }
+
+    #[test]
+    fn named_ranges_count_the_fields_they_cover() {
+        let expr: PenumExpr = parse_quote!({ id: u64, meta?: u8, 1..=2: Copy });
+        let item: ItemEnum = parse_quote!(
+            enum Enum {
+                V1 { id: u64, a: u8 },
+                V2 { id: u64, meta: u8, a: u8, b: u8 },
+                V3 { id: u64, a: u8, b: u8, c: u8 },
+                V4 { id: u64, meta: u8 },
+            }
+        );
+
+        let comparable_pats = expr.get_comparable_patterns();
+        let matched: Vec<bool> = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let item = Comparable::from(&variant.fields);
+                !comparable_pats.compare(&item).is_empty()
+            })
+            .collect();
+
+        assert_eq!(vec![true, true, false, false], matched);
+    }
+
+    #[test]
+    fn fragments_can_have_their_own_clause() {
+        let expr: PenumExpr =
+            parse_quote!((T, U) where T: Copy | { name: T } where T: Clone | (V) where V: Trait);
+
+        assert!(expr.pattern[0].clause.is_some());
+        assert!(expr.pattern[1].clause.is_some());
+
+        // The last clause is shared by every fragment.
+        assert!(expr.pattern[2].clause.is_none());
+        assert!(expr.clause.is_some());
+        assert_eq!("(T , U) | { name : T } | (V)", expr.pattern_to_string());
+
+        // Only the shared clause can dispatch or bound associated types.
+        assert!(syn::parse_str::<PenumExpr>("(T) where T: ^Trait | (U)").is_err());
+        assert!(syn::parse_str::<PenumExpr>("(T) where T::Item: Copy | (U)").is_err());
+        assert!(syn::parse_str::<PenumExpr>("(T) | (U) where T: ^Trait").is_ok());
+    }
 }
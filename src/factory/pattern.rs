@@ -1,18 +1,31 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::iter::Flatten;
+use std::option;
+
 use syn::{
     punctuated::{Iter, Punctuated},
     spanned::Spanned,
-    token, ExprRange, Field, Ident, Token,
+    token,
+    visit::{self, Visit},
+    Field, Ident, LitInt, RangeLimits, Token, Type,
 };
 
+use proc_macro2::TokenStream;
 use quote::ToTokens;
 
 use crate::{
     dispatch::{Blueprint, BlueprintsMap},
     error::Diagnostic,
     polym::UniqueHashId,
+    utils::{TypeAliases, TypeUtils, DEFAULT_FRAGMENT_LIMIT},
 };
 
-use super::{ComparablePats, PredicateType, WhereClause, WherePredicate};
+use super::{
+    ComparablePats, CompositeKind, PredicateType, TraitBound, TypeParamBound, WhereClause,
+    WherePredicate,
+};
 
 mod boilerplate;
 mod parse;
@@ -21,6 +34,12 @@ mod to_tokens;
 // TODO: Replace `Punctuated` with custom sequence type
 pub type PunctuatedParameters = Punctuated<PatFieldKind, Token![,]>;
 
+/// Iterates over the parameters of a composite, see `PatComposite::iter`.
+pub type ParameterIter<'a> = Flatten<option::IntoIter<Iter<'a, PatFieldKind>>>;
+
+/// The bounds of a variadic or a range, e.g. `: Copy` in `(T, ..: Copy)` and `(T, 1..3: Copy)`.
+pub type ResidualBounds = (Token![:], Punctuated<TypeParamBound, Token![+]>);
+
 /// A Penum expression consists of one or more patterns, and an optional WhereClause.
 ///
 /// ```text
@@ -36,6 +55,49 @@ pub struct PenumExpr {
     /// Contains an optional where clause with one or more where
     /// predicates.
     pub clause: Option<WhereClause>,
+
+    /// Options that are placed in front of, or after, the pattern.
+    pub options: Vec<PenumOption>,
+}
+
+/// Options are comma separated and placed in front of the pattern, which can then be left out.
+/// They can be placed after the pattern as well.
+///
+/// ```text
+/// impl_display_from(render), (T) | { name: T }   where T: Clone, alias(Name = String)
+/// ^^^^^^^^^^^^^^^^^^^^^^^^^                                      ^^^^^^^^^^^^^^^^^^^^
+/// <PenumOption>                                                  <PenumOption>
+/// ```
+#[derive(Debug)]
+pub enum PenumOption {
+    /// Implements `Display` by forwarding to an inherent method that returns something
+    /// displayable, e.g. `fn render(&self) -> String`.
+    ImplDisplayFrom(Ident),
+
+    /// Adds `fn name_of_index(index: usize) -> Option<&'static str>` to a fieldless enum, which
+    /// returns the name of the variant at `index`.
+    NameOfIndex(Ident),
+
+    /// Dispatches through the deref target of each field, e.g. `str` for `String` fields.
+    ViaDeref(Ident),
+
+    /// Reports every pattern fragment that isn't matched by any variant.
+    Strict(Ident),
+
+    /// Requires every pattern generic to be bound to the same type in every variant.
+    Uniform(Ident),
+
+    /// Lets pattern generics named like a type or const parameter of the subject stand for that
+    /// parameter, e.g. `T` in `(T) | (Vec<T>)` given `enum E<T>`.
+    SubjectGenerics(Ident),
+
+    /// Type aliases that are treated as the type they stand for, e.g. `Bytes = Vec<u8>` in
+    /// `alias(Bytes = Vec<u8>, Id = u64)`.
+    Alias(Ident, Vec<(Type, Type)>),
+
+    /// Raises or lowers the number of fragments an expression can have before a warning is
+    /// emitted, e.g. `fragment_limit(128)`.
+    FragmentLimit(Ident, usize),
 }
 
 /// Pattern fragments are used as constituents for the Penum expression composite type.
@@ -44,17 +106,62 @@ pub struct PenumExpr {
 /// ```text
 ///  Variant    () | (T, T) | { name: T }
 ///  ^^^^^^^    ^^   ^^^^^^   ^^^^^^^^^^^
-///  <Ident>    <Composite>
+///  <Selector> <Composite>
 /// ```
 #[derive(Debug)]
 pub struct PatFrag {
-    /// An optional identifier that is currently only used to mark
-    /// nullary variants.
-    pub ident: Option<Ident>,
+    /// An optional name that limits the fragment to the variants it matches, e.g.
+    /// `Foo(T, ..) | Net*(T) | *Error { .. }`. A lowercase name is only used to mark nullary
+    /// variants, e.g. `unit`.
+    pub selector: Option<VariantSelector>,
 
     /// A group is a composite of zero or more PatComposite surrounded
     /// by a delimiter
     pub group: PatComposite,
+
+    /// An optional where clause that is only asserted for the variants this fragment matches,
+    /// e.g. `where T: Copy` in `(T) where T: Copy | { name: U }`.
+    pub clause: Option<WhereClause>,
+
+    /// A `!` in front of the fragment, e.g. `!(String, ..)`, rejects the variants it matches
+    /// instead of accepting them.
+    pub negation: Option<Token![!]>,
+
+    /// An optional range that the discriminant of a matched variant has to be in, e.g.
+    /// `V = 0..=255` or `* = _ as u8`.
+    pub discriminant: Option<DiscriminantRange>,
+}
+
+/// The values that the discriminant of a variant is limited to. Both bounds are inclusive, so
+/// `0..256` and `0..=255` are the same range, a single value like `3` is a range of one, and
+/// `_ as u8` is every value of `u8`.
+///
+/// ```text
+/// A = 0..=255 | * = _ as u8
+///   ^^^^^^^^^     ^^^^^^^^^
+/// ```
+#[derive(Debug)]
+pub struct DiscriminantRange {
+    pub eq_token: Token![=],
+    pub tokens: TokenStream,
+    pub min: i128,
+    pub max: i128,
+}
+
+/// The name of the variants that a fragment is limited to, made out of names and `*` wildcards
+/// that match any part of a variant name.
+///
+/// ```text
+/// Foo(T) | Net*(T) | *Error(String, ..) | Io*Error { .. }
+/// ^^^      ^^^^      ^^^^^^                ^^^^^^^^
+/// ```
+#[derive(Debug)]
+pub struct VariantSelector(pub Vec<SelectorPart>);
+
+#[derive(Debug)]
+pub enum SelectorPart {
+    Name(Ident),
+    Wildcard(Token![*]),
 }
 
 /// A composite can come in 3 flavors:
@@ -81,8 +188,39 @@ pub enum PatComposite {
     /// Represents a `Unit`-like pattern
     Unit,
 
-    /// Represents a `Inferred` pattern
-    Inferred,
+    /// Represents a `Inferred` pattern, with an optional arity constraint.
+    ///
+    /// ```text
+    /// _ | _(3) | _{3}
+    ///     ^^^^   ^^^^
+    ///     <InferredArity>
+    /// ```
+    Inferred(Option<InferredArity>),
+}
+
+/// The number of fields a bounded variadic can take. Both bounds are inclusive, so `..2` and
+/// `..=2` both take at most two fields, `1..3` takes between one and three fields and `2..`
+/// takes at least two fields.
+///
+/// Like a variadic, a range can carry bounds that are asserted for every field that it covers,
+/// e.g. `(T, 1..3: Copy)`.
+#[derive(Debug)]
+pub struct FieldRange {
+    pub from: Option<LitInt>,
+    pub limits: RangeLimits,
+    pub to: Option<LitInt>,
+    pub min: usize,
+    pub max: Option<usize>,
+    pub bounds: Option<ResidualBounds>,
+}
+
+/// Constrains an inferred pattern to a specific composite kind and arity, e.g. `_(3)` only
+/// matches tuple variants with three fields and `_{3}` only struct variants with three fields.
+#[derive(Debug)]
+pub struct InferredArity {
+    pub kind: CompositeKind,
+    pub arity: LitInt,
+    pub value: usize,
 }
 
 /// A parameter comes in different flavors:
@@ -93,9 +231,10 @@ pub enum PatComposite {
 /// <Field>           <Field>     <Variadic>
 /// ```
 ///
-/// Given that the `Regular(Field)` can also either be `named` or
-/// `unnamed`, it's possible to use a `PatParamKind::Regular->Named`
-/// field inside a `GroupKind::Unnamed-Parameters` composite type.
+/// Fields inside a `Named` composite are always `named`, while a `ident: ..` inside an
+/// `Unnamed` composite is read as a type with inline bounds, e.g. `(i32: Trait, ..)`.
+/// Uppercase-only idents are generics, so `{ T: Trait }` is rejected instead of being read as
+/// a field named `T`.
 #[derive(Debug)]
 pub enum PatFieldKind {
     /// Used to indicate that this field will be inferred
@@ -108,28 +247,188 @@ pub enum PatFieldKind {
     /// optional.
     Field(Field),
 
+    /// A field with inline bounds that are asserted for the matched type.
+    ///
+    /// ```text
+    /// (i32: Trait, ..) | { name: T: Trait }
+    ///  ^^^^^^^^^^         ^^^^^^^^^^^^^^^^
+    /// ```
+    Bounded {
+        field: Field,
+        colon_token: Token![:],
+        bounds: Punctuated<TypeParamBound, Token![+]>,
+    },
+
+    /// A field that matches any type except the given one.
+    ///
+    /// ```text
+    /// (!String, ..) | { name: !String }
+    ///  ^^^^^^^            ^^^^^^^^^^^^^
+    /// ```
+    ///
+    /// NOTE: Only concrete types can be negated.
+    Negated { field: Field, not_token: Token![!] },
+
+    /// A named field that matches an item field of its type, whatever its name.
+    ///
+    /// ```text
+    /// { _: T, .. }
+    ///   ^^^^
+    /// ```
+    Wildcard {
+        underscore_token: Token![_],
+        field: Field,
+    },
+
+    /// A named field that a variant may leave out.
+    ///
+    /// ```text
+    /// { name: T, age?: usize }
+    ///            ^^^^^^^^^^^
+    /// ```
+    Optional {
+        field: Field,
+        question_token: Token![?],
+    },
+
+    /// A generic with a default type, which is used for placeholder fields and for the clauses
+    /// of variants that the generic isn't bound in.
+    ///
+    /// ```text
+    /// (T = i32, U) | { name: T = String }
+    ///  ^^^^^^^         ^^^^^^^^^^^^^^^^^
+    /// ```
+    Defaulted {
+        field: Field,
+        eq_token: Token![=],
+        default: Box<Type>,
+    },
+
     /// We use this to represent that we don't care amount the left over
     /// arguments.
     ///
     /// The use for variadic fields are currently only supported in the
     /// last argument position.
-    Variadic(Token![..]),
+    ///
+    /// A variadic can be named, e.g. `(T, ..rest)`. The name is kept so
+    /// that it's printed in diagnostics, but the fields it covers are still
+    /// discarded in dispatch arms given that a tuple pattern can't bind
+    /// `..` to a name.
+    ///
+    /// A variadic can also carry bounds, e.g. `(String, ..rest: Copy)`, which are asserted for
+    /// every field that it covers.
+    Variadic(Token![..], Option<Ident>, Option<ResidualBounds>),
 
-    /// Use `Variadic(Token![..])` instead.
+    /// A bounded variadic, that is, a variadic that can take a limited number of fields.
     ///
     /// Supported `>` Not supported
     /// ```text
-    /// (T, ..) > (T, ..10) (T, ...) (T, ..Copy) (T, Copy..2)
-    ///     ^^        ^^^^      ^^^      ^^^^^^      ^^^^^^^
-    ///
-    /// Variadic(Token![..]) > Range(ExprRange)
+    /// (T, ..2) (..=10) (T, 1..3) (2..) > (T, ...) (T, Copy..2)
+    ///     ^^^   ^^^^^      ^^^^   ^^^        ^^^      ^^^^^^^
     /// ```
-    Range(ExprRange),
+    Range(FieldRange),
 
     /// Suppose to be used for derived Default
     Nothing,
 }
 
+impl PatFrag {
+    /// Returns the selector that this fragment is limited to, e.g. `Foo` in `Foo(T, ..)`.
+    /// Variants are capitalized, so `unit` isn't a selector.
+    pub fn get_selector(&self) -> Option<&VariantSelector> {
+        self.selector
+            .as_ref()
+            .filter(|selector| !selector.is_marker())
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negation.is_some()
+    }
+
+    /// Returns every generic that the fragment uses, including nested ones, e.g. `T` and `U` for
+    /// `(T, Vec<U>)`.
+    pub fn get_generic_idents(&self) -> BTreeSet<Ident> {
+        struct Generics(BTreeSet<Ident>);
+
+        impl<'ast> Visit<'ast> for Generics {
+            fn visit_type(&mut self, node: &'ast Type) {
+                if node.is_generic() {
+                    self.0.insert(node.get_generic_ident());
+                }
+                visit::visit_type(self, node);
+            }
+        }
+
+        let mut generics = Generics(BTreeSet::new());
+
+        self.group
+            .iter()
+            .filter_map(|param| param.get_field())
+            .for_each(|field| generics.visit_type(&field.ty));
+
+        generics.0
+    }
+}
+
+impl DiscriminantRange {
+    pub fn contains(&self, value: i128) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+impl VariantSelector {
+    /// Used to check if this is a lowercase name without wildcards, e.g. `unit`.
+    pub fn is_marker(&self) -> bool {
+        matches!(
+            self.0.as_slice(),
+            [SelectorPart::Name(ident)] if !ident.to_string().starts_with(char::is_uppercase)
+        )
+    }
+
+    /// Used to check if a variant name is matched, e.g. `NetRead` by `Net*`. Wildcards can
+    /// match an empty part of the name as well, so `Net*` also matches `Net`.
+    pub fn matches(&self, ident: &Ident) -> bool {
+        let selector = self.to_string();
+        let name = ident.to_string();
+        let pieces: Vec<&str> = selector.split('*').collect();
+
+        let [first, middle @ .., last] = pieces.as_slice() else {
+            return name == selector;
+        };
+
+        if name.len() < first.len() + last.len()
+            || !name.starts_with(first)
+            || !name.ends_with(last)
+        {
+            return false;
+        }
+
+        let mut rest = &name[first.len()..name.len() - last.len()];
+
+        for piece in middle {
+            match rest.find(piece) {
+                Some(position) => rest = &rest[position + piece.len()..],
+                None => return false,
+            }
+        }
+
+        true
+    }
+}
+
+impl fmt::Display for VariantSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in self.0.iter() {
+            match part {
+                SelectorPart::Name(ident) => write!(f, "{ident}")?,
+                SelectorPart::Wildcard(_) => f.write_str("*")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl PenumExpr {
     pub fn pattern_to_string(&self) -> String {
         self.pattern
@@ -147,6 +446,26 @@ impl PenumExpr {
         self.into()
     }
 
+    /// Returns every generic that the fragments use, including nested ones, e.g. `T` and `U` for
+    /// `(T, Vec<U>) | (T)`.
+    pub fn get_generic_idents(&self) -> BTreeSet<Ident> {
+        self.pattern
+            .iter()
+            .flat_map(PatFrag::get_generic_idents)
+            .collect()
+    }
+
+    /// Returns the default type of every generic that has one, e.g. `T => i32` for
+    /// `(T = i32, U) | (T)`.
+    pub fn get_generic_defaults(&self) -> BTreeMap<Ident, &Type> {
+        self.pattern
+            .iter()
+            .flat_map(|frag| frag.group.iter())
+            .filter_map(|param| Some((param.get_field()?, param.get_default()?)))
+            .map(|(field, default)| (field.ty.get_generic_ident(), default))
+            .collect()
+    }
+
     pub fn has_predicates(&self) -> bool {
         matches!(&self.clause, Some(wc) if !wc.predicates.is_empty())
     }
@@ -155,6 +474,75 @@ impl PenumExpr {
         self.clause.is_some()
     }
 
+    pub fn has_via_deref(&self) -> bool {
+        self.options
+            .iter()
+            .any(|option| matches!(option, PenumOption::ViaDeref(_)))
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.options
+            .iter()
+            .any(|option| matches!(option, PenumOption::Strict(_)))
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        self.options
+            .iter()
+            .any(|option| matches!(option, PenumOption::Uniform(_)))
+    }
+
+    pub fn binds_subject_generics(&self) -> bool {
+        self.options
+            .iter()
+            .any(|option| matches!(option, PenumOption::SubjectGenerics(_)))
+    }
+
+    /// Returns the number of fragments the expression can have before a warning is emitted.
+    pub fn get_fragment_limit(&self) -> usize {
+        self.options
+            .iter()
+            .find_map(|option| match option {
+                PenumOption::FragmentLimit(_, limit) => Some(*limit),
+                _ => None,
+            })
+            .unwrap_or(DEFAULT_FRAGMENT_LIMIT)
+    }
+
+    /// Returns every type alias together with the type it stands for, e.g. `Bytes => Vec<u8>`.
+    pub fn get_type_aliases(&self) -> Vec<(Type, Type)> {
+        self.options
+            .iter()
+            .filter_map(|option| match option {
+                PenumOption::Alias(_, aliases) => Some(aliases.iter().cloned()),
+                _ => None,
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Replaces every type alias within the pattern and its clauses with the type it stands
+    /// for, e.g. `Bytes` with `Vec<u8>` given `alias(Bytes = Vec<u8>)`.
+    pub fn resolve_type_aliases(&mut self, aliases: &TypeAliases) {
+        for frag in self.pattern.iter_mut() {
+            if let PatComposite::Named { parameters, .. }
+            | PatComposite::Unnamed { parameters, .. } = &mut frag.group
+            {
+                for param in parameters.iter_mut() {
+                    param.resolve_type_aliases(aliases);
+                }
+            }
+
+            if let Some(clause) = frag.clause.as_mut() {
+                clause.resolve_type_aliases(aliases);
+            }
+        }
+
+        if let Some(clause) = self.clause.as_mut() {
+            clause.resolve_type_aliases(aliases);
+        }
+    }
+
     /// This should probably be refactored...
     ///
     /// NOTE: This totally works when we are using Generics with patterns. But if we use
@@ -169,40 +557,73 @@ impl PenumExpr {
     /// SOLUTION: We could keep this as it is, and instead fold our blueprints map so that types with the
     /// same trait bounds are combined.
     pub fn get_blueprints_map(&self, error: &Diagnostic) -> Option<BlueprintsMap> {
-        let Some(clause) = self.clause.as_ref() else {
-            return None;
-        };
-
         let mut polymap = BlueprintsMap::default();
 
-        for pred in clause.predicates.iter() {
-            if let WherePredicate::Type(pred_ty) = pred {
-                let mut blueprints = Vec::<Blueprint>::default();
-
-                for param_bound in pred_ty.bounds.iter() {
-                    // Only get trait bound with `^` caret. e.g Type: ^Trait
-                    if let Some(trait_bound) = param_bound.get_dispatchable_trait_bound() {
-                        // This will try to first check if the trait exists in our
-                        // std trait store, and if it's not found, we'll check our
-                        // SHM map.
-                        match Blueprint::try_from(trait_bound) {
-                            Ok(blueprint) => blueprints.push(blueprint),
-                            Err(err) => error.extend(trait_bound.span(), err),
+        let clause_bounds = self
+            .clause
+            .iter()
+            .flat_map(|clause| clause.predicates.iter())
+            .filter_map(|pred| match pred {
+                WherePredicate::Type(pred_ty) => {
+                    Some((&pred_ty.bounded_ty, &pred_ty.bounds, false))
+                }
+                _ => None,
+            });
+
+        // Inline bounds of our pattern generics and concrete types, e.g. `(T: ^Trait)` or
+        // `(i32: ^Trait, ..)`, are dispatched just like the ones in our where clause.
+        let inline_bounds = self
+            .pattern
+            .iter()
+            .filter(|frag| !frag.is_negated())
+            .flat_map(|frag| frag.group.iter())
+            .filter_map(|param| Some((&param.get_field()?.ty, param.get_bounds()?, true)))
+            .filter(|(ty, ..)| ty.is_dispatchable_inline());
+
+        // A type can be bounded inline in more than one fragment, e.g. `(T: ^Trait) | (_, T: ^Trait)`,
+        // but should only be dispatched once per trait.
+        let mut inlined = Vec::<(UniqueHashId<Type>, &TraitBound)>::new();
+        let mut position = 0;
+
+        for (bounded_ty, bounds, is_inline) in clause_bounds.chain(inline_bounds) {
+            let mut blueprints = Vec::<Blueprint>::default();
+            let ty = bounded_ty.get_unique_id();
+
+            for param_bound in bounds.iter() {
+                // Only get trait bound with `^` caret. e.g Type: ^Trait
+                if let Some(trait_bound) = param_bound.get_dispatchable_trait_bound() {
+                    if is_inline {
+                        if inlined.contains(&(ty.clone(), trait_bound)) {
+                            continue;
                         }
+
+                        inlined.push((ty.clone(), trait_bound));
                     }
-                }
 
-                if blueprints.is_empty() {
-                    return None;
+                    // This will try to first check if the trait exists in our
+                    // std trait store, and if it's not found, we'll check our
+                    // SHM map.
+                    match Blueprint::try_from(trait_bound) {
+                        Ok(mut blueprint) => {
+                            blueprint.position = position;
+                            position += 1;
+                            blueprints.push(blueprint);
+                        }
+                        Err(err) => error.extend(trait_bound.span(), err),
+                    }
                 }
+            }
 
-                let ty = UniqueHashId(pred_ty.bounded_ty.clone());
+            // Predicates without dispatchable bounds, e.g. `T: Display`, can sit next to
+            // the ones we dispatch.
+            if blueprints.is_empty() {
+                continue;
+            }
 
-                if let Some(entry) = polymap.get_mut(&ty) {
-                    entry.append(&mut blueprints);
-                } else {
-                    polymap.insert(ty, blueprints);
-                }
+            if let Some(entry) = polymap.get_mut(&ty) {
+                entry.append(&mut blueprints);
+            } else {
+                polymap.insert(ty, blueprints);
             }
         }
         (!polymap.is_empty()).then_some(polymap)
@@ -232,13 +653,21 @@ impl PatFieldKind {
     /// This is useful when we just want to check if we should care
     /// about checking the inner structure of PatParamKind.
     pub fn is_field(&self) -> bool {
-        matches!(self, PatFieldKind::Field(_))
+        matches!(
+            self,
+            PatFieldKind::Field(_)
+                | PatFieldKind::Bounded { .. }
+                | PatFieldKind::Negated { .. }
+                | PatFieldKind::Optional { .. }
+                | PatFieldKind::Wildcard { .. }
+                | PatFieldKind::Defaulted { .. }
+        )
     }
 
     /// Used in ComparablePair method calls to check if a parameter is
     /// variadic
     pub fn is_variadic(&self) -> bool {
-        matches!(self, PatFieldKind::Variadic(_))
+        matches!(self, PatFieldKind::Variadic(..))
     }
 
     /// We currently don't use this one
@@ -246,6 +675,19 @@ impl PatFieldKind {
         matches!(self, PatFieldKind::Range(_))
     }
 
+    /// Used to check if the field is either an unbounded `..` or a bounded `..N` variadic.
+    pub fn is_residual(&self) -> bool {
+        self.is_variadic() || self.is_range()
+    }
+
+    /// Returns the bounds of a range, e.g. `(1, Some(3))` for `1..3`.
+    pub fn get_range_bounds(&self) -> Option<(usize, Option<usize>)> {
+        match self {
+            PatFieldKind::Range(range) => Some((range.min, range.max)),
+            _ => None,
+        }
+    }
+
     /// Used to quickly check if PatFieldKind is `Infer`
     pub fn is_infer(&self) -> bool {
         matches!(self, PatFieldKind::Infer)
@@ -257,6 +699,79 @@ impl PatFieldKind {
     pub fn get_field(&self) -> Option<&Field> {
         match self {
             PatFieldKind::Field(field) => Some(field),
+            PatFieldKind::Bounded { field, .. } => Some(field),
+            PatFieldKind::Negated { field, .. } => Some(field),
+            PatFieldKind::Optional { field, .. } => Some(field),
+            PatFieldKind::Wildcard { field, .. } => Some(field),
+            PatFieldKind::Defaulted { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+
+    /// Returns the default type of a generic, e.g. `i32` in `T = i32`.
+    pub fn get_default(&self) -> Option<&Type> {
+        match self {
+            PatFieldKind::Defaulted { default, .. } => Some(default.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Used to check if a named field can be left out by a variant, e.g. `age?: usize`.
+    pub fn is_optional(&self) -> bool {
+        matches!(self, PatFieldKind::Optional { .. })
+    }
+
+    /// Used to check if a named field matches an item field of any name, e.g. `_: T`.
+    pub fn is_wildcard(&self) -> bool {
+        matches!(self, PatFieldKind::Wildcard { .. })
+    }
+
+    /// Used to check if the field matches every type except its own, e.g. `!String`.
+    pub fn is_negated(&self) -> bool {
+        matches!(self, PatFieldKind::Negated { .. })
+    }
+
+    fn resolve_type_aliases(&mut self, aliases: &TypeAliases) {
+        let bounds = match self {
+            PatFieldKind::Field(field)
+            | PatFieldKind::Negated { field, .. }
+            | PatFieldKind::Optional { field, .. }
+            | PatFieldKind::Wildcard { field, .. } => {
+                aliases.resolve(&mut field.ty);
+                None
+            }
+            PatFieldKind::Bounded { field, bounds, .. } => {
+                aliases.resolve(&mut field.ty);
+                Some(bounds)
+            }
+            PatFieldKind::Defaulted { field, default, .. } => {
+                aliases.resolve(&mut field.ty);
+                aliases.resolve(default);
+                None
+            }
+            PatFieldKind::Variadic(_, _, Some((_, bounds)))
+            | PatFieldKind::Range(FieldRange {
+                bounds: Some((_, bounds)),
+                ..
+            }) => Some(bounds),
+            _ => None,
+        };
+
+        for bound in bounds.into_iter().flatten() {
+            bound.resolve_type_aliases(aliases);
+        }
+    }
+
+    /// Returns the inline bounds of a field, a variadic or a range, e.g. `Trait` in
+    /// `(i32: Trait)`, `(..: Trait)` and `(1..3: Trait)`.
+    pub fn get_bounds(&self) -> Option<&Punctuated<TypeParamBound, Token![+]>> {
+        match self {
+            PatFieldKind::Bounded { bounds, .. } => Some(bounds),
+            PatFieldKind::Variadic(_, _, Some((_, bounds))) => Some(bounds),
+            PatFieldKind::Range(FieldRange {
+                bounds: Some((_, bounds)),
+                ..
+            }) => Some(bounds),
             _ => None,
         }
     }
@@ -267,39 +782,58 @@ impl PatComposite {
         match self {
             PatComposite::Named { parameters, .. } => parameters.len(),
             PatComposite::Unnamed { parameters, .. } => parameters.len(),
+            PatComposite::Inferred(Some(constraint)) => constraint.value,
             _ => 0,
         }
     }
 
-    pub fn iter(&self) -> Iter<'_, PatFieldKind> {
-        thread_local! {static EMPTY_SLICE_ITER: Punctuated<PatFieldKind, ()> = Punctuated::new();}
-
+    /// Iterates over the parameters of the composite. Unit and inferred composites have none,
+    /// e.g. when a unit variant is paired with `unit` or `_`.
+    pub fn iter(&self) -> ParameterIter<'_> {
         match self {
-            PatComposite::Named { parameters, .. } => parameters.iter(),
-            PatComposite::Unnamed { parameters, .. } => parameters.iter(),
-            _ => EMPTY_SLICE_ITER.with(|f| {
-                // "SAFETY": This is not recommended. The thing is that we
-                //           are transmuting an empty iter that is created
-                //           from a static Punctuated struct. The lifetime
-                //           is invariant in Iter<'_> which means that we
-                //           are not allowed to return another lifetime,
-                //           even if it outlives 'a. But, in this case it
-                //           should be "okay" given that it's static and empty.
-                //           Though, I'm not 100% sure if this actually can
-                //           cause UB because of the Punctuated Adding to this, it's not currently
-                //           possible to get here right now because of the
-                //           if statement in
-                //           `penum::assemble->is_unit()->continue`. So this
-                //           could also be marked as `unreachable`.
-                unsafe { std::mem::transmute(f.iter()) }
-            }),
+            PatComposite::Named { parameters, .. } | PatComposite::Unnamed { parameters, .. } => {
+                Some(parameters.iter())
+            }
+            _ => None,
         }
+        .into_iter()
+        .flatten()
     }
 
     pub fn is_unit(&self) -> bool {
         matches!(self, PatComposite::Unit)
     }
 
+    /// Used to check if this is a named or unnamed pattern, i.e. one with parameters.
+    pub fn is_compound(&self) -> bool {
+        matches!(
+            self,
+            PatComposite::Named { .. } | PatComposite::Unnamed { .. }
+        )
+    }
+
+    /// Returns the kind of variant this composite can match. An unconstrained `Inferred`
+    /// returns `None` given that it can match any kind.
+    pub fn get_kind(&self) -> Option<CompositeKind> {
+        match self {
+            PatComposite::Named { .. } => Some(CompositeKind::Named),
+            PatComposite::Unnamed { .. } => Some(CompositeKind::Unnamed),
+            PatComposite::Unit => Some(CompositeKind::Unit),
+            PatComposite::Inferred(constraint) => constraint.as_ref().map(|c| c.kind),
+        }
+    }
+
+    /// Used to check if this is an inferred pattern with an arity constraint, e.g. `_(3)`.
+    pub fn has_arity_constraint(&self) -> bool {
+        matches!(self, PatComposite::Inferred(Some(_)))
+    }
+
+    /// Used to check if this is a bare `_`, which catches any variant that the other patterns
+    /// reject.
+    pub fn is_catch_all(&self) -> bool {
+        matches!(self, PatComposite::Inferred(None))
+    }
+
     pub fn has_variadic(&self) -> bool {
         match self {
             PatComposite::Named { parameters, .. } => parameters.iter().any(|fk| fk.is_variadic()),
@@ -310,20 +844,29 @@ impl PatComposite {
         }
     }
 
+    /// Returns the position of either an unbounded `..` or a bounded `..N` variadic.
     pub fn get_variadic_position(&self) -> Option<usize> {
         match self {
             PatComposite::Named { parameters, .. } => parameters
                 .iter()
                 .enumerate()
-                .find_map(|(pos, fk)| fk.is_variadic().then_some(pos)),
+                .find_map(|(pos, fk)| fk.is_residual().then_some(pos)),
             PatComposite::Unnamed { parameters, .. } => parameters
                 .iter()
                 .enumerate()
-                .find_map(|(pos, fk)| fk.is_variadic().then_some(pos)),
+                .find_map(|(pos, fk)| fk.is_residual().then_some(pos)),
             _ => None,
         }
     }
 
+    /// Returns the minimum and maximum number of fields a variadic can take, e.g. `(0, Some(2))`
+    /// for `(T, ..2)`. An unbounded `..` returns `(0, None)`.
+    pub fn get_residual_bounds(&self) -> (usize, Option<usize>) {
+        self.iter()
+            .find_map(PatFieldKind::get_range_bounds)
+            .unwrap_or((0, None))
+    }
+
     pub fn has_last_variadic(&self) -> bool {
         match self {
             PatComposite::Named { parameters, .. } => {
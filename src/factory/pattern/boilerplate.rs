@@ -1,10 +1,10 @@
 use syn::{
     parse_quote,
-    punctuated::{IntoIter, Iter, Punctuated},
+    punctuated::{IntoIter, Punctuated},
     Fields, FieldsNamed, FieldsUnnamed,
 };
 
-use crate::factory::{PatComposite, PatFieldKind};
+use crate::factory::{ParameterIter, PatComposite, PatFieldKind};
 
 impl From<&Fields> for PatComposite {
     fn from(value: &Fields) -> Self {
@@ -40,7 +40,7 @@ impl IntoIterator for PatComposite {
 
 impl<'a> IntoIterator for &'a PatComposite {
     type Item = &'a PatFieldKind;
-    type IntoIter = Iter<'a, PatFieldKind>;
+    type IntoIter = ParameterIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
@@ -1,13 +1,32 @@
-use proc_macro2::TokenStream;
+use std::collections::BTreeMap;
+
+use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
 use syn::{
     braced, parenthesized,
+    parse::Parser,
     parse::{Parse, ParseStream},
-    token, Field, Ident, LitInt, LitStr, Token, Type,
+    parse_quote,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    token, Field, Ident, Lifetime, LitInt, LitStr, RangeLimits, Token, Type,
 };
 
-use crate::factory::{TraitBound, WhereClause};
+use crate::{
+    dispatch::{P_SHM, S_SHM},
+    factory::{
+        CompositeKind, PredicateType, TraitBound, TypeParamBound, WhereClause, WherePredicate,
+    },
+    utils::{
+        find_generics, generic_declared_twice, generic_never_used, generic_not_declared,
+        get_projection, pattern_not_registered, peek_trailing_option, shape_not_registered,
+        TypeUtils, PENUM_OPTIONS,
+    },
+};
 
-use super::{PatComposite, PatFieldKind, PatFrag, PenumExpr};
+use super::{
+    DiscriminantRange, FieldRange, InferredArity, PatComposite, PatFieldKind, PatFrag, PenumExpr,
+    PenumOption, ResidualBounds, SelectorPart, VariantSelector,
+};
 
 struct ImplExpr {
     impl_token: token::Impl,
@@ -46,30 +65,52 @@ impl Parse for ImplExpr {
 
                 tys
             } else {
-                vec![input.parse()?]
+                // e.g. `impl Trait for String, ::std::path::PathBuf`. A comma followed by another
+                // `impl` starts the next expression instead.
+                let mut tys = vec![input.call(Type::without_plus)?];
+
+                while input.peek(token::Comma)
+                    && !input.peek2(token::Impl)
+                    && !peek_trailing_option(input)
+                {
+                    let _: token::Comma = input.parse()?;
+                    tys.push(input.call(Type::without_plus)?);
+                }
+
+                tys
             },
         })
     }
 }
 
-impl ImplExpr {
-    fn into_clause(self) -> WhereClause {
-        let Self {
-            trait_bound, tys, ..
-        } = self;
+/// One or more impl expressions separated by `,` or `+`, e.g.
+/// `impl Abc for String, impl Abc for i32` or `impl Abc for String + impl Def for i32`.
+struct ImplExprs(Vec<ImplExpr>);
 
-        let mut bounds = TokenStream::new();
-
-        for (index, ty) in tys.iter().enumerate() {
-            // Always dispatch for impl expressions
-            bounds.extend(quote::quote!(#ty: ^#trait_bound));
+impl Parse for ImplExprs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut exprs = vec![input.parse::<ImplExpr>()?];
 
-            if index != tys.len() - 1 {
-                bounds.extend(quote::quote!(,));
-            }
+        while (input.peek(token::Comma) || input.peek(token::Add)) && input.peek2(token::Impl) {
+            let _: TokenTree = input.parse()?;
+            exprs.push(input.parse()?);
         }
 
-        syn::parse_quote!(where #bounds)
+        Ok(Self(exprs))
+    }
+}
+
+impl ImplExprs {
+    fn into_clause(self) -> WhereClause {
+        // Always dispatch for impl expressions
+        let predicates = self.0.iter().flat_map(|expr| {
+            let trait_bound = &expr.trait_bound;
+            expr.tys
+                .iter()
+                .map(move |ty| quote::quote!(#ty: ^#trait_bound))
+        });
+
+        syn::parse_quote!(where #(#predicates),*)
     }
 }
 
@@ -77,31 +118,86 @@ impl Parse for PenumExpr {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         if input.peek(LitStr) {
             let pat: LitStr = input.parse()?;
-            let penum: PenumExpr = pat.parse_with(PenumExpr::parse)?;
-            return Ok(penum);
+            return parse_lit_str(&pat);
         }
 
-        if input.peek(token::Where) || input.peek(token::For) || input.peek(token::Impl) {
-            if ImplExpr::parse(&input.fork()).is_ok() {
-                return Ok(Self {
-                    pattern: vec![PatFrag {
-                        ident: None,
-                        group: PatComposite::Inferred,
-                    }],
-                    clause: Some(input.parse::<ImplExpr>()?.into_clause()),
+        let mut options = input.call(parse_options)?;
+
+        if input.is_empty() && !options.is_empty() {
+            return Ok(Self {
+                pattern: vec![PatFrag {
+                    selector: None,
+                    group: PatComposite::Inferred(None),
+                    clause: None,
+                    negation: None,
+                    discriminant: None,
+                }],
+                clause: None,
+                options,
+            });
+        }
+
+        if peek_declared_generics(input) {
+            let declared = input.call(parse_declared_generics)?;
+            let mut expr: PenumExpr = input.parse()?;
+
+            check_declared_generics(&expr, &declared)?;
+
+            options.append(&mut expr.options);
+            expr.options = options;
+
+            let predicates: Punctuated<WherePredicate, Token![,]> = declared
+                .into_iter()
+                .filter_map(|(generic, bounds)| {
+                    Some(WherePredicate::Type(PredicateType {
+                        lifetimes: None,
+                        bounded_ty: syn::parse_quote!(#generic),
+                        colon_token: Default::default(),
+                        bounds: bounds?,
+                    }))
+                })
+                .collect();
+
+            if !predicates.is_empty() {
+                expr.merge_clause(WhereClause {
+                    where_token: Default::default(),
+                    predicates,
                 });
             }
 
+            return Ok(expr);
+        }
+
+        if input.peek(Token![use]) {
+            return parse_registered_pattern(input, options);
+        }
+
+        if input.peek(token::Where) || input.peek(token::For) || input.peek(token::Impl) {
+            let clause = if ImplExprs::parse(&input.fork()).is_ok() {
+                input.parse::<ImplExprs>()?.into_clause()
+            } else {
+                input.parse()?
+            };
+
+            options.extend(input.call(parse_trailing_options)?);
+
             return Ok(Self {
                 pattern: vec![PatFrag {
-                    ident: None,
-                    group: PatComposite::Inferred,
+                    selector: None,
+                    group: PatComposite::Inferred(None),
+                    clause: None,
+                    negation: None,
+                    discriminant: None,
                 }],
-                clause: Some(input.parse()?),
+                clause: Some(clause),
+                options,
             });
         }
 
-        if input.peek(Ident) && input.peek2(token::Eq) {
+        let fork = input.fork();
+        let _: Option<Ident> = fork.parse().ok();
+
+        if input.peek(Ident) && input.peek2(token::Eq) && !peek_discriminant_range(&fork) {
             let _: Ident = input.parse()?;
             let _: token::Eq = input.parse()?;
 
@@ -110,23 +206,152 @@ impl Parse for PenumExpr {
             }
         }
 
-        Ok(Self {
-            pattern: input.call(parse_pattern)?,
-            clause: {
-                if input.peek(Token![where]) {
-                    Some(input.parse()?)
-                } else {
-                    None
+        let mut expr = input.call(parse_pattern)?;
+        options.append(&mut expr.options);
+        expr.options = options;
+
+        if input.peek(Token![where]) {
+            expr.merge_clause(input.parse()?);
+        }
+
+        expr.options.extend(input.call(parse_trailing_options)?);
+
+        Ok(expr)
+    }
+}
+
+impl PenumExpr {
+    /// Adds the fragments, clause and options of a registered shape to this expression. The
+    /// predicates of the shape are scoped to the fragments of the shape that use their generic,
+    /// just like a fragment clause, so that they aren't asserted for the fragments next to it.
+    fn compose(&mut self, shape: PenumExpr) {
+        let PenumExpr {
+            mut pattern,
+            clause,
+            options,
+        } = shape;
+
+        self.options.extend(options);
+
+        if let Some(mut clause) = clause {
+            for predicate in std::mem::take(&mut clause.predicates) {
+                let mut scoped = pattern
+                    .iter_mut()
+                    .filter(|frag| is_scoped_to(&predicate, frag))
+                    .peekable();
+
+                // Dispatching and projections are resolved for the whole enum, and a generic
+                // that no fragment uses is reported later on, so those stay in the shared clause.
+                if scoped.peek().is_none() {
+                    clause.predicates.push(predicate);
+                    continue;
                 }
-            },
+
+                for frag in scoped {
+                    frag.clause
+                        .get_or_insert_with(|| parse_quote!(where))
+                        .predicates
+                        .push(parse_quote!(#predicate));
+                }
+            }
+
+            if !clause.predicates.is_empty() {
+                self.merge_clause(clause);
+            }
+        }
+
+        self.pattern.extend(pattern);
+    }
+
+    fn merge_clause(&mut self, clause: WhereClause) {
+        match self.clause.as_mut() {
+            Some(shared) => shared.predicates.extend(clause.predicates),
+            None => self.clause = Some(clause),
+        }
+    }
+}
+
+impl Parse for FieldRange {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let from: Option<LitInt> = input.parse()?;
+        let limits: RangeLimits = input.parse()?;
+        let to: Option<LitInt> = input.parse()?;
+
+        if matches!(limits, RangeLimits::Closed(_)) && to.is_none() {
+            return Err(syn::Error::new(
+                limits.span(),
+                "Expected an upper bound after `..=`.",
+            ));
+        }
+
+        let min = from.as_ref().map_or(Ok(0), LitInt::base10_parse)?;
+        let max = to.as_ref().map(LitInt::base10_parse).transpose()?;
+
+        if matches!(max, Some(max) if max < min) {
+            return Err(syn::Error::new(
+                to.span(),
+                format!(
+                    "The range `{min}..{}` can never be satisfied.",
+                    max.unwrap()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            from,
+            limits,
+            to,
+            min,
+            max,
+            bounds: parse_residual_bounds(input)?,
         })
     }
 }
 
+impl Parse for PenumOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: Ident = input.parse()?;
+
+        match ident.to_string().as_str() {
+            "impl_display_from" => {
+                let content;
+                let _ = parenthesized!(content in input);
+                Ok(PenumOption::ImplDisplayFrom(content.parse()?))
+            }
+            "name_of_index" => Ok(PenumOption::NameOfIndex(ident)),
+            "via_deref" => Ok(PenumOption::ViaDeref(ident)),
+            "strict" => Ok(PenumOption::Strict(ident)),
+            "uniform" => Ok(PenumOption::Uniform(ident)),
+            "subject_generics" => Ok(PenumOption::SubjectGenerics(ident)),
+            "alias" => {
+                let content;
+                let _ = parenthesized!(content in input);
+                let aliases = content.parse_terminated::<_, Token![,]>(|input| {
+                    let alias: Type = input.parse()?;
+                    let _: Token![=] = input.parse()?;
+                    Ok((alias, input.parse::<Type>()?))
+                })?;
+
+                Ok(PenumOption::Alias(ident, aliases.into_iter().collect()))
+            }
+            "fragment_limit" => {
+                let content;
+                let _ = parenthesized!(content in input);
+                let limit: LitInt = content.parse()?;
+                Ok(PenumOption::FragmentLimit(ident, limit.base10_parse()?))
+            }
+            _ => Err(syn::Error::new(
+                ident.span(),
+                format!("`{ident}` is not a penum option"),
+            )),
+        }
+    }
+}
+
 impl Parse for PatComposite {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let content;
-        Ok(if input.peek(token::Brace) {
+        let composite = if input.peek(token::Brace) {
             let token = braced!(content in input);
             PatComposite::Named {
                 parameters: content.parse_terminated(PatFieldKind::parse)?,
@@ -135,38 +360,264 @@ impl Parse for PatComposite {
         } else if input.peek(token::Paren) {
             let token = parenthesized!(content in input);
             PatComposite::Unnamed {
-                parameters: content.parse_terminated(PatFieldKind::parse)?,
+                parameters: content.parse_terminated(PatFieldKind::parse_unnamed)?,
                 delimiter: token,
             }
         } else {
             PatComposite::Unit
-        })
+        };
+
+        if composite.count_with(PatFieldKind::is_residual) > 1 {
+            return Err(syn::Error::new(
+                composite.span(),
+                "Only one variadic `..` is allowed per pattern fragment.",
+            ));
+        }
+
+        Ok(composite)
     }
 }
 
 impl Parse for PatFieldKind {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(if input.peek(Token![..]) && input.peek2(LitInt) {
+        Ok(if peek_range(input) {
             PatFieldKind::Range(input.parse()?)
         } else if input.peek(Token![..]) {
-            PatFieldKind::Variadic(input.parse()?)
+            PatFieldKind::variadic(input)?
+        } else if peek_bounded_generic(input) {
+            let ident: Ident = input.parse()?;
+
+            return Err(syn::Error::new(
+                ident.span(),
+                format!(
+                    "`{ident}: ..` is read as a generic with inline bounds, which needs a field name inside `{{ .. }}`, e.g. `{{ name: {ident}: .. }}`."
+                ),
+            ));
+        } else if input.peek(Token![_]) && input.peek2(Token![:]) {
+            PatFieldKind::wildcard(input)?
+        } else if input.peek(Ident) && input.peek2(Token![?]) {
+            PatFieldKind::optional(input)?
         } else if input.peek(Ident) && input.peek2(Token![:]) {
-            PatFieldKind::Field(input.call(Field::parse_named)?)
+            let ident: Ident = input.parse()?;
+            let colon_token: Token![:] = input.parse()?;
+
+            if peek_negation(input) {
+                return PatFieldKind::negated(input, Some(ident), Some(colon_token));
+            }
+
+            let field = Field {
+                ident: Some(ident),
+                colon_token: Some(colon_token),
+                ..input.call(Field::parse_unnamed)?
+            };
+
+            PatFieldKind::with_bounds(input, field)?
+        } else if peek_negation(input) {
+            PatFieldKind::negated(input, None, None)?
         } else {
-            PatFieldKind::Field(input.call(Field::parse_unnamed)?)
+            PatFieldKind::with_bounds(input, input.call(Field::parse_unnamed)?)?
         })
     }
 }
 
-pub fn parse_pattern(input: ParseStream) -> syn::Result<Vec<PatFrag>> {
-    let mut shape = vec![input.call(parse_pattern_fragment)?];
+impl PatFieldKind {
+    /// Used for `Unnamed` composites, where `ident: ..` means that a type has inline bounds
+    /// instead of being a named field.
+    pub fn parse_unnamed(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![..]) || input.peek(LitInt) {
+            return input.parse();
+        }
+
+        if peek_negation(input) {
+            return PatFieldKind::negated(input, None, None);
+        }
+
+        let field = input.call(Field::parse_unnamed)?;
+        PatFieldKind::with_bounds(input, field)
+    }
+
+    /// Parses the type of a negated field, e.g. `!String`. Generics, placeholders and impl
+    /// expressions are rejected given that they can't be excluded.
+    fn negated(
+        input: ParseStream,
+        ident: Option<Ident>,
+        colon_token: Option<Token![:]>,
+    ) -> syn::Result<Self> {
+        let not_token = input.parse()?;
+        let field = Field {
+            ident,
+            colon_token,
+            ..input.call(Field::parse_unnamed)?
+        };
+
+        if field.ty.is_generic()
+            || field.ty.is_placeholder()
+            || field.ty.get_type_impl_trait().is_some()
+        {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "Only concrete types can be negated, e.g. `!String`.",
+            ));
+        }
+
+        Ok(PatFieldKind::Negated { field, not_token })
+    }
+
+    /// Parses a field that matches by type instead of by name, e.g. `_: T`.
+    fn wildcard(input: ParseStream) -> syn::Result<Self> {
+        let underscore_token = input.parse()?;
+        let field = Field {
+            colon_token: Some(input.parse()?),
+            ..input.call(Field::parse_unnamed)?
+        };
+
+        Ok(PatFieldKind::Wildcard {
+            underscore_token,
+            field,
+        })
+    }
+
+    /// Parses a named field that a variant may leave out, e.g. `age?: usize`.
+    fn optional(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        let question_token = input.parse()?;
+        let field = Field {
+            ident: Some(ident),
+            colon_token: Some(input.parse()?),
+            ..input.call(Field::parse_unnamed)?
+        };
+
+        Ok(PatFieldKind::Optional {
+            field,
+            question_token,
+        })
+    }
+
+    fn with_bounds(input: ParseStream, field: Field) -> syn::Result<Self> {
+        if input.peek(Token![=]) {
+            return PatFieldKind::with_default(input, field);
+        }
+
+        if !peek_bounds(input) {
+            return Ok(PatFieldKind::Field(field));
+        }
+
+        let colon_token = input.parse()?;
+        let bounds = parse_bounds(input)?;
+
+        if let Some(bound) =
+            find_dispatchable(&bounds).filter(|_| !field.ty.is_dispatchable_inline())
+        {
+            return Err(syn::Error::new(
+                bound.span(),
+                "Only generics and concrete types can dispatch inline bounds, e.g. `(T: ^Trait)` or `(i32: ^Trait)`. Use a generic instead.",
+            ));
+        }
+
+        Ok(PatFieldKind::Bounded {
+            field,
+            colon_token,
+            bounds,
+        })
+    }
+
+    /// Parses the default type of a generic, e.g. `T = i32`.
+    fn with_default(input: ParseStream, field: Field) -> syn::Result<Self> {
+        let eq_token = input.parse()?;
+        let default: Type = input.parse()?;
+
+        if !field.ty.is_generic() {
+            return Err(syn::Error::new(
+                field.ty.span(),
+                "Only generics can have a default type, e.g. `(T = i32)`.",
+            ));
+        }
+
+        if default.is_generic()
+            || default.is_placeholder()
+            || default.get_type_impl_trait().is_some()
+        {
+            return Err(syn::Error::new(
+                default.span(),
+                "The default of a generic has to be a concrete type, e.g. `(T = i32)`.",
+            ));
+        }
+
+        Ok(PatFieldKind::Defaulted {
+            field,
+            eq_token,
+            default: Box::new(default),
+        })
+    }
+
+    /// Parses a variadic with an optional name and bounds, e.g. `..`, `..rest` or `..rest: Copy`.
+    fn variadic(input: ParseStream) -> syn::Result<Self> {
+        Ok(PatFieldKind::Variadic(
+            input.parse()?,
+            input.parse()?,
+            parse_residual_bounds(input)?,
+        ))
+    }
+}
+
+/// Parses the fragments of a pattern. Fragments that name a registered shape are replaced by the
+/// fragments of that shape, e.g. `Pair | (T, ..) | _`.
+pub fn parse_pattern(input: ParseStream) -> syn::Result<PenumExpr> {
+    let mut expr = PenumExpr {
+        pattern: vec![],
+        clause: None,
+        options: vec![],
+    };
+
+    loop {
+        if peek_registered_shape(input) {
+            let name: Ident = input.parse()?;
+            expr.compose(expand_registered_shape(&name)?);
+        } else {
+            expr.pattern.push(input.call(parse_pattern_fragment)?);
+        }
+
+        if !input.peek(token::Or) {
+            break;
+        }
 
-    while input.peek(token::Or) {
         let _: token::Or = input.parse()?;
-        shape.push(input.call(parse_pattern_fragment)?);
     }
 
-    Ok(shape)
+    // A generic can only fall back on a single type, e.g. `(T = i32) | (T = u8)` is rejected.
+    let mut defaults = BTreeMap::new();
+
+    for param in expr.pattern.iter().flat_map(|frag| frag.group.iter()) {
+        let (Some(field), Some(default)) = (param.get_field(), param.get_default()) else {
+            continue;
+        };
+
+        let generic = field.ty.get_generic_ident();
+        let default_id = default.get_unique_id();
+
+        if defaults
+            .insert(generic.clone(), default_id.clone())
+            .is_some_and(|other| other != default_id)
+        {
+            return Err(syn::Error::new(
+                default.span(),
+                format!("`{generic}` already has a different default type."),
+            ));
+        }
+    }
+
+    // A pattern of only negated fragments, e.g. `!(String, ..)`, accepts every other variant.
+    if expr.pattern.iter().all(PatFrag::is_negated) {
+        expr.pattern.push(PatFrag {
+            selector: None,
+            group: PatComposite::Inferred(None),
+            clause: None,
+            negation: None,
+            discriminant: None,
+        });
+    }
+
+    Ok(expr)
 }
 
 pub fn parse_pattern_fragment(input: ParseStream) -> syn::Result<PatFrag> {
@@ -174,16 +625,611 @@ pub fn parse_pattern_fragment(input: ParseStream) -> syn::Result<PatFrag> {
         let _: Token![$] = input.parse()?;
     }
 
-    if input.peek(Token![_]) {
+    let negation: Option<Token![!]> = input.parse()?;
+
+    let fragment = if input.peek(Token![_]) {
         let _: Token![_] = input.parse()?;
-        Ok(PatFrag {
-            ident: None,
-            group: PatComposite::Inferred,
-        })
+        let group = PatComposite::Inferred(input.call(parse_inferred_arity)?);
+        let discriminant = input.call(parse_discriminant_range)?;
+
+        PatFrag {
+            selector: None,
+            group,
+            clause: input.call(parse_fragment_clause)?,
+            negation,
+            discriminant,
+        }
     } else {
-        Ok(PatFrag {
-            ident: input.parse()?,
-            group: input.parse()?,
-        })
+        let selector = input.call(parse_variant_selector)?;
+        let group = input.parse()?;
+        let discriminant = input.call(parse_discriminant_range)?;
+
+        PatFrag {
+            selector,
+            group,
+            clause: input.call(parse_fragment_clause)?,
+            negation,
+            discriminant,
+        }
+    };
+
+    // A rejected variant isn't given any bounds, so there is nothing to scope a clause to.
+    if let (Some(_), Some(clause)) = (fragment.negation, fragment.clause.as_ref()) {
+        return Err(syn::Error::new(
+            clause.where_token.span,
+            "A negated fragment can't have a where clause.",
+        ));
+    }
+
+    Ok(fragment)
+}
+
+/// Checks if a predicate of a registered shape can be asserted as a clause of `frag`, i.e. if it
+/// bounds a generic of the fragment without dispatching or projecting.
+fn is_scoped_to(predicate: &WherePredicate, frag: &PatFrag) -> bool {
+    let WherePredicate::Type(predicate) = predicate else {
+        return false;
+    };
+
+    !frag.is_negated()
+        && predicate.bounded_ty.is_generic()
+        && get_projection(&predicate.bounded_ty).is_none()
+        && find_dispatchable(&predicate.bounds).is_none()
+        && frag
+            .get_generic_idents()
+            .contains(&predicate.bounded_ty.get_generic_ident())
+}
+
+/// Parses the clause of a fragment, e.g. `where T: Copy` in `(T) where T: Copy | (U)`. A clause
+/// that isn't followed by another fragment is left to be the one shared by every fragment.
+fn parse_fragment_clause(input: ParseStream) -> syn::Result<Option<WhereClause>> {
+    if !input.peek(Token![where]) {
+        return Ok(None);
+    }
+
+    let fork = input.fork();
+    if fork.parse::<WhereClause>().is_err() || !fork.peek(Token![|]) {
+        return Ok(None);
+    }
+
+    let clause: WhereClause = input.parse()?;
+
+    // Dispatching and projections are resolved for the whole enum, so they can't be scoped to
+    // the variants of a single fragment.
+    for predicate in clause.predicates.iter() {
+        let WherePredicate::Type(predicate) = predicate else {
+            continue;
+        };
+
+        if get_projection(&predicate.bounded_ty).is_some() {
+            return Err(syn::Error::new(
+                predicate.bounded_ty.span(),
+                "Associated types can only be bounded in the trailing where clause.",
+            ));
+        }
+
+        if let Some(bound) = find_dispatchable(&predicate.bounds) {
+            return Err(syn::Error::new(
+                bound.span(),
+                "Only the trailing where clause can dispatch, e.g. `(T) | (U) where T: ^Trait`.",
+            ));
+        }
+    }
+
+    Ok(Some(clause))
+}
+
+/// Parses the optional selector in front of a fragment, e.g. `Foo`, `Net*` or `*Error`.
+fn parse_variant_selector(input: ParseStream) -> syn::Result<Option<VariantSelector>> {
+    let mut parts = vec![];
+
+    loop {
+        if input.peek(Token![*]) {
+            parts.push(SelectorPart::Wildcard(input.parse()?));
+        } else if input.peek(Ident) && !matches!(parts.last(), Some(SelectorPart::Name(_))) {
+            parts.push(SelectorPart::Name(input.parse()?));
+        } else {
+            break;
+        }
+    }
+
+    Ok((!parts.is_empty()).then_some(VariantSelector(parts)))
+}
+
+/// Parses the optional arity constraint of an inferred fragment, i.e. the `(3)` in `_(3)` or
+/// the `{3}` in `_{3}`.
+pub fn parse_inferred_arity(input: ParseStream) -> syn::Result<Option<InferredArity>> {
+    let content;
+    let kind = if input.peek(token::Paren) {
+        let _ = parenthesized!(content in input);
+        CompositeKind::Unnamed
+    } else if input.peek(token::Brace) {
+        let _ = braced!(content in input);
+        CompositeKind::Named
+    } else {
+        return Ok(None);
+    };
+
+    let arity: LitInt = content.parse()?;
+    let value = arity.base10_parse()?;
+
+    Ok(Some(InferredArity { kind, arity, value }))
+}
+
+/// A generic declared in front of a pattern together with its bounds, e.g. `T: Trait`.
+type DeclaredGeneric = (Ident, Option<Punctuated<TypeParamBound, Token![+]>>);
+
+/// Generics declared in front of a pattern, e.g. `for<T: Trait, U>`. A lifetime, e.g.
+/// `for<'a>`, is left to the where clause.
+fn peek_declared_generics(input: ParseStream) -> bool {
+    input.peek(Token![for]) && input.peek2(Token![<]) && !input.peek3(Lifetime)
+}
+
+/// Parses the generics declared in front of a pattern together with their bounds, e.g.
+/// `for<T: ^Trait, U>`.
+fn parse_declared_generics(input: ParseStream) -> syn::Result<Vec<DeclaredGeneric>> {
+    let _: Token![for] = input.parse()?;
+    let _: Token![<] = input.parse()?;
+
+    let mut generics: Vec<DeclaredGeneric> = vec![];
+
+    while !input.peek(Token![>]) {
+        let generic: Ident = input.parse()?;
+        let ty: Type = syn::parse_quote!(#generic);
+
+        if !ty.is_generic() {
+            return Err(syn::Error::new(
+                generic.span(),
+                "Only generics can be declared, e.g. `for<T, U>`.",
+            ));
+        }
+
+        if generics.iter().any(|(declared, _)| *declared == generic) {
+            return Err(syn::Error::new(
+                generic.span(),
+                generic_declared_twice(&generic),
+            ));
+        }
+
+        let bounds = if input.peek(Token![:]) {
+            let _: Token![:] = input.parse()?;
+            Some(parse_bounds(input)?)
+        } else {
+            None
+        };
+
+        generics.push((generic, bounds));
+
+        if !input.peek(Token![,]) {
+            break;
+        }
+
+        let _: Token![,] = input.parse()?;
+    }
+
+    let _: Token![>] = input.parse()?;
+
+    Ok(generics)
+}
+
+/// Once generics are declared, every generic of the pattern and its clauses has to be one of
+/// them, and each of them has to be used by the pattern.
+fn check_declared_generics(expr: &PenumExpr, declared: &[DeclaredGeneric]) -> syn::Result<()> {
+    let params = expr
+        .pattern
+        .iter()
+        .flat_map(|fragment| fragment.group.iter());
+
+    let used: Vec<&Ident> = params
+        .clone()
+        .filter_map(PatFieldKind::get_field)
+        .flat_map(|field| find_generics(&field.ty))
+        .collect();
+
+    let predicates = expr
+        .pattern
+        .iter()
+        .filter_map(|fragment| fragment.clause.as_ref())
+        .chain(expr.clause.as_ref())
+        .flat_map(|clause| clause.predicates.iter())
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(predicate) => Some(predicate),
+            _ => None,
+        });
+
+    let bounds = params
+        .filter_map(PatFieldKind::get_bounds)
+        .chain(predicates.clone().map(|predicate| &predicate.bounds))
+        .flat_map(|bounds| bounds.iter())
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(bound) => Some(&bound.ty),
+            _ => None,
+        });
+
+    let mentioned = predicates
+        .map(|predicate| &predicate.bounded_ty)
+        .chain(bounds)
+        .flat_map(find_generics);
+
+    if let Some(generic) = used
+        .iter()
+        .copied()
+        .chain(mentioned)
+        .find(|generic| declared.iter().all(|(declared, _)| declared != *generic))
+    {
+        return Err(syn::Error::new(
+            generic.span(),
+            generic_not_declared(generic),
+        ));
+    }
+
+    if let Some((generic, _)) = declared
+        .iter()
+        .find(|(generic, _)| !used.contains(&generic))
+    {
+        return Err(syn::Error::new(generic.span(), generic_never_used(generic)));
+    }
+
+    Ok(())
+}
+
+/// A `=` followed by a discriminant value, a range of them or an integer type, e.g. `= 3`,
+/// `= -1..=1` or `= _ as u8`.
+fn peek_discriminant_range(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    if fork.parse::<Token![=]>().is_err() || fork.peek(Token![>]) {
+        return false;
     }
+
+    fork.peek(LitInt)
+        || fork.peek(Token![-])
+        || fork.peek(Token![..])
+        || (fork.peek(Token![_]) && fork.peek2(Token![as]))
+}
+
+/// Parses the range that the discriminant of a matched variant has to be in, e.g. the
+/// `= 0..=255` in `V = 0..=255`.
+fn parse_discriminant_range(input: ParseStream) -> syn::Result<Option<DiscriminantRange>> {
+    if !peek_discriminant_range(input) {
+        return Ok(None);
+    }
+
+    let eq_token = input.parse()?;
+
+    if input.peek(Token![_]) {
+        let underscore_token: Token![_] = input.parse()?;
+        let as_token: Token![as] = input.parse()?;
+        let ty: Ident = input.parse()?;
+
+        let Some((min, max)) = get_integer_range(&ty) else {
+            return Err(syn::Error::new(
+                ty.span(),
+                "Expected an integer type, e.g. `_ as u8`.",
+            ));
+        };
+
+        return Ok(Some(DiscriminantRange {
+            eq_token,
+            tokens: quote::quote!(#underscore_token #as_token #ty),
+            min,
+            max,
+        }));
+    }
+
+    let from = input.call(parse_discriminant_value)?;
+    let limits: Option<RangeLimits> = input.peek(Token![..]).then(|| input.parse()).transpose()?;
+    let to = match limits {
+        Some(_) => input.call(parse_discriminant_value)?,
+        None => None,
+    };
+
+    let tokens = {
+        let from = from.as_ref().map(|(tokens, _)| tokens);
+        let to = to.as_ref().map(|(tokens, _)| tokens);
+        quote::quote!(#from #limits #to)
+    };
+
+    let (min, max) = match (
+        from.map(|(_, value)| value),
+        limits,
+        to.map(|(_, value)| value),
+    ) {
+        (Some(value), None, _) => (value, value),
+        (from, Some(RangeLimits::HalfOpen(_)), to) => (
+            from.unwrap_or(i128::MIN),
+            to.map_or(i128::MAX, |to| to.saturating_sub(1)),
+        ),
+        (from, Some(RangeLimits::Closed(limits)), to) => {
+            let Some(to) = to else {
+                return Err(syn::Error::new(
+                    limits.span(),
+                    "Expected an upper bound after `..=`.",
+                ));
+            };
+
+            (from.unwrap_or(i128::MIN), to)
+        }
+        (None, None, _) => unreachable!("a discriminant range starts with a value or `..`"),
+    };
+
+    if min > max {
+        return Err(syn::Error::new_spanned(
+            &tokens,
+            "The discriminant range is empty.",
+        ));
+    }
+
+    Ok(Some(DiscriminantRange {
+        eq_token,
+        tokens,
+        min,
+        max,
+    }))
+}
+
+/// Parses an optionally negative integer, e.g. `-1`.
+fn parse_discriminant_value(input: ParseStream) -> syn::Result<Option<(TokenStream, i128)>> {
+    if !input.peek(LitInt) && !input.peek(Token![-]) {
+        return Ok(None);
+    }
+
+    let minus: Option<Token![-]> = input.parse()?;
+    let lit: LitInt = input.parse()?;
+    let value: i128 = lit.base10_parse()?;
+
+    Ok(Some((
+        quote::quote!(#minus #lit),
+        if minus.is_some() { -value } else { value },
+    )))
+}
+
+/// Returns the smallest and largest value of an integer type, where `usize` and `isize` are
+/// read as 64-bit integers.
+fn get_integer_range(ty: &Ident) -> Option<(i128, i128)> {
+    Some(match ty.to_string().as_str() {
+        "u8" => (0, u8::MAX.into()),
+        "u16" => (0, u16::MAX.into()),
+        "u32" => (0, u32::MAX.into()),
+        "u64" | "usize" => (0, u64::MAX.into()),
+        "u128" => (0, i128::MAX),
+        "i8" => (i8::MIN.into(), i8::MAX.into()),
+        "i16" => (i16::MIN.into(), i16::MAX.into()),
+        "i32" => (i32::MIN.into(), i32::MAX.into()),
+        "i64" | "isize" => (i64::MIN.into(), i64::MAX.into()),
+        "i128" => (i128::MIN, i128::MAX),
+        _ => return None,
+    })
+}
+
+/// A `!` followed by a type, e.g. `!String`. A lone `!` is the never type.
+fn peek_negation(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![!]>().is_ok() && !fork.is_empty() && !fork.peek(Token![,])
+}
+
+fn peek_bounds(input: ParseStream) -> bool {
+    input.peek(Token![:]) && !input.peek(Token![::])
+}
+
+fn parse_bounds(input: ParseStream) -> syn::Result<Punctuated<TypeParamBound, Token![+]>> {
+    let mut bounds = Punctuated::new();
+
+    loop {
+        bounds.push_value(input.parse()?);
+
+        if !input.peek(Token![+]) {
+            break;
+        }
+
+        bounds.push_punct(input.parse()?);
+    }
+
+    Ok(bounds)
+}
+
+/// Parses the optional bounds of a variadic or a range, e.g. `: Copy` in `(T, ..: Copy)`. These
+/// cover several fields, so they can't be dispatched.
+fn parse_residual_bounds(input: ParseStream) -> syn::Result<Option<ResidualBounds>> {
+    if !peek_bounds(input) {
+        return Ok(None);
+    }
+
+    let colon_token = input.parse()?;
+    let bounds = parse_bounds(input)?;
+
+    if let Some(bound) = find_dispatchable(&bounds) {
+        return Err(syn::Error::new(
+            bound.span(),
+            "The bounds of a variadic cannot be dispatched. Move the `^` bound into the where clause instead.",
+        ));
+    }
+
+    Ok(Some((colon_token, bounds)))
+}
+
+fn find_dispatchable(bounds: &Punctuated<TypeParamBound, Token![+]>) -> Option<&TypeParamBound> {
+    bounds
+        .iter()
+        .find(|bound| bound.get_dispatchable_trait_bound().is_some())
+}
+
+/// An uppercase-only ident followed by a `:`, e.g. `T: Trait`, is a generic with inline bounds and
+/// not a named field.
+fn peek_bounded_generic(input: ParseStream) -> bool {
+    let fork = input.fork();
+    let Ok(ident) = fork.parse::<Ident>() else {
+        return false;
+    };
+
+    let name = ident.to_string();
+    name == name.to_uppercase() && peek_bounds(&fork)
+}
+
+/// `..` is made out of two tokens, so we can't use `peek2` to look for the bound.
+fn peek_range(input: ParseStream) -> bool {
+    if input.peek(LitInt) || input.peek(Token![..=]) {
+        return true;
+    }
+
+    let fork = input.fork();
+    fork.parse::<RangeLimits>().is_ok() && fork.peek(LitInt)
+}
+
+/// Parses the leading options of a penum expression. Every option has to be followed by either a
+/// `,`, a `;` or the end of the expression, e.g. `strict, (T)` or `strict; (T)`.
+pub fn parse_options(input: ParseStream) -> syn::Result<Vec<PenumOption>> {
+    let mut options = vec![];
+
+    while matches!(
+        input.fork().parse::<Ident>(),
+        Ok(ident) if PENUM_OPTIONS.contains(&ident.to_string().as_str())
+    ) {
+        options.push(input.parse()?);
+
+        if input.is_empty() {
+            break;
+        }
+
+        if input.peek(Token![;]) {
+            let _: Token![;] = input.parse()?;
+        } else {
+            let _: Token![,] = input.parse()?;
+        }
+    }
+
+    Ok(options)
+}
+
+/// Parses the options that follow a penum expression, e.g. `, alias(Bytes = Vec<u8>)` in
+/// `(T), alias(Bytes = Vec<u8>)`.
+fn parse_trailing_options(input: ParseStream) -> syn::Result<Vec<PenumOption>> {
+    let mut options = vec![];
+
+    while peek_trailing_option(input) {
+        let _: Token![,] = input.parse()?;
+        options.push(input.parse()?);
+    }
+
+    Ok(options)
+}
+
+/// Parses a reference to a pattern registered with `penum::pattern!`, e.g. `use = Shape`.
+fn parse_registered_pattern(
+    input: ParseStream,
+    options: Vec<PenumOption>,
+) -> syn::Result<PenumExpr> {
+    let _: Token![use] = input.parse()?;
+    let _: Token![=] = input.parse()?;
+    let name: Ident = input.parse()?;
+
+    expand_registered_pattern(&name, options)
+}
+
+/// A fragment that is just a name registered with `penum::shape!`, e.g. `#[penum(Pair | _)]`.
+/// Names that aren't registered are read as a selector of a unit variant instead, e.g.
+/// `#[penum(Foo)]`.
+fn peek_registered_shape(input: ParseStream) -> bool {
+    let fork = input.fork();
+    let Ok(name) = fork.parse::<Ident>() else {
+        return false;
+    };
+
+    (fork.is_empty() || fork.peek(token::Or) || fork.peek(Token![where]))
+        && S_SHM.find(&name.to_string()).is_some()
+}
+
+fn expand_registered_shape(name: &Ident) -> syn::Result<PenumExpr> {
+    let Some(shape) = S_SHM.find(&name.to_string()) else {
+        return Err(syn::Error::new(name.span(), shape_not_registered(name)));
+    };
+
+    syn::parse_str(&shape)
+}
+
+/// The options in front of the reference are added to the ones of the registered pattern.
+fn expand_registered_pattern(
+    name: &Ident,
+    mut options: Vec<PenumOption>,
+) -> syn::Result<PenumExpr> {
+    let Some(pattern) = P_SHM.find(&name.to_string()) else {
+        return Err(syn::Error::new(name.span(), pattern_not_registered(name)));
+    };
+
+    let mut expr: PenumExpr = syn::parse_str(&pattern)?;
+    options.append(&mut expr.options);
+    expr.options = options;
+
+    Ok(expr)
+}
+
+/// Parses a penum expression written as a string literal, e.g. `#[penum("(T, U)")]`.
+///
+/// The tokens of the string are respanned onto the part of the literal they were written in, so
+/// that an error like `(T,, U)` points at the offending token instead of the whole literal.
+///
+/// NOTE: Pointing into a literal requires `Literal::subspan`, which is only supported on nightly.
+/// Otherwise, and for literals with escapes, we fall back on the span of the whole literal.
+fn parse_lit_str(lit: &LitStr) -> syn::Result<PenumExpr> {
+    let value = lit.value();
+    let source = lit.token().to_string();
+
+    if source.get(1..source.len().saturating_sub(1)) != Some(value.as_str()) {
+        return lit.parse_with(PenumExpr::parse);
+    }
+
+    let tokens: TokenStream = value.parse()?;
+    let mut cursor = 0;
+    let tokens = respan_into_literal(tokens, lit, &value, &mut cursor);
+
+    PenumExpr::parse.parse2(tokens)
+}
+
+/// Sets the span of every token to the part of the literal it was written in.
+fn respan_into_literal(
+    tokens: TokenStream,
+    lit: &LitStr,
+    value: &str,
+    cursor: &mut usize,
+) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            match &mut token {
+                TokenTree::Group(group) => {
+                    let open = match group.delimiter() {
+                        Delimiter::Parenthesis => "(",
+                        Delimiter::Brace => "{",
+                        Delimiter::Bracket => "[",
+                        Delimiter::None => "",
+                    };
+
+                    let span = find_subspan(open, lit, value, cursor);
+                    let stream = respan_into_literal(group.stream(), lit, value, cursor);
+
+                    *group = Group::new(group.delimiter(), stream);
+                    group.set_span(span);
+                }
+                token => {
+                    let span = find_subspan(&token.to_string(), lit, value, cursor);
+                    token.set_span(span);
+                }
+            }
+            token
+        })
+        .collect()
+}
+
+/// Returns the span of the first occurrence of `text` in the literal after `cursor`, and moves
+/// the cursor past it. Offsets are shifted by one to skip the opening quote.
+fn find_subspan(text: &str, lit: &LitStr, value: &str, cursor: &mut usize) -> Span {
+    let Some(offset) = value[*cursor..].find(text).map(|offset| *cursor + offset) else {
+        return lit.span();
+    };
+
+    *cursor = offset + text.len();
+
+    lit.token()
+        .subspan(offset + 1..offset + 1 + text.len())
+        .unwrap_or_else(|| lit.span())
 }
@@ -3,12 +3,37 @@ use std::str::FromStr;
 use proc_macro2::TokenStream;
 use quote::ToTokens;
 
-use super::{PatComposite, PatFieldKind, PatFrag};
+use crate::factory::CompositeKind;
+
+use super::{
+    DiscriminantRange, InferredArity, PatComposite, PatFieldKind, PatFrag, SelectorPart,
+    VariantSelector,
+};
 
 impl ToTokens for PatFrag {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        self.ident.to_tokens(tokens);
+        self.negation.to_tokens(tokens);
+        self.selector.to_tokens(tokens);
         self.group.to_tokens(tokens);
+        self.discriminant.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for DiscriminantRange {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.eq_token.to_tokens(tokens);
+        self.tokens.to_tokens(tokens);
+    }
+}
+
+impl ToTokens for VariantSelector {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for part in self.0.iter() {
+            match part {
+                SelectorPart::Name(ident) => ident.to_tokens(tokens),
+                SelectorPart::Wildcard(star) => star.to_tokens(tokens),
+            }
+        }
     }
 }
 
@@ -16,8 +41,66 @@ impl ToTokens for PatFieldKind {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         match self {
             PatFieldKind::Field(f) => f.to_tokens(tokens),
-            PatFieldKind::Variadic(v) => v.to_tokens(tokens),
-            PatFieldKind::Range(r) => r.to_tokens(tokens),
+            PatFieldKind::Bounded {
+                field,
+                colon_token,
+                bounds,
+            } => {
+                field.to_tokens(tokens);
+                colon_token.to_tokens(tokens);
+                bounds.to_tokens(tokens);
+            }
+            PatFieldKind::Negated { field, not_token } => {
+                field.ident.to_tokens(tokens);
+                field.colon_token.to_tokens(tokens);
+                not_token.to_tokens(tokens);
+                field.ty.to_tokens(tokens);
+            }
+            PatFieldKind::Wildcard {
+                underscore_token,
+                field,
+            } => {
+                underscore_token.to_tokens(tokens);
+                field.colon_token.to_tokens(tokens);
+                field.ty.to_tokens(tokens);
+            }
+            PatFieldKind::Optional {
+                field,
+                question_token,
+            } => {
+                field.ident.to_tokens(tokens);
+                question_token.to_tokens(tokens);
+                field.colon_token.to_tokens(tokens);
+                field.ty.to_tokens(tokens);
+            }
+            PatFieldKind::Defaulted {
+                field,
+                eq_token,
+                default,
+            } => {
+                field.to_tokens(tokens);
+                eq_token.to_tokens(tokens);
+                default.to_tokens(tokens);
+            }
+            PatFieldKind::Variadic(v, name, bounds) => {
+                v.to_tokens(tokens);
+                name.to_tokens(tokens);
+
+                if let Some((colon_token, bounds)) = bounds {
+                    colon_token.to_tokens(tokens);
+                    bounds.to_tokens(tokens);
+                }
+            }
+            PatFieldKind::Range(range) => {
+                range.from.to_tokens(tokens);
+                range.limits.to_tokens(tokens);
+                range.to.to_tokens(tokens);
+
+                if let Some((colon_token, bounds)) = &range.bounds {
+                    colon_token.to_tokens(tokens);
+                    bounds.to_tokens(tokens);
+                }
+            }
             PatFieldKind::Infer => tokens.extend(TokenStream::from_str("_")),
             PatFieldKind::Nothing => (),
         }
@@ -35,6 +118,12 @@ impl ToTokens for PatComposite {
                 parameters,
                 delimiter,
             } => delimiter.surround(tokens, |tokens| parameters.to_tokens(tokens)),
+            PatComposite::Inferred(Some(InferredArity { kind, arity, .. })) => {
+                tokens.extend(match kind {
+                    CompositeKind::Named => quote::quote!(_ { #arity }),
+                    _ => quote::quote!(_(#arity)),
+                })
+            }
             _ => (),
         }
     }
@@ -1,15 +1,24 @@
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
 
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
 use quote::{format_ident, ToTokens};
 use syn::{
+    parse::Parser,
     punctuated::Punctuated,
+    spanned::Spanned,
     token::{self, Comma},
-    Attribute, DataEnum, Expr, ExprMacro, Fields, Generics, Macro, Token, TraitBound, Variant,
-    Visibility,
+    Attribute, DataEnum, Expr, ExprGroup, ExprLit, ExprMacro, ExprParen, ExprUnary, Fields,
+    GenericParam, Generics, Lit, Macro, Pat, Token, TraitBound, Type, UnOp, Variant, Visibility,
+    WherePredicate,
 };
 
-use crate::utils::{Stringify, ABSTRACT_MACRO_EXPR_SYMBOL, DEFAULT_VARIANT_SYMBOL};
+use crate::polym::UniqueHashId;
+use crate::utils::{
+    get_referenced_generics, Stringify, TypeAliases, TypeUtils, ABSTRACT_MACRO_EXPR_SYMBOL,
+    DEFAULT_VARIANT_SYMBOL,
+};
 
 use super::Comparable;
 
@@ -25,6 +34,15 @@ pub struct Subject {
     pub ident: Ident,
     pub generics: Generics,
     pub data: DataEnum,
+    pub kind: SubjectKind,
+}
+
+/// A struct is read as an enum with a single variant named after the struct. This lets us
+/// validate its fields exactly like we do with variants.
+#[derive(Clone, Debug)]
+pub enum SubjectKind {
+    Enum,
+    Struct(Token![struct], Option<Token![;]>),
 }
 
 #[derive(Clone, Debug)]
@@ -45,15 +63,158 @@ impl Subject {
         &self.data.variants
     }
 
+    pub fn is_struct(&self) -> bool {
+        matches!(self.kind, SubjectKind::Struct(..))
+    }
+
+    /// Returns true if every variant has a discriminant, meaning that no fallback arm is needed.
+    pub fn is_discriminant_exhaustive(&self) -> bool {
+        self.get_variants()
+            .iter()
+            .all(|variant| variant.discriminant.is_some())
+    }
+
+    /// Returns the discriminant value of each variant together with its span, where implicit
+    /// values count up from the previous one, e.g. `2` and `3` for `A = 2, B`. Values that can't
+    /// be evaluated here, e.g. `A = SIZE`, are `None`, and so are the implicit ones after them.
+    pub fn get_discriminant_values(&self) -> Vec<(&Ident, Option<(i128, Span)>)> {
+        let mut next = Some(0);
+
+        self.get_variants()
+            .iter()
+            .map(|variant| {
+                let value = match variant.discriminant.as_ref() {
+                    Some((_, expr)) => get_integer_value(expr).map(|value| (value, expr.span())),
+                    None => next.map(|value| (value, variant.ident.span())),
+                };
+
+                next = value.and_then(|(value, _)| value.checked_add(1));
+
+                (&variant.ident, value)
+            })
+            .collect()
+    }
+
+    /// Removes the variant named `symbol` and returns its discriminant, e.g. the `expr` of
+    /// `error = expr`.
+    pub fn take_variant_discriminant(&mut self, symbol: &str) -> Option<Expr> {
+        let mut discriminant = None;
+
+        self.data.variants = std::mem::take(&mut self.data.variants)
+            .into_iter()
+            .filter_map(|variant| {
+                if variant.ident != symbol || variant.discriminant.is_none() {
+                    return Some(variant);
+                }
+
+                discriminant = variant.discriminant.map(|(_, expr)| expr);
+                None
+            })
+            .collect();
+
+        discriminant
+    }
+
+    /// Returns the where clause predicates that bound one of the enum's own type parameters,
+    /// e.g. `T: Trait`, `T::Item: Trait` or `<T as Deref>::Target: Trait`, or a type that is
+    /// built from its const parameters, e.g. `[u8; N]: Trait`, but not `i32: Trait`.
+    pub fn get_generic_predicates(&self) -> Vec<WherePredicate> {
+        let Some(where_clause) = self.generics.where_clause.as_ref() else {
+            return vec![];
+        };
+
+        let type_params = self
+            .generics
+            .params
+            .iter()
+            .filter_map(|param| match param {
+                GenericParam::Type(type_param) => Some(&type_param.ident),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let const_params = self
+            .generics
+            .const_params()
+            .map(|param| param.ident.clone())
+            .collect::<BTreeSet<_>>();
+
+        fn starts_with_param(ty: &Type, type_params: &[&Ident]) -> bool {
+            match ty {
+                Type::Path(p) => match p.qself.as_ref() {
+                    Some(qself) => starts_with_param(&qself.ty, type_params),
+                    None => p
+                        .path
+                        .segments
+                        .first()
+                        .is_some_and(|seg| type_params.contains(&&seg.ident)),
+                },
+                _ => false,
+            }
+        }
+
+        where_clause
+            .predicates
+            .iter()
+            .filter(|predicate| {
+                matches!(
+                    predicate,
+                    WherePredicate::Type(pred_ty) if starts_with_param(&pred_ty.bounded_ty, &type_params)
+                        || !get_referenced_generics(&pred_ty.bounded_ty, &const_params).is_empty()
+                )
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Replaces every type alias within the fields with the type it stands for, and returns the
+    /// position and the original type of every field that changed, so that it can be restored.
+    pub fn resolve_type_aliases(&mut self, aliases: &TypeAliases) -> Vec<(usize, usize, Type)> {
+        if aliases.is_empty() {
+            return vec![];
+        }
+
+        let mut aliased = vec![];
+
+        for (variant_index, variant) in self.data.variants.iter_mut().enumerate() {
+            for (field_index, field) in variant.fields.iter_mut().enumerate() {
+                let original = field.ty.clone();
+                aliases.resolve(&mut field.ty);
+
+                if field.ty != original {
+                    aliased.push((variant_index, field_index, original));
+                }
+            }
+        }
+
+        aliased
+    }
+
     /// This will basically break each variant into two parts, VariantIdent and a Comparable. A
     /// Comparable will eventually pair up with another Comparable to create a ComparablePair.
     ///
     /// This intermediate construct is used to extract fields that will be used multiple times during
-    /// compairs.
-    pub fn comparable_fields_iter(&self) -> impl Iterator<Item = (&Ident, Comparable<Fields>)> {
-        self.get_variants()
-            .iter()
-            .map(|variant| (&variant.ident, Comparable::from(&variant.fields)))
+    /// compairs. The unique id of each field type is paired along with it, and only computed once
+    /// per distinct type, since large enums tend to repeat the same few field types.
+    pub fn comparable_fields_iter(
+        &self,
+    ) -> impl Iterator<Item = (&Ident, Comparable<'_, Fields>, Vec<UniqueHashId<Type>>)> {
+        let mut ids: HashMap<&Type, UniqueHashId<Type>> = HashMap::new();
+
+        self.get_variants().iter().map(move |variant| {
+            let comparable = Comparable::from(&variant.fields).with_ident(&variant.ident);
+            let field_ids = variant
+                .fields
+                .iter()
+                .map(|field| {
+                    ids.entry(&field.ty)
+                        .or_insert_with(|| field.ty.get_unique_id())
+                        .clone()
+                })
+                .collect();
+
+            (&variant.ident, comparable, field_ids)
+        })
     }
 
     /// I just wanted to add this quickly and try it out, so I need to refactor this once I'm done testing.
@@ -64,57 +225,72 @@ impl Subject {
         self.get_variants()
             .iter()
             .filter_map(|variant| {
-                variant.discriminant.as_ref()?;
-                let name = &variant.ident;
+                let (_, expr) = variant.discriminant.as_ref()?;
 
-                if name.get_string().contains(DEFAULT_VARIANT_SYMBOL) {
+                if variant.ident.get_string().contains(DEFAULT_VARIANT_SYMBOL) {
                     return None;
                 }
 
-                let (_, expr) = variant.discriminant.as_ref().unwrap();
-
                 let expr_toks = match expr {
                     syn::Expr::Lit(_) => wapper(expr),
                     _ => expr.to_token_stream(),
                 };
 
-                match &variant.fields {
+                let attrs = get_arm_attrs(variant);
+                let pattern = variant_pattern(variant);
+
+                Some(quote::quote!(
+                    #attrs #pattern => { #expr_toks },
+                ))
+            })
+            .collect()
+    }
+
+    /// Like `variants_to_arms`, but variants without a discriminant get an arm that writes them
+    /// the same way `#[derive(Debug)]` would, e.g. `V1(1, 2)` or `V2 { name: "a" }`.
+    pub fn variants_to_debug_arms(
+        &self,
+        wapper: impl Fn(&Expr) -> proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let structural_arms = self
+            .get_variants()
+            .iter()
+            .filter(|variant| variant.discriminant.is_none())
+            .map(|variant| {
+                let name = variant.ident.get_string();
+                let pattern = variant_pattern(variant);
+
+                let body = match &variant.fields {
                     Fields::Named(named) => {
-                        let fields = named.named.iter().enumerate().map(|(_, f)| {
-                            let name = f.ident.as_ref();
-                            quote::quote!(#name)
+                        let fields = named.named.iter().map(|field| {
+                            let ident = field.ident.as_ref();
+                            let key = ident.get_string();
+                            quote::quote!(.field(#key, #ident))
                         });
 
-                        let tokens: proc_macro2::TokenStream =
-                            itertools::intersperse(fields, quote::quote!(,)).collect();
-
-                        quote::quote!(
-                            Self::#name { #tokens } => { #expr_toks },
-                        )
+                        quote::quote!(f.debug_struct(#name) #(#fields)* .finish())
                     }
                     Fields::Unnamed(tup) => {
-                        let fields = tup
-                            .unnamed
-                            .iter()
-                            .enumerate()
-                            .map(|(i, _)| format_ident!("f{i}").to_token_stream());
-
-                        let tokens: proc_macro2::TokenStream =
-                            itertools::intersperse(fields, quote::quote!(,)).collect();
+                        let fields = (0..tup.unnamed.len()).map(|i| {
+                            let ident = format_ident!("f{i}");
+                            quote::quote!(.field(#ident))
+                        });
 
-                        quote::quote!(
-                            Self::#name ( #tokens ) => { #expr_toks },
-                        )
+                        quote::quote!(f.debug_tuple(#name) #(#fields)* .finish())
                     }
-                    Fields::Unit => {
-                        quote::quote!(
-                                Self::#name => { #expr_toks },
-                        )
-                    }
-                }
-                .into()
-            })
-            .collect()
+                    Fields::Unit => quote::quote!(f.write_str(#name)),
+                };
+
+                let attrs = get_arm_attrs(variant);
+
+                quote::quote!(
+                    #attrs #pattern => { #body },
+                )
+            });
+
+        let mut arms = self.variants_to_arms(wapper);
+        arms.extend(structural_arms);
+        arms
     }
 
     /// The idea behind this method is that it will construct a Map that contains `TraitBound -> Self::$V $fields => Expr`
@@ -218,11 +394,13 @@ impl Subject {
                 }
             };
 
+            let attrs = get_arm_attrs(variant);
+
             impls_iter.for_each(|AbstractExpr { expr, bound, .. }| {
                 arms_map.insert(
                     bound.to_token_stream().to_string(),
                     quote::quote!(
-                        #partial_arm => { #expr },
+                        #attrs #partial_arm => { #expr },
                     ),
                 );
             });
@@ -280,3 +458,72 @@ impl Subject {
         )
     }
 }
+
+/// Returns the attributes of an arm that names `variant`. Its `#[cfg(..)]` attributes are repeated
+/// so that the arm is configured away together with the variant, and a deprecated variant gets
+/// `#[allow(deprecated)]` so that we don't warn about our own use of it.
+pub fn get_arm_attrs(variant: &Variant) -> proc_macro2::TokenStream {
+    let cfgs = variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("cfg"));
+
+    let allow = variant
+        .attrs
+        .iter()
+        .any(|attr| attr.path.is_ident("deprecated"))
+        .then(|| quote::quote!(#[allow(deprecated)]));
+
+    quote::quote!(#(#cfgs)* #allow)
+}
+
+/// Puts the attributes of an arm, see `get_arm_attrs`, on a pattern that names `variant`, e.g.
+/// `Enum::V { .. }`.
+pub fn attach_arm_attrs(pat: &mut Pat, variant: &Variant) {
+    let Ok(attrs) = Attribute::parse_outer.parse2(get_arm_attrs(variant)) else {
+        return;
+    };
+
+    match pat {
+        Pat::Path(pat) => pat.attrs = attrs,
+        Pat::Struct(pat) => pat.attrs = attrs,
+        Pat::TupleStruct(pat) => pat.attrs = attrs,
+        _ => {}
+    }
+}
+
+/// Returns the pattern that binds every field of a variant, e.g. `Self::V { name }` or
+/// `Self::V(f0, f1)`.
+pub fn variant_pattern(variant: &Variant) -> proc_macro2::TokenStream {
+    let name = &variant.ident;
+
+    match &variant.fields {
+        Fields::Named(named) => {
+            let fields = named.named.iter().map(|f| f.ident.as_ref());
+            quote::quote!(Self::#name { #(#fields),* })
+        }
+        Fields::Unnamed(tup) => {
+            let fields = (0..tup.unnamed.len()).map(|i| format_ident!("f{i}"));
+            quote::quote!(Self::#name ( #(#fields),* ))
+        }
+        Fields::Unit => quote::quote!(Self::#name),
+    }
+}
+
+/// Evaluates an integer literal, e.g. `3` or `-1`.
+fn get_integer_value(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => get_integer_value(expr).map(|value: i128| -value),
+        Expr::Paren(ExprParen { expr, .. }) | Expr::Group(ExprGroup { expr, .. }) => {
+            get_integer_value(expr)
+        }
+        _ => None,
+    }
+}
@@ -3,10 +3,11 @@ use syn::{
     braced,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    token, Attribute, DataEnum, Generics, Token, Variant, Visibility, WhereClause,
+    token, Attribute, DataEnum, Fields, FieldsNamed, FieldsUnnamed, Generics, Token, Variant,
+    Visibility, WhereClause,
 };
 
-use super::{AbstractExpr, DiscriminantImpl, Subject};
+use super::{AbstractExpr, DiscriminantImpl, Subject, SubjectKind};
 
 impl Parse for Subject {
     fn parse(input: ParseStream) -> syn::Result<Self> {
@@ -37,6 +38,40 @@ impl Parse for Subject {
                 ident,
                 generics,
                 data,
+                kind: SubjectKind::Enum,
+            })
+        } else if lookahead.peek(Token![struct]) {
+            let struct_token = input.parse::<Token![struct]>()?;
+            let ident = input.parse::<Ident>()?;
+            let generics = input.parse::<Generics>()?;
+            let (where_clause, fields, semi_token) = parse_struct(input)?;
+
+            let generics = Generics {
+                where_clause,
+                ..generics
+            };
+
+            let mut variants = Punctuated::new();
+            variants.push(Variant {
+                attrs: vec![],
+                ident: ident.clone(),
+                fields,
+                discriminant: None,
+            });
+
+            let data = DataEnum {
+                enum_token: Token![enum](struct_token.span),
+                brace_token: token::Brace(ident.span()),
+                variants,
+            };
+
+            Ok(Subject {
+                attrs,
+                vis,
+                ident,
+                generics,
+                data,
+                kind: SubjectKind::Struct(struct_token, semi_token),
             })
         } else {
             Err(lookahead.error())
@@ -79,3 +114,26 @@ pub fn parse_enum(
 
     Ok((where_clause, brace, variants))
 }
+
+/// Named structs place their where clause before the fields, while tuple and unit structs place
+/// it after, followed by a semicolon.
+pub fn parse_struct(
+    input: ParseStream,
+) -> syn::Result<(Option<WhereClause>, Fields, Option<Token![;]>)> {
+    let mut where_clause: Option<WhereClause> = input.parse()?;
+
+    if where_clause.is_none() && input.peek(token::Paren) {
+        let fields = input.parse::<FieldsUnnamed>()?;
+        where_clause = input.parse()?;
+
+        Ok((where_clause, Fields::Unnamed(fields), Some(input.parse()?)))
+    } else if input.peek(token::Brace) {
+        Ok((
+            where_clause,
+            Fields::Named(input.parse::<FieldsNamed>()?),
+            None,
+        ))
+    } else {
+        Ok((where_clause, Fields::Unit, Some(input.parse()?)))
+    }
+}
@@ -1,8 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::ToTokens;
-use syn::{AttrStyle, Attribute};
+use syn::{AttrStyle, Attribute, Fields};
 
-use super::Subject;
+use super::{Subject, SubjectKind};
 
 impl ToTokens for Subject {
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -12,14 +12,41 @@ impl ToTokens for Subject {
             .for_each(|attr| attr.to_tokens(tokens));
 
         self.vis.to_tokens(tokens);
-        self.data.enum_token.to_tokens(tokens);
+
+        let SubjectKind::Struct(struct_token, semi_token) = &self.kind else {
+            self.data.enum_token.to_tokens(tokens);
+            self.ident.to_tokens(tokens);
+            self.generics.to_tokens(tokens);
+
+            self.generics.where_clause.to_tokens(tokens);
+            self.data.brace_token.surround(tokens, |tokens| {
+                self.data.variants.to_tokens(tokens);
+            });
+
+            return;
+        };
+
+        struct_token.to_tokens(tokens);
         self.ident.to_tokens(tokens);
         self.generics.to_tokens(tokens);
 
-        self.generics.where_clause.to_tokens(tokens);
-        self.data.brace_token.surround(tokens, |tokens| {
-            self.data.variants.to_tokens(tokens);
-        });
+        let fields = self.data.variants.iter().map(|variant| &variant.fields);
+
+        for fields in fields {
+            match fields {
+                Fields::Named(named) => {
+                    self.generics.where_clause.to_tokens(tokens);
+                    named.to_tokens(tokens);
+                }
+                Fields::Unnamed(unnamed) => {
+                    unnamed.to_tokens(tokens);
+                    self.generics.where_clause.to_tokens(tokens);
+                }
+                Fields::Unit => self.generics.where_clause.to_tokens(tokens),
+            }
+        }
+
+        semi_token.to_tokens(tokens);
     }
 }
 
@@ -69,6 +69,80 @@ mod utils;
 ///     Bur { num: f32 }
 /// }
 /// ```
+/// Structs are validated like an enum with a single variant, and dispatch directly through
+/// their fields:
+/// ```rust
+/// #[penum( (_, T) where T: ^AsRef<str> )]
+/// struct Tagged(u8, String);
+/// ```
+/// Options can be placed in front of the pattern. Use `impl_display_from` to implement
+/// `Display` through an existing inherent method:
+/// ```rust
+/// #[penum( impl_display_from(render) )]
+/// enum Foo {
+///     Bar(u32),
+///     Bur { num: f32 }
+/// }
+///
+/// impl Foo {
+///     fn render(&self) -> String {
+///         "foo".to_string()
+///     }
+/// }
+/// ```
+/// Use `name_of_index` on a fieldless enum to look up the name of a variant by its index:
+/// ```rust
+/// #[penum( name_of_index )]
+/// enum Color {
+///     Red,
+///     Green,
+/// }
+///
+/// assert_eq!(Some("Green"), Color::name_of_index(1));
+/// assert_eq!(None, Color::name_of_index(2));
+/// ```
+/// Use `via_deref` to dispatch through the deref target of each field, e.g. when a trait is
+/// implemented for `str` but the fields are `String`. Only the dispatched bounds are asserted on
+/// the target, and methods that take `self` by value can't be dispatched this way:
+/// ```rust
+/// #[penum( via_deref, (T) where T: ^AsRef<[u8]> )]
+/// enum Foo {
+///     Bar(String),
+///     Bur(Box<str>),
+/// }
+/// ```
+/// Use `strict` to report every pattern fragment that isn't matched by any variant, e.g. the
+/// `{ name: T }` fragment below is an error:
+/// ```rust,compile_fail
+/// #[penum( strict, (T) | (T, U) | { name: T } )]
+/// enum Foo {
+///     Bar(i32),
+///     Bur(i32, String),
+/// }
+/// ```
+/// Tag an impl with `#[penum]` to let dispatched bounds infer its associated types, e.g. `^Abc`
+/// instead of `^Abc<Input = str>`:
+/// ```rust
+/// #[penum]
+/// trait Abc {
+///     type Input: ?Sized;
+///     fn get(&self) -> &Self::Input;
+/// }
+///
+/// #[penum]
+/// impl Abc for String {
+///     type Input = str;
+///     fn get(&self) -> &str {
+///         self
+///     }
+/// }
+///
+/// #[penum( (T) where T: ^Abc )]
+/// enum Foo {
+///     Bar(String),
+///     Bur(String),
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn penum(attr: TokenStream, input: TokenStream) -> TokenStream {
     services::penum_expand(attr, input)
@@ -76,6 +150,9 @@ pub fn penum(attr: TokenStream, input: TokenStream) -> TokenStream {
 
 /// Use this to express how `ToString` should be implemented through variants descriminant.
 ///
+/// Fields are bound by name, and tuple fields as `f0`, `f1`, .. which means that a string
+/// literal can refer to them either as `{f0}` or positionally as `{0}` or `{}`.
+///
 /// # Example
 ///
 /// ```rust
@@ -83,8 +160,8 @@ pub fn penum(attr: TokenStream, input: TokenStream) -> TokenStream {
 /// enum EnumVariants {
 ///     Variant0 = "Return on match",
 ///     Variant1(i32) = "Return {f0} on match",
-///     Variant2(i32, u32) = stringify!(f0, f1).to_string(),
-///     Variant3 { name: String } = format!("My string {name}"),
+///     Variant2(i32, u32) = "Return {0} and {1} on match",
+///     Variant3 { name: String } = "My string {name}",
 ///     Variant4 { age: u32 } = age.to_string(),
 /// }
 /// let enum_variants = Enum::Variant0;
@@ -116,7 +193,31 @@ pub fn fmt(_: TokenStream, input: TokenStream) -> TokenStream {
     services::fmt_expand(input)
 }
 
+/// Use this to express how `Debug` should be implemented through variants descriminant.
+///
+/// Variants with a discriminant are written like `penum::fmt` would write them, and variants
+/// without one are written like `#[derive(Debug)]` would write them.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::debug]
+/// enum EnumVariants {
+///     Variant0(i32) = "Custom {f0}",
+///     Variant1(i32),
+///     Variant2 { name: String },
+/// }
+/// assert_eq!("Custom 1", format!("{:?}", EnumVariants::Variant0(1)));
+/// assert_eq!("Variant1(2)", format!("{:?}", EnumVariants::Variant1(2)));
+/// ```
+#[proc_macro_attribute]
+pub fn debug(_: TokenStream, input: TokenStream) -> TokenStream {
+    services::debug_expand(input)
+}
+
 /// Use this to express how `Into<T>` should be implemented through variants descriminant.
+/// Several target types can be given at once, e.g. `#[penum::into(u32, u64)]`, which reuses the
+/// same discriminants for each `Into` impl.
 ///
 /// # Example
 ///
@@ -137,6 +238,28 @@ pub fn into(attr: TokenStream, input: TokenStream) -> TokenStream {
     services::into_expand(attr, input)
 }
 
+/// Use this to express how `TryInto<T>` should be implemented through variants descriminant.
+/// Variants without a discriminant return `Err`, with the value of the `error` variant if one
+/// exists. The error type can be given as a second argument, and defaults to `()`.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::try_into(i32, String)]
+/// enum EnumVariants {
+///     Variant0 = 0,
+///     Variant1(i32) = f0,
+///     Variant2,
+///     error = "no number".to_string(),
+/// }
+/// let number: Result<i32, String> = EnumVariants::Variant1(10).try_into();
+/// assert_eq!(Ok(10), number);
+/// ```
+#[proc_macro_attribute]
+pub fn try_into(attr: TokenStream, input: TokenStream) -> TokenStream {
+    services::try_into_expand(attr, input)
+}
+
 /// Use this to express how `Deref<Target = T>` should be implemented through variants descriminant.
 ///
 /// # Example
@@ -158,6 +281,50 @@ pub fn deref(attr: TokenStream, input: TokenStream) -> TokenStream {
     services::deref_expand(attr, input, None)
 }
 
+/// Use this to express how `Deref<Target = T>` and `DerefMut` should be implemented through
+/// variants descriminant. Fields are bound as `&mut` in `deref_mut`, so literals and variants
+/// without a descriminant deref to the field that `default` names, e.g. `default = f0`. Those
+/// that don't have such a field are rejected.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::deref_mut(Vec<i32>)]
+/// enum EnumVariants {
+///     Variant0(Vec<i32>) = f0,
+///     Variant1 { items: Vec<i32> } = items,
+///     Variant2(Vec<i32>),
+///     default = f0,
+/// }
+/// let mut enum_variants = EnumVariants::Variant0(vec![]);
+/// enum_variants.push(10);
+/// assert_eq!(&[10], &enum_variants[..]);
+/// ```
+#[proc_macro_attribute]
+pub fn deref_mut(attr: TokenStream, input: TokenStream) -> TokenStream {
+    services::deref_mut_expand(attr, input, None)
+}
+
+/// Use this to express that you want the enum to implement `Deref<Target = str>`, `DerefMut`,
+/// `AsRef<str>` and `AsMut<str>`, including `as_str()` and `as_mut_str()`.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::as_mut_str]
+/// enum EnumVariants {
+///     Variant0(String) = f0,
+///     Variant1 { name: String } = name,
+/// }
+/// let mut enum_variants = EnumVariants::Variant0("hello".to_string());
+/// enum_variants.make_ascii_uppercase();
+/// assert_eq!("HELLO", enum_variants.as_str());
+/// ```
+#[proc_macro_attribute]
+pub fn as_mut_str(_: TokenStream, input: TokenStream) -> TokenStream {
+    services::as_mut_str(input)
+}
+
 /// Use this to express that you want the enum to implement `deref() -> &str`, `as_str()` and `as_ref()`;
 ///
 /// # Example
@@ -181,6 +348,138 @@ pub fn static_str(_: TokenStream, input: TokenStream) -> TokenStream {
     services::static_str(input)
 }
 
+/// Use this to implement `FromStr` as the inverse of `static_str`, where each variant is parsed
+/// from its string discriminant. Only unit variants with a string literal discriminant are
+/// supported. Unknown strings return `Err`, with the value of the `error` variant if one exists.
+/// The error type can be given as an argument, and defaults to `()`.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::from_str(String)]
+/// enum Color {
+///     Red = "red",
+///     Green = "green",
+///     error = "unknown color".to_string(),
+/// }
+/// assert!(matches!("red".parse(), Ok(Color::Red)));
+/// assert!("blue".parse::<Color>().is_err());
+/// ```
+#[proc_macro_attribute]
+pub fn from_str(attr: TokenStream, input: TokenStream) -> TokenStream {
+    services::from_str_expand(attr, input)
+}
+
+/// Use this to implement `Default` by constructing the first variant, where each field is
+/// constructed through `Default::default()`.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::default]
+/// enum EnumVariants {
+///     Variant0(i32, String),
+///     Variant1 { name: String },
+///     Variant2,
+/// }
+/// assert!(matches!(EnumVariants::default(), EnumVariants::Variant0(0, _)));
+/// ```
+#[proc_macro_attribute]
+pub fn default(_: TokenStream, input: TokenStream) -> TokenStream {
+    services::default_expand(input)
+}
+
+/// Use this to look up the variants of an enum, through `VARIANT_COUNT`, `VARIANT_NAMES` and
+/// `variant_name()`. The enum itself is left untouched.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum::variant_count]
+/// enum EnumVariants {
+///     Variant0(i32, String),
+///     Variant1 { name: String },
+///     Variant2,
+/// }
+/// assert_eq!(3, EnumVariants::VARIANT_COUNT);
+/// assert_eq!(&["Variant0", "Variant1", "Variant2"], EnumVariants::VARIANT_NAMES);
+/// assert_eq!("Variant2", EnumVariants::Variant2.variant_name());
+/// ```
+#[proc_macro_attribute]
+pub fn variant_count(_: TokenStream, input: TokenStream) -> TokenStream {
+    services::variant_count_expand(input)
+}
+
+/// Use this to declare a pattern once and share it between several enums through
+/// `#[penum(use = Name)]`. The pattern has to be declared before the enums that use it.
+///
+/// # Example
+///
+/// ```rust
+/// penum::pattern!(Pair = (T, U) where T: Copy);
+///
+/// #[penum( use = Pair )]
+/// enum Foo {
+///     Bar(i32, String),
+/// }
+///
+/// #[penum( strict, use = Pair )]
+/// enum Baz {
+///     Bor(u8, u8),
+/// }
+/// ```
+#[proc_macro]
+pub fn pattern(input: TokenStream) -> TokenStream {
+    services::pattern_expand(input)
+}
+
+/// Use this to declare a named shape that enums can conform to through `#[penum(Name)]`, or
+/// compose with other fragments, e.g. `#[penum(Name | _)]`. Shapes are kept apart from the
+/// patterns of `penum::pattern!`, which are only used through `use = Name`.
+///
+/// # Example
+///
+/// ```rust
+/// penum::shape! { Pair = (T, T) where T: PartialEq }
+///
+/// #[penum( Pair )]
+/// enum Foo {
+///     Bar(i32, i32),
+/// }
+///
+/// #[penum( strict, Pair )]
+/// enum Baz {
+///     Bor(String, String),
+/// }
+/// ```
+#[proc_macro]
+pub fn shape(input: TokenStream) -> TokenStream {
+    services::shape_expand(input)
+}
+
+/// Use this to check that a family of `#[penum(..)]` tagged enums don't claim the same shape.
+/// The enums have to be declared before the family.
+///
+/// # Example
+///
+/// ```rust
+/// #[penum( (T) where T: Copy )]
+/// enum Single {
+///     Bar(i32),
+/// }
+///
+/// #[penum( (T, U) where T: Copy, U: Copy )]
+/// enum Pair {
+///     Bar(i32, u8),
+/// }
+///
+/// penum::family!(Single, Pair);
+/// ```
+#[proc_macro]
+pub fn family(input: TokenStream) -> TokenStream {
+    services::family_expand(input)
+}
+
 /// Use this when you want to be able to associate a ...
 /// UNDER DEVELOPMENT
 /// # Example
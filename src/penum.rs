@@ -1,38 +1,95 @@
 use std::borrow::Borrow;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 
+use quote::format_ident;
 use quote::ToTokens;
 
 use syn::punctuated::Punctuated;
 use syn::token::Add;
 use syn::token::Comma;
+use syn::Field;
+use syn::Fields;
 use syn::Ident;
 use syn::ItemImpl;
+use syn::Pat;
 
 use syn::parse_quote;
 use syn::spanned::Spanned;
-use syn::Error;
+use syn::visit_mut::VisitMut;
+use syn::TraitBound as SynTraitBound;
 use syn::Type;
 use syn::TypeParamBound;
 
+use crate::factory::attach_arm_attrs;
+use crate::factory::get_arm_attrs;
+use crate::factory::ArityMismatch;
 use crate::factory::Comparable;
+use crate::factory::FieldMismatch;
+use crate::factory::PatComposite;
+use crate::factory::PatFrag;
 use crate::factory::PenumExpr;
+use crate::factory::PenumOption;
+use crate::factory::PredicateType;
 use crate::factory::Subject;
+use crate::factory::WhereClause;
 use crate::factory::WherePredicate;
 
+use crate::dispatch::MonomorphizeTraitBound;
+use crate::dispatch::RemoveBoundBindings;
 use crate::dispatch::VariantSig;
 use crate::error::Diagnostic;
 
+use crate::utils::almost_matched;
+use crate::utils::assoc_const_not_resolved;
+use crate::utils::assoc_type_ambiguous;
+use crate::utils::closest_pattern_mismatch;
 use crate::utils::create_unique_ident;
-use crate::utils::lifetime_not_permitted;
+use crate::utils::dedup_generic_bounds;
+use crate::utils::discriminant_already_used;
+use crate::utils::discriminant_out_of_range;
+use crate::utils::find_generic_path;
+use crate::utils::find_similar_ident;
+use crate::utils::fragment_limit_exceeded;
+use crate::utils::generic_never_bound;
+use crate::utils::generic_not_in_fragment;
+use crate::utils::generic_not_in_pattern;
+use crate::utils::generic_not_uniform;
+use crate::utils::get_nested_type_ids;
+use crate::utils::get_projection;
+use crate::utils::get_referenced_generics;
+use crate::utils::in_fragment;
+use crate::utils::lifetime_not_declared;
 use crate::utils::maybe_bounds_not_permitted;
+use crate::utils::method_not_derefable;
+use crate::utils::method_not_forwardable;
+use crate::utils::missing_named_field;
+use crate::utils::negated_type_matched;
+use crate::utils::nested_projection_not_supported;
 use crate::utils::no_match_found;
+use crate::utils::pattern_never_matched;
+use crate::utils::pattern_unreachable;
+use crate::utils::projection_trait_ambiguous;
+use crate::utils::set_subject_generics;
+use crate::utils::too_few_fields;
+use crate::utils::too_many_fields;
+use crate::utils::type_mismatch;
+use crate::utils::unexpected_named_field;
+use crate::utils::unify_types;
+use crate::utils::variant_not_found;
+use crate::utils::variant_rejected;
+use crate::utils::Projection;
+use crate::utils::QualifyProjections;
 use crate::utils::Stringify;
 use crate::utils::TraitBoundUtils;
+use crate::utils::TypeAliases;
 use crate::utils::TypeUtils;
+use crate::utils::CLOSEST_PATTERN_DISTANCE;
 
 use crate::polym::PolymorphicMap;
 use crate::polym::UniqueHashId;
@@ -50,7 +107,7 @@ pub struct Penum<State = Unassembled> {
     /// A Penum expression consists of one or more patterns, and an optional WhereClause.
     expr: PenumExpr,
 
-    /// The enum (or ADT in the future) that we will read and specialize.
+    /// The enum (or struct) that we will read and specialize.
     subject: Subject,
 
     /// A simple macro diagnostic struct that we use to append compiler errors with span information.
@@ -59,6 +116,10 @@ pub struct Penum<State = Unassembled> {
     /// I use this to map generics to concrete types that I then can use during substitution stage.
     types: PolyMap,
 
+    /// The concrete type of each pattern generic, per variant. Used to substitute generics that
+    /// appear inside of bounds, e.g. `T` in `U: Into<T>`, with a type from the same variant.
+    variant_generics: Vec<BTreeMap<Ident, Type>>,
+
     /// Contains all the impls that we've managed to construct.
     impls: Vec<ItemImpl>,
 
@@ -76,11 +137,18 @@ impl Penum<Unassembled> {
             // NOTE: I could extract these fields into another struct.
             error: Default::default(),
             types: Default::default(),
+            variant_generics: Default::default(),
             impls: Default::default(),
             _marker: Default::default(),
         }
     }
 
+    // NOTE: This is only used for unit tests
+    #[cfg(test)]
+    pub fn from_tokens(attr: TokenStream2, input: TokenStream2) -> syn::Result<Self> {
+        Ok(Self::new(syn::parse2(attr)?, syn::parse2(input)?))
+    }
+
     fn transmute_to_assembled(self) -> Penum<Assembled> {
         // SAFETY: Transmuting self into self with a different zero-sized marker.
         // Since State is a PhantomData, this is safe.
@@ -93,6 +161,25 @@ impl Penum<Unassembled> {
         // - Argument usually refers to unnamed variants
         // - Parameter usually refers to penum patterns (unnamed/named).
 
+        // With `subject_generics`, pattern generics named like a type or const parameter of the
+        // subject, e.g. `T` given `enum E<T>`, stand for that parameter instead of being bound per
+        // variant.
+        set_subject_generics(if self.expr.binds_subject_generics() {
+            self.subject
+                .generics
+                .type_params()
+                .map(|param| param.ident.clone())
+                .chain(
+                    self.subject
+                        .generics
+                        .const_params()
+                        .map(|param| param.ident.clone()),
+                )
+                .collect()
+        } else {
+            vec![]
+        });
+
         if self.subject.get_variants().is_empty() {
             self.error.extend(
                 self.subject.ident.span(),
@@ -102,11 +189,86 @@ impl Penum<Unassembled> {
             return self.transmute_to_assembled();
         }
 
-        let enum_ident = self.subject.ident.borrow();
+        let fragment_limit = self.expr.get_fragment_limit();
+
+        if let Some(fragment) = self.expr.pattern.get(fragment_limit) {
+            self.error.warn(
+                fragment.span(),
+                fragment_limit_exceeded(self.expr.pattern.len(), fragment_limit),
+            );
+        }
+
         // Expecting failure like `variant doesn't match shape`,
         // hence pre-calling.
         let pattern_fmt = self.expr.pattern_to_string();
 
+        // Type aliases, e.g. `alias(Bytes = Vec<u8>)`, are resolved up front in the pattern and in
+        // the fields of the subject, so that types can be compared as they are. The fields are
+        // spelled the way they were written again once every variant has been checked.
+        let aliases = TypeAliases::new(self.expr.get_type_aliases()).unwrap_or_else(|error| {
+            self.error.extend(error.span(), error);
+            TypeAliases::default()
+        });
+
+        self.expr.resolve_type_aliases(&aliases);
+        let aliased = self.subject.resolve_type_aliases(&aliases);
+
+        let enum_ident = self.subject.ident.borrow();
+
+        // A fragment can be limited to variants by name, e.g. `Foo(T, ..)` or `Net*(T)`, which
+        // has to match at least one of them.
+        for selector in self.expr.pattern.iter().filter_map(PatFrag::get_selector) {
+            if !self
+                .subject
+                .get_variants()
+                .iter()
+                .any(|variant| selector.matches(&variant.ident))
+            {
+                self.error
+                    .extend(selector.span(), variant_not_found(selector, enum_ident));
+            }
+        }
+
+        // A generic of the clause that no fragment uses is never mapped to a type, so its bounds
+        // would silently be dropped. It's most likely a typo, e.g. `Y` instead of `U`, but just
+        // like a generic that no variant binds, it's only an error when `strict`.
+        let pattern_generics = self.expr.get_generic_idents();
+        let clause_preds = self
+            .expr
+            .clause
+            .iter()
+            .flat_map(|clause| clause.predicates.iter())
+            .filter_map(|pred| match pred {
+                WherePredicate::Type(pred) => Some(pred),
+                _ => None,
+            });
+
+        // Only pattern generics without bounds of their own are suggested, since those are the
+        // ones the typo most likely meant.
+        let unbounded_generics: Vec<&Ident> = pattern_generics
+            .iter()
+            .filter(|generic| {
+                !clause_preds.clone().any(|pred| {
+                    pred.bounded_ty.is_generic() && pred.bounded_ty.get_generic_ident() == **generic
+                })
+            })
+            .collect();
+
+        for pred in clause_preds {
+            if let Some(generic) = pred
+                .bounded_ty
+                .is_generic()
+                .then(|| pred.bounded_ty.get_generic_ident())
+                .filter(|generic| !pattern_generics.contains(generic))
+            {
+                self.report_not_in_pattern(
+                    &pred.bounded_ty,
+                    &generic,
+                    find_similar_ident(&generic, unbounded_generics.iter().copied()),
+                );
+            }
+        }
+
         // The point is that as we check for equality, we also do
         // impl assertions by extending the `subjects` where clause.
         // This is something that we might want to change in the
@@ -125,76 +287,246 @@ impl Penum<Unassembled> {
         // self.expr.has_clause(); Turn into iterator instead?
         let mut opt_blueprints = self.expr.get_blueprints_map(self.error.borrow());
 
+        // Patterns of the variants without fields, e.g. `Enum::V`.
+        let mut fieldless: Vec<Pat> = vec![];
+
+        // Dispatch through the deref target of each field instead of the field itself.
+        let via_deref = self.expr.has_via_deref();
+
+        // A struct is validated like a single variant, but dispatched through its fields.
+        let is_struct = self.subject.is_struct();
+
+        // Generics with a default type, e.g. `(T = i32)`, and the placeholder fields that have
+        // been given that type, e.g. `V(_)`.
+        let defaults = self.expr.get_generic_defaults();
+        let mut defaulted: Vec<(Ident, usize, Type)> = vec![];
+
+        // In `uniform` mode, a generic has to be bound to the same type in every variant, so the
+        // first type each generic is bound to is kept together with the variant it was bound in.
+        let uniform = self.expr.is_uniform();
+        let mut uniform_bindings: BTreeMap<Ident, (Type, Ident)> = BTreeMap::new();
+
+        // The discriminant values are only checked if a fragment limits them, e.g. `V = 0..=255`,
+        // in which case two variants can't share a value either.
+        let discriminants = self.subject.get_discriminant_values();
+
+        if self
+            .expr
+            .pattern
+            .iter()
+            .any(|fragment| fragment.discriminant.is_some())
+        {
+            let mut used: BTreeMap<i128, &Ident> = BTreeMap::new();
+
+            for (variant_ident, value) in discriminants.iter() {
+                let Some((value, span)) = value else {
+                    continue;
+                };
+
+                match used.get(value) {
+                    Some(other) => self.error.extend(
+                        *span,
+                        discriminant_already_used(variant_ident, *value, other),
+                    ),
+                    None => {
+                        used.insert(*value, *variant_ident);
+                    }
+                }
+            }
+        }
+
         // For each variant:
         // 1. Validate its shape by comparing discriminant and
         //    unit/tuple/struct arity. (OUTER)
         //    - Failure: add a "no_match_found" error and continue
         //      to next variant.
         // 2. Validate each parameter    ...continue... (INNER)
-        for (variant_ident, comparable_item) in self.subject.comparable_fields_iter() {
-            // FIXME: This only affects concrete types.. but
-            //  `.compare(..)` should return a list of matches
-            //  instead of just the first match it finds.
-            //
-            //  # Uni-matcher -> Multi-matcher
-            //  Currently, we can end up returning a pattern that matches in shape, but not
-            //  in structure, even though another pattern could satisfy our variant. In a case
-            //  like the one below, we have a "catch all" variadic.
+        for (variant_ident, comparable_item, field_ids) in self.subject.comparable_fields_iter() {
+            // # Multi-matcher
+            //  Several patterns can match a variant in shape, but not all of them in structure.
+            //  In a case like the one below, the first fragment would fail while the "catch all"
+            //  variadic satisfies our variant.
             //
             //  e.g. (i32, ..) | (..) => V1(String, i32), V2(String, String)
-            //                              ^^^^^^           ^^^^^^
-            //                              |                |
-            //                              `Found 'String' but expected 'i32'`
             //
-            //  Because the first pattern fragment contains a concrete type, it should be possible
-            //  mark the error as temporary and then check for other pattern matches. Note, the first
-            //  error should always be the default one.
+            //  So every pair that matches in shape is tried, and we pick the first one that also
+            //  matches in structure. If none does, the pair with the fewest mismatched fields is
+            //  the one we report errors for, e.g. `(String, String, ..) | (i32, i32, i32)` given
+            //  `V(u8, i32, i32)` only reports `u8`.
             //
-            //  Given our pattern above, `(..)` should be a fallback pattern.
-            //
-            //  Should we allow concrete types with trait bound at argument position?
+            //  Concrete types can carry inline bounds at argument position, which are asserted
+            //  for that type, and dispatched if they have a caret.
             //  e.g.
             //    (i32: Trait,  ..) | (..)
             //    (i32: ^Trait, ..) | (..)
             //
-            //  For future reference! This should help with dispach inference.
-            //
             //  # "catch-all" syntax
-            //  Given the example above, if we were to play with it a little, we could end up with
-            //  something like this:
-            //  `(i32, ..) | _` that translate to `(i32, ..) | (..) | {..}`
+            //  A bare `_` matches every variant in shape, and since it's always satisfied in
+            //  structure it only catches the variants that earlier fragments reject, e.g.
+            //  `(i32, ..) | _` behaves like `(i32, ..) | (..) | {..}`. Unit variants are caught as
+            //  well, so that `impl Trait for Type` (`_ where Type: ^Trait`) works for any enum.
             //
             //  Maybe it's something that would be worth having considering something like this:
             //  `_ where String: ^AsRef<str>`
+            //
+            //  # Negated fragments
+            //  A fragment with a `!` in front, e.g. `!(String, ..)`, is never matched. Instead, a
+            //  variant that it would match in both shape and structure is rejected.
+
+            if let Some(position) = comparable_pats.find_rejection(&comparable_item) {
+                self.error.extend(
+                    variant_ident.span(),
+                    variant_rejected(variant_ident, &self.expr.pattern[position]),
+                );
+                continue;
+            }
 
             // 1. Check if we match in `shape`
-            let Some(matched_pair) = comparable_pats.compare(&comparable_item) else {
-                self.report_invalid_shape(&comparable_item, variant_ident, &pattern_fmt);
+            let matched_pairs = comparable_pats.compare(&comparable_item);
+
+            let Some(matched_pair) = matched_pairs
+                .iter()
+                .min_by_key(|pair| pair.count_mismatches())
+            else {
+                let arity_mismatch = comparable_pats.find_arity_mismatch(&comparable_item);
+                let closest =
+                    comparable_pats.find_closest(&comparable_item, CLOSEST_PATTERN_DISTANCE);
+                let near_arity = comparable_pats.find_near_arity(&comparable_item);
+                self.report_invalid_shape(
+                    &comparable_item,
+                    variant_ident,
+                    &pattern_fmt,
+                    arity_mismatch,
+                    closest,
+                    near_arity,
+                );
                 continue;
             };
 
-            // No support for empty unit iter, yet...
-            // NOTE: Make sure to handle composite::unit iterator before removing this
-            if matched_pair.as_composite().is_unit() {
-                continue;
+            comparable_pats.mark_matched(matched_pair);
+
+            // With several fragments, errors about the structure of the variant name the fragment
+            // that it matched in shape, e.g. "in fragment 2 `(i32, T)`".
+            let fragment = self
+                .expr
+                .pattern
+                .iter()
+                .position(|frag| std::ptr::eq(&frag.group, matched_pair.as_composite()))
+                .filter(|_| self.expr.pattern.len() > 1)
+                .map(|index| (index + 1, self.expr.pattern[index].to_token_stream()));
+            let name_fragment = |error: String| match &fragment {
+                Some((position, tokens)) => in_fragment(error, *position, tokens),
+                None => error,
+            };
+
+            // The arms of a variant behind `#[cfg(..)]` are configured away together with it.
+            let variant = self
+                .subject
+                .get_variants()
+                .iter()
+                .find(|variant| variant.ident == *variant_ident);
+            let arm_attrs = variant.map(get_arm_attrs).unwrap_or_default();
+
+            // The fragment can limit the discriminant of the variant, e.g. `V = 0..=255`.
+            if let Some(range) = matched_pair.get_discriminant() {
+                if let Some((value, span)) = discriminants
+                    .iter()
+                    .find(|(ident, _)| *ident == variant_ident)
+                    .and_then(|(_, value)| *value)
+                    .filter(|(value, _)| !range.contains(*value))
+                {
+                    self.error.extend(
+                        span,
+                        name_fragment(discriminant_out_of_range(
+                            variant_ident,
+                            value,
+                            &range.tokens,
+                        )),
+                    );
+                }
+            }
+
+            // Fieldless variants have nothing to dispatch through, so they get an arm that returns
+            // the default value of each method instead, e.g. `Enum::V => ""`.
+            if comparable_item.inner.is_empty() {
+                let mut pat = match comparable_item.inner {
+                    Fields::Unit => parse_quote!(#enum_ident::#variant_ident),
+                    Fields::Unnamed(_) => parse_quote!(#enum_ident::#variant_ident()),
+                    Fields::Named(_) => parse_quote!(#enum_ident::#variant_ident {}),
+                };
+
+                if let Some(variant) = variant {
+                    attach_arm_attrs(&mut pat, variant);
+                }
+
+                fieldless.push(pat);
+            }
+
+            // Named fields are validated by name, so a missing or an unexpected field is
+            // reported on its own instead of as a type mismatch.
+            for mismatch in matched_pair.find_name_mismatches() {
+                match mismatch {
+                    FieldMismatch::Missing(_, param) => {
+                        if let Some(ident) =
+                            param.get_field().and_then(|field| field.ident.as_ref())
+                        {
+                            self.error.extend(
+                                comparable_item.inner.span(),
+                                name_fragment(missing_named_field(ident)),
+                            );
+                        }
+                    }
+                    FieldMismatch::Unexpected(_, field) => {
+                        if let Some(ident) = field.ident.as_ref() {
+                            self.error
+                                .extend(ident.span(), name_fragment(unexpected_named_field(ident)));
+                        }
+                    }
+                    FieldMismatch::Type(..) => {}
+                }
             }
 
             let arity = comparable_item.inner.len();
+            let mut generics = BTreeMap::new();
 
             // 2. Check if we match in `structure`. (We are naively
             // always expecting to never have infixed variadics)
-            for (field_index, (param_pattern, field_item)) in matched_pair.zip().enumerate() {
-                let item_ty_unique = field_item.ty.get_unique_id();
+            for (field_index, param_pattern, field_item) in matched_pair.zip() {
+                // A placeholder field of a generic with a default type, e.g. `V(_)` given
+                // `(T = i32)`, is read as the default type, and replaced by it once every variant
+                // has been checked.
+                let field_item = match param_pattern
+                    .get_field()
+                    .filter(|pat_field| pat_field.ty.is_generic() && field_item.ty.is_placeholder())
+                    .and_then(|pat_field| defaults.get(&pat_field.ty.get_generic_ident()))
+                {
+                    Some(&default) => {
+                        defaulted.push((variant_ident.clone(), field_index, default.clone()));
+                        Cow::Owned(Field {
+                            ty: default.clone(),
+                            ..field_item.clone()
+                        })
+                    }
+                    None => Cow::Borrowed(field_item),
+                };
+
+                let item_ty_unique = match &field_item {
+                    Cow::Borrowed(_) => field_ids[field_index].clone(),
+                    Cow::Owned(field_item) => field_item.ty.get_unique_id(),
+                };
+
+                let variant_sig =
+                    VariantSig::new(enum_ident, variant_ident, &field_item, field_index, arity)
+                        .via_deref(via_deref)
+                        .for_struct(is_struct)
+                        .with_attrs(arm_attrs.clone());
 
                 if param_pattern.is_infer() {
                     opt_blueprints.as_mut().map(|blueprints| {
-                        blueprints.find_and_attach_variant_sig(
-                            enum_ident,
-                            variant_ident,
-                            field_item,
-                            field_index,
-                            arity,
+                        blueprints.find_and_attach(
                             &item_ty_unique,
+                            &variant_sig,
+                            Some(&item_ty_unique),
                         );
                     });
 
@@ -204,17 +536,63 @@ impl Penum<Unassembled> {
                     continue;
                 }
 
-                // If we cannot desctructure a pattern field, then it must be variadic.
-                //
-                // NOTE: This causes certain bugs (see tests/test-concrete-bound.rs)
+                // If we cannot desctructure a pattern field, then it must be a range. We keep going
+                // so that every mismatched field of the variant is reported at once.
                 let Some(pat_field) = param_pattern.get_field() else {
-                    break;
+                    continue;
                 };
 
+                // Negated fields accept any type but their own, and are dispatched through the
+                // type of the item just like placeholders.
+                if param_pattern.is_negated() {
+                    if item_ty_unique == pat_field.ty.get_unique_id() {
+                        self.error.extend(
+                            field_item.ty.span(),
+                            name_fragment(negated_type_matched(&pat_field.ty)),
+                        );
+                        continue;
+                    }
+
+                    if let Some(blueprints) = opt_blueprints.as_mut() {
+                        blueprints.find_and_attach(
+                            &item_ty_unique,
+                            &variant_sig,
+                            Some(&item_ty_unique),
+                        );
+                    }
+
+                    self.types
+                        .polymap_insert(item_ty_unique.clone(), item_ty_unique);
+
+                    continue;
+                }
+
+                // Inline bounds, e.g. `(i32: Trait, ..)`, are asserted just like the ones in our
+                // where clause. Placeholders are asserted for the matched type instead.
+                if let Some(bounds) = param_pattern.get_bounds() {
+                    let ty = if pat_field.ty.is_placeholder() {
+                        &field_item.ty
+                    } else {
+                        &pat_field.ty
+                    };
+
+                    predicates.push(parse_quote!(#ty: #bounds));
+                }
+
                 // FIXME: Remove this, or refactor it. Remember that there's
                 // tests that needs to be removed/changed.
                 if let Some(ty_impl_trait) = pat_field.ty.get_type_impl_trait() {
-                    let bounds = &ty_impl_trait.bounds;
+                    // `?Sized` relaxes the generic that stands for the impl, e.g. `str` in
+                    // `(impl ?Sized + AsRef<str>)`, but concrete types can't be relaxed in our
+                    // where clause, and nothing is asserted of them being sized anyway.
+                    let bounds = &ty_impl_trait
+                        .bounds
+                        .iter()
+                        .filter(|bound| {
+                            !matches!(bound, TypeParamBound::Trait(tb) if tb.is_maybe_sized())
+                        })
+                        .cloned()
+                        .collect::<Punctuated<TypeParamBound, Add>>();
 
                     self.create_impl_string(bounds).map(|impl_string| {
                         let unique_impl_id =
@@ -238,9 +616,6 @@ impl Penum<Unassembled> {
 
                 let pat_ty_unique = pat_field.ty.get_unique_id();
 
-                let variant_sig =
-                    VariantSig::new(enum_ident, variant_ident, field_item, field_index, arity);
-
                 // Check if it's a generic or concrete type
                 // - We only accept `_|[A-Z][A-Z0-9]*` as generics.
                 //
@@ -249,6 +624,21 @@ impl Penum<Unassembled> {
                 let pat_field_ty_is_generic = pat_field.ty.is_generic();
                 let item_ty_and_pat_ty_is_equal = item_ty_unique == pat_ty_unique;
 
+                if pat_field_ty_is_generic {
+                    let generic = pat_field.ty.get_generic_ident();
+
+                    if uniform {
+                        self.check_uniform_binding(
+                            &mut uniform_bindings,
+                            &generic,
+                            &field_item.ty,
+                            variant_ident,
+                        );
+                    }
+
+                    generics.insert(generic, field_item.ty.clone());
+                }
+
                 if pat_field_ty_is_generic && item_ty_and_pat_ty_is_equal {
                     opt_blueprints.as_mut().map(|blueprints| {
                         blueprints.find_and_attach(
@@ -281,6 +671,59 @@ impl Penum<Unassembled> {
                     continue;
                 }
 
+                // Containers of pattern generics, e.g. `Vec<T>`, bind each generic to the matching
+                // part of the item type, so that `T` is asserted as `i32` given `Vec<i32>`. The
+                // lifetimes of references are bound as well, so `&'a str` matches `&'b str`.
+                let mut bindings = BTreeMap::new();
+
+                if !item_ty_and_pat_ty_is_equal
+                    && !pat_field.ty.is_placeholder()
+                    && unify_types(&pat_field.ty, &field_item.ty, &mut bindings)
+                {
+                    if let Some(blueprints) = opt_blueprints.as_mut() {
+                        blueprints.find_and_attach(
+                            &pat_ty_unique,
+                            &variant_sig,
+                            Some(&item_ty_unique),
+                        );
+                    }
+
+                    for (ident, ty) in bindings {
+                        let generic: Type = parse_quote!(#ident);
+                        let generic_unique = generic.get_unique_id();
+                        let ty_unique = ty.get_unique_id();
+
+                        // Generics behind references, e.g. `&'a T`, or inside of tuples, e.g.
+                        // `(T, U)`, can be dispatched through by destructuring the field.
+                        if let Some(path) = find_generic_path(&pat_field.ty, &ident) {
+                            if let Some(blueprints) = opt_blueprints.as_mut() {
+                                blueprints.find_and_attach(
+                                    &generic_unique,
+                                    &variant_sig.clone().project(&path),
+                                    Some(&ty_unique),
+                                );
+                            }
+                        }
+
+                        self.types.polymap_insert(generic_unique, ty_unique);
+
+                        if uniform {
+                            self.check_uniform_binding(
+                                &mut uniform_bindings,
+                                &ident,
+                                &ty,
+                                variant_ident,
+                            );
+                        }
+
+                        generics.insert(ident, ty);
+                    }
+
+                    self.types.polymap_insert(pat_ty_unique, item_ty_unique);
+
+                    continue;
+                }
+
                 // is concrete type equal to concrete type
                 if item_ty_and_pat_ty_is_equal {
                     // 3. Dispachable list
@@ -319,37 +762,182 @@ impl Penum<Unassembled> {
 
                 // ELSE DO THIS:
 
-                // TODO: Refactor into TypeId instead.
-                let item_ty_string = field_item.ty.get_string();
-                // NOTE: This string only contains the Ident, so any generic parameters will
-                // be discarded.
-                let pat_ty_string = pat_field.ty.get_string();
-
+                // The whole types are printed, including generic arguments and `dyn` bounds.
                 self.error.extend_spanned(
                     &field_item.ty,
-                    format!("Found `{item_ty_string}` but expected `{pat_ty_string}`."),
+                    name_fragment(type_mismatch(&field_item.ty, &pat_field.ty)),
                 );
             }
+
+            // The bounds of a variadic, e.g. `(String, ..: Copy)`, are asserted for every field
+            // that it covers.
+            if let Some((residual, fields)) = matched_pair.get_residual() {
+                if let Some(bounds) = residual.get_bounds() {
+                    for field in fields {
+                        let ty = &field.ty;
+                        predicates.push(parse_quote!(#ty: #bounds));
+                    }
+                }
+            }
+
+            // The clause of a fragment, e.g. `(T) where T: Copy | (U)`, is only asserted for the
+            // variants it matches, with the generics substituted with the types of the variant.
+            if let Some(clause) = matched_pair.get_clause() {
+                let polymap = defaults
+                    .iter()
+                    .map(|(k, v)| (k.clone(), *v))
+                    .chain(generics.iter().map(|(k, v)| (k.clone(), v)))
+                    .collect();
+
+                for predicate in clause.predicates.iter() {
+                    let WherePredicate::Type(pred) = predicate else {
+                        predicates.push(parse_quote!(#predicate));
+                        continue;
+                    };
+
+                    if let Some(generic) = pred
+                        .bounded_ty
+                        .is_generic()
+                        .then(|| pred.bounded_ty.get_generic_ident())
+                        .filter(|generic| {
+                            !generics.contains_key(generic) && !defaults.contains_key(generic)
+                        })
+                    {
+                        self.error.extend(
+                            pred.bounded_ty.span(),
+                            generic_not_in_fragment(&generic, variant_ident),
+                        );
+                        continue;
+                    }
+
+                    let mut ty = pred.bounded_ty.clone();
+                    let bounds = &pred.bounds;
+                    let mut bounds: Punctuated<TypeParamBound, Add> = parse_quote!(#bounds);
+
+                    MonomorphizeTraitBound(&polymap).visit_type_mut(&mut ty);
+                    for bound in bounds.iter_mut() {
+                        MonomorphizeTraitBound(&polymap).visit_type_param_bound_mut(bound);
+                    }
+
+                    predicates.push(parse_quote!(#ty: #bounds));
+                }
+            }
+
+            if !generics.is_empty() {
+                self.variant_generics.push(generics);
+            }
         }
 
+        for (variant_ident, field_index, default) in defaulted {
+            if let Some(field) = self
+                .subject
+                .data
+                .variants
+                .iter_mut()
+                .find(|variant| variant.ident == variant_ident)
+                .and_then(|variant| variant.fields.iter_mut().nth(field_index))
+            {
+                field.ty = default;
+            }
+        }
+
+        for (variant_index, field_index, ty) in aliased {
+            if let Some(field) = self
+                .subject
+                .data
+                .variants
+                .iter_mut()
+                .nth(variant_index)
+                .and_then(|variant| variant.fields.iter_mut().nth(field_index))
+            {
+                field.ty = ty;
+            }
+        }
+
+        if self.expr.is_strict() {
+            // A fragment that comes after one that matches everything it does can never be
+            // selected, e.g. `(i32, ..)` in `(..) | (i32, ..)`, so we say which one shadows it.
+            let unreachable = comparable_pats.find_unreachable();
+
+            for position in comparable_pats.unmatched() {
+                let fragment = &self.expr.pattern[position];
+                let message = match unreachable.iter().find(|(dead, _)| *dead == position) {
+                    Some((_, earlier)) => {
+                        pattern_unreachable(fragment, &self.expr.pattern[*earlier])
+                    }
+                    None => pattern_never_matched(fragment),
+                };
+
+                self.error.extend(fragment.span(), message);
+            }
+        }
+
+        dedup_generic_bounds(&mut self.subject.generics);
+
         // Assemble all our impl statements
         opt_blueprints.map(|blueprints| {
-            let (impl_generics, ty_generics, where_clause) =
-                &self.subject.generics.split_for_impl();
+            let variants: Vec<Pat> = self
+                .subject
+                .get_variants()
+                .iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let mut pat = parse_quote!(#enum_ident::#variant_ident { .. });
+                    attach_arm_attrs(&mut pat, variant);
+                    pat
+                })
+                .collect();
 
             blueprints.for_each_blueprint(|blueprint| {
                 let trait_path = blueprint.get_sanatized_impl_path();
-                let assoc_methods = blueprint.get_associated_methods();
+                let generics = blueprint.get_impl_generics(&self.subject.generics);
+                let (impl_generics, _, where_clause) = generics.split_for_impl();
+                let (_, ty_generics, _) = self.subject.generics.split_for_impl();
 
-                let assoc_types = blueprint.get_mapped_bindings().map(|bind| {
-                    bind.iter()
-                        .map(|b| b.to_token_stream())
-                        .collect::<TokenStream2>()
+                for sig in blueprint.get_unforwardable_methods() {
+                    self.error.extend(
+                        blueprint.bound.span(),
+                        method_not_forwardable(&blueprint.bound.get_ident(), sig),
+                    );
+                }
+
+                if via_deref {
+                    for sig in blueprint.get_by_value_methods() {
+                        self.error.extend(
+                            blueprint.bound.span(),
+                            method_not_derefable(&blueprint.bound.get_ident(), sig),
+                        );
+                    }
+                }
+
+                let assoc_methods =
+                    blueprint.get_associated_methods(is_struct, &variants, &fieldless);
+
+                let assoc_types = blueprint.get_mapped_bindings().unwrap_or_else(|ambiguous| {
+                    for ident in ambiguous {
+                        self.error.extend(
+                            blueprint.bound.span(),
+                            assoc_type_ambiguous(&ident, &trait_path),
+                        );
+                    }
+                    vec![]
+                });
+
+                let assoc_consts = blueprint.get_mapped_consts().unwrap_or_else(|unresolved| {
+                    for ident in unresolved {
+                        self.error.extend(
+                            blueprint.bound.span(),
+                            assoc_const_not_resolved(&ident, &trait_path),
+                        );
+                    }
+                    vec![]
                 });
 
                 let implementation: ItemImpl = parse_quote!(
                     impl #impl_generics #trait_path for #enum_ident #ty_generics #where_clause {
-                        #assoc_types
+                        #(#assoc_types)*
+
+                        #(#assoc_consts)*
 
                         #(#assoc_methods)*
                     }
@@ -359,11 +947,65 @@ impl Penum<Unassembled> {
             });
         });
 
+        self.attach_option_impls();
+
         self.update_where_clause(&predicates);
 
         self.transmute_to_assembled()
     }
 
+    /// Options that aren't tied to the pattern, e.g. `impl_display_from(render)`, generate their
+    /// impls here.
+    fn attach_option_impls(&mut self) {
+        let enum_ident = &self.subject.ident;
+        let (impl_generics, ty_generics, where_clause) = self.subject.generics.split_for_impl();
+
+        for option in self.expr.options.iter() {
+            match option {
+                PenumOption::ImplDisplayFrom(method) => self.impls.push(parse_quote!(
+                    impl #impl_generics std::fmt::Display for #enum_ident #ty_generics #where_clause {
+                        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                            write!(f, "{}", self.#method())
+                        }
+                    }
+                )),
+                PenumOption::NameOfIndex(option) => {
+                    let variants = self.subject.get_variants();
+
+                    if let Some(variant) = variants.iter().find(|v| !v.fields.is_empty()) {
+                        self.error.extend(
+                            variant.fields.span(),
+                            format!("`{option}` can only be used on fieldless enums."),
+                        );
+                        continue;
+                    }
+
+                    let vis = &self.subject.vis;
+                    let indices = 0..variants.len();
+                    let names = variants.iter().map(|variant| variant.ident.get_string());
+
+                    self.impls.push(parse_quote!(
+                        impl #impl_generics #enum_ident #ty_generics #where_clause {
+                            #vis fn #option(index: usize) -> Option<&'static str> {
+                                match index {
+                                    #(#indices => Some(#names),)*
+                                    _ => None
+                                }
+                            }
+                        }
+                    ))
+                }
+                // Handled while dispatching and assembling.
+                PenumOption::ViaDeref(_)
+                | PenumOption::Strict(_)
+                | PenumOption::Uniform(_)
+                | PenumOption::SubjectGenerics(_)
+                | PenumOption::Alias(..)
+                | PenumOption::FragmentLimit(..) => (),
+            }
+        }
+    }
+
     fn update_where_clause(&mut self, predicates: &Punctuated<WherePredicate, Comma>) {
         let penum_expr_clause = self.expr.clause.get_or_insert_with(|| parse_quote!(where));
 
@@ -374,22 +1016,138 @@ impl Penum<Unassembled> {
             .for_each(|pred| penum_expr_clause.predicates.push(parse_quote!(#pred)));
     }
 
+    /// Reports a generic that is bound to a different type than it was bound to before, e.g.
+    /// `T` given `V1(i32)` and `V2(u8)` for `(T)` in `uniform` mode.
+    fn check_uniform_binding(
+        &self,
+        bindings: &mut BTreeMap<Ident, (Type, Ident)>,
+        generic: &Ident,
+        ty: &Type,
+        variant_ident: &Ident,
+    ) {
+        match bindings.get(generic) {
+            Some((bound, other)) if bound.get_unique_id() != ty.get_unique_id() => {
+                self.error
+                    .extend(ty.span(), generic_not_uniform(generic, ty, bound, other));
+            }
+            Some(_) => {}
+            None => {
+                bindings.insert(generic.clone(), (ty.clone(), variant_ident.clone()));
+            }
+        }
+    }
+
+    fn report_not_in_pattern(&self, ty: &Type, generic: &Ident, suggestion: Option<&Ident>) {
+        if self.expr.is_strict() {
+            self.error
+                .extend(ty.span(), generic_not_in_pattern(generic, suggestion));
+        } else {
+            self.error
+                .warn(ty.span(), generic_not_in_pattern(generic, suggestion));
+        }
+    }
+
     fn report_invalid_shape(
         &self,
         comparable_item: &Comparable<'_, syn::Fields>,
         variant_ident: &Ident,
         pattern_fmt: &String,
+        arity_mismatch: Option<(&Comparable<'_, PatComposite>, ArityMismatch)>,
+        closest: Option<(&Comparable<'_, PatComposite>, FieldMismatch)>,
+        near_arity: Option<&Comparable<'_, PatComposite>>,
     ) {
-        if comparable_item.inner.is_empty() {
+        // Prefer printing the whole fragment, e.g. `tuple(_)` instead of `(_)`.
+        let get_fragment = |pattern: &PatComposite| {
+            self.expr
+                .pattern
+                .iter()
+                .find(|frag| std::ptr::eq(&frag.group, pattern))
+                .map(ToTokens::to_token_stream)
+                .unwrap_or_else(|| pattern.to_token_stream())
+        };
+
+        if let Some((pattern, mismatch)) = arity_mismatch.is_none().then_some(closest).flatten() {
+            // Point at the field that keeps the variant from matching its closest pattern.
+            let (span, position, expected, found) = match mismatch {
+                FieldMismatch::Type(index, param, field) => (
+                    field.ty.span(),
+                    index,
+                    param.ty.to_token_stream(),
+                    field.ty.to_token_stream(),
+                ),
+                FieldMismatch::Missing(index, param) if comparable_item.inner.is_empty() => (
+                    variant_ident.span(),
+                    index,
+                    param.to_token_stream(),
+                    TokenStream2::new(),
+                ),
+                FieldMismatch::Missing(index, param) => (
+                    comparable_item.inner.span(),
+                    index,
+                    param.to_token_stream(),
+                    TokenStream2::new(),
+                ),
+                FieldMismatch::Unexpected(index, field) => (
+                    field.span(),
+                    index,
+                    TokenStream2::new(),
+                    field.ty.to_token_stream(),
+                ),
+            };
+
+            let fragment = get_fragment(pattern.inner);
+
+            let item = if comparable_item.inner.is_empty() {
+                variant_ident.to_token_stream()
+            } else {
+                comparable_item.inner.to_token_stream()
+            };
+
             self.error.extend(
-                variant_ident.span(),
-                no_match_found(variant_ident, pattern_fmt),
+                span,
+                closest_pattern_mismatch(&item, &fragment, position + 1, &expected, &found),
             );
-        } else {
+        } else if let Some((pattern, mismatch)) = arity_mismatch {
+            // Name the fragment that the item fails, rather than every fragment of the pattern.
+            let fragment = get_fragment(pattern.inner).to_string();
+
             self.error.extend(
                 comparable_item.inner.span(),
-                no_match_found(comparable_item.inner, pattern_fmt),
+                match mismatch {
+                    ArityMismatch::TooFew(minimum) => {
+                        too_few_fields(comparable_item.inner, minimum, &fragment)
+                    }
+                    ArityMismatch::TooMany(maximum) => {
+                        too_many_fields(comparable_item.inner, maximum, &fragment)
+                    }
+                },
             );
+        } else {
+            let (span, mut message) = if comparable_item.inner.is_empty() {
+                (
+                    variant_ident.span(),
+                    no_match_found(variant_ident, pattern_fmt),
+                )
+            } else {
+                (
+                    comparable_item.inner.span(),
+                    no_match_found(comparable_item.inner, pattern_fmt),
+                )
+            };
+
+            // Too far off to point at a single field, but a pattern could still be a field away.
+            if let Some(pattern) = near_arity {
+                message = format!(
+                    "{message} — {}",
+                    almost_matched(
+                        &get_fragment(pattern.inner),
+                        pattern.inner.len(),
+                        comparable_item.inner.len()
+                    )
+                );
+            }
+
+            self.error.extend(span, message);
         };
     }
 
@@ -398,95 +1156,606 @@ impl Penum<Unassembled> {
         bounds: &'a Punctuated<TypeParamBound, Add>,
     ) -> Option<String> {
         // TODO: If we have an error, should we just return?
-        let mut impl_string = String::new();
+        // The ids are sorted so that `impl A + B` and `impl B + A` end up with the same string.
+        let mut bound_ids = BTreeSet::new();
 
         for bound in bounds.iter() {
             match bound {
                 syn::TypeParamBound::Trait(trait_bound) => {
                     if let syn::TraitBoundModifier::None = trait_bound.modifier {
-                        impl_string.push_str(&trait_bound.get_unique_trait_bound_id())
+                        bound_ids.insert(trait_bound.get_unique_trait_bound_id());
                     } else {
                         self.error
                             .extend(bound.span(), maybe_bounds_not_permitted(trait_bound));
                     }
                 }
-                syn::TypeParamBound::Lifetime(_) => {
-                    self.error.extend_spanned(bound, lifetime_not_permitted());
+                syn::TypeParamBound::Lifetime(lifetime) => {
+                    bound_ids.insert(UniqueHashId::from_value(lifetime).get_unique_string());
                 }
             }
         }
 
-        if self.error.has_error() || impl_string.is_empty() {
+        if self.error.has_error() || bound_ids.is_empty() {
             None
         } else {
-            Some(impl_string)
+            Some(bound_ids.into_iter().collect())
         }
     }
 }
 
-impl Penum<Assembled> {
-    // NOTE: This is only used for unit tests
-    #[allow(dead_code)]
-    pub fn get_tokenstream(self) -> TokenStream2 {
-        let (subject, impls, diagnostic) = self.attach_assertions();
+/// Joins cfg predicates with `all` or `any`, e.g. `all(unix, feature = "x")`, but leaves a single
+/// one as it is.
+fn combine_cfgs(op: &str, cfgs: Vec<TokenStream2>) -> Option<TokenStream2> {
+    let op = format_ident!("{}", op);
 
-        if diagnostic.has_error() {
-            diagnostic.map(Error::to_compile_error).unwrap()
+    match cfgs.len() {
+        0 => None,
+        1 => cfgs.into_iter().next(),
+        _ => Some(quote::quote!(#op(#(#cfgs),*))),
+    }
+}
+
+/// Pushes `#ty: #bounds` onto the where clause of `generics`, spanned to `ty`.
+/// `lifetimes` are the higher-ranked lifetimes of the predicate, e.g. `for<'a>` in
+/// `for<'a> T: Fn(&'a str)`, which are kept in front of the substituted type.
+///
+/// Bounds paired with `true` are asserted on the deref target of `ty` instead, i.e. the
+/// dispatched bounds under `via_deref`, while the rest are still asserted on `ty` itself.
+fn push_bound_predicate<B: ToTokens>(
+    generics: &mut syn::Generics,
+    lifetimes: &Option<syn::BoundLifetimes>,
+    ty: &Type,
+    bounds: impl IntoIterator<Item = (B, bool)>,
+) {
+    let mut plain: Punctuated<TokenStream2, Add> = Punctuated::new();
+    let mut deref: Punctuated<TokenStream2, Add> = Punctuated::new();
+
+    for (bound, via_deref) in bounds {
+        // Could remove this.
+        let spanned_bound = bound
+            .to_token_stream()
+            .into_iter()
+            .map(|mut token| {
+                // NOTE: This is the only way we can
+                // impose a new span for a `bound`..
+                // FIXES: tests/ui/placeholder_with_bound.rs
+                // FIXES: tests/ui/trait-bound-not-satisfied.rs
+                token.set_span(ty.span());
+                token
+            })
+            .collect::<TokenStream2>();
+
+        if via_deref {
+            deref.push(spanned_bound);
         } else {
-            quote::quote!(#subject #(#impls)*)
+            plain.push(spanned_bound);
         }
     }
 
-    pub fn unwrap_or_error(self) -> TokenStream {
-        let (subject, impls, diagnostic) = self.attach_assertions();
+    let predicates = &mut generics.make_where_clause().predicates;
 
-        diagnostic
-            .map(Error::to_compile_error)
-            .unwrap_or_else(|| quote::quote!(#subject #(#impls)*))
-            .into()
+    if !plain.is_empty() {
+        predicates.push(parse_quote! {#lifetimes #ty: #plain});
     }
 
-    pub(self) fn attach_assertions(mut self) -> (Subject, Vec<ItemImpl>, Diagnostic) {
-        if let Some(where_cl) = self.expr.clause.as_ref() {
-            for predicate in where_cl.predicates.iter() {
-                match predicate {
-                    WherePredicate::Type(pred) => {
-                        let id = pred.bounded_ty.get_unique_id();
-
-                        if let Some(pty_set) = self.types.get(&id) {
-                            for ty_id in pty_set.iter() {
-                                let ty = &**ty_id;
-
-                                // Could remove this.
-                                let spanned_bounds = pred
-                                    .bounds
-                                    .to_token_stream()
-                                    .into_iter()
-                                    .map(|mut token| {
-                                        // NOTE: This is the only way we can
-                                        // impose a new span for a `bound`..
-                                        // FIXES: tests/ui/placeholder_with_bound.rs
-                                        // FIXES: tests/ui/trait-bound-not-satisfied.rs
-                                        token.set_span(ty.span());
-                                        token
-                                    })
-                                    .collect::<TokenStream2>();
-
-                                self.subject
-                                    .generics
-                                    .make_where_clause()
-                                    .predicates
-                                    .push(parse_quote! {#ty: #spanned_bounds})
-                            }
+    if !deref.is_empty() {
+        predicates.push(parse_quote! {#ty: std::ops::Deref});
+        predicates.push(parse_quote! {
+            #lifetimes <#ty as std::ops::Deref>::Target: #deref
+        });
+    }
+}
+
+impl Penum<Assembled> {
+    /// Returns the subject together with its impls, or a compile error if any of the assertions
+    /// failed. Warnings are emitted as deprecation notices.
+    pub fn get_tokenstream(self) -> TokenStream2 {
+        let (subject, impls, diagnostic) = self.attach_assertions();
+        let warnings = diagnostic.warnings_to_tokens();
+
+        // The next expression is parsed before it's assembled, so it mustn't see our generics.
+        set_subject_generics(vec![]);
+
+        if diagnostic.error_count() > 1 {
+            return diagnostic.to_compile_errors();
+        }
+
+        match diagnostic.into_error() {
+            Some(error) => error.to_compile_error(),
+            None => quote::quote!(#subject #(#impls)* #warnings),
+        }
+    }
+
+    pub fn unwrap_or_error(self) -> TokenStream {
+        self.get_tokenstream().into()
+    }
+
+    /// Returns `None` if neither the bounds of `pred` nor a bounded type other than a generic
+    /// reference any pattern generic. Otherwise, each variant gets its own predicate where the
+    /// generics are substituted with the types of that variant, i.e. `(T, U) where U: Into<T>`
+    /// gives `i32: Into<i64>` for `V(i64, i32)`.
+    ///
+    /// Generics that aren't part of the variant fall back to every type they've been mapped to.
+    fn get_substituted_bounds(
+        &self,
+        pred: &PredicateType,
+    ) -> Option<Vec<(Type, Punctuated<TypeParamBound, Add>)>> {
+        let generics = self
+            .variant_generics
+            .iter()
+            .flat_map(|generics| generics.keys().cloned())
+            .collect::<BTreeSet<_>>();
+
+        let mut referenced = get_referenced_generics(&pred.bounds, &generics);
+
+        // Generics within the bounded type, e.g. `T` in `Vec<<T as Iterator>::Item>`, are
+        // substituted too.
+        if !pred.bounded_ty.is_generic() {
+            referenced.extend(get_referenced_generics(&pred.bounded_ty, &generics));
+        }
+
+        if referenced.is_empty() {
+            return None;
+        }
+
+        let bounded = pred
+            .bounded_ty
+            .is_generic()
+            .then(|| pred.bounded_ty.get_generic_ident());
+
+        let bounds = &pred.bounds;
+        let bounds: Punctuated<TypeParamBound, Add> = parse_quote!(#bounds);
+        let mut substituted = vec![];
+
+        for variant in self.variant_generics.iter() {
+            let in_variant = match bounded.as_ref() {
+                Some(bounded) => variant.contains_key(bounded),
+                None => referenced.iter().any(|ident| variant.contains_key(ident)),
+            };
+
+            if !in_variant {
+                continue;
+            }
+
+            let mut substitutions = vec![variant.clone()];
+
+            for ident in referenced
+                .iter()
+                .filter(|ident| !variant.contains_key(ident))
+            {
+                let generic: Type = parse_quote!(#ident);
+                let Some(tys) = self.types.get(&generic.get_unique_id()) else {
+                    continue;
+                };
+
+                substitutions = substitutions
+                    .into_iter()
+                    .flat_map(|substitution| {
+                        tys.iter().map(move |ty| {
+                            let mut substitution = substitution.clone();
+                            substitution.insert(ident.clone(), (**ty).clone());
+                            substitution
+                        })
+                    })
+                    .collect();
+            }
+
+            for substitution in substitutions {
+                let polymap = substitution.iter().map(|(k, v)| (k.clone(), v)).collect();
+
+                let ty = match bounded.as_ref() {
+                    Some(bounded) => substitution[bounded].clone(),
+                    None => {
+                        let mut ty = pred.bounded_ty.clone();
+                        MonomorphizeTraitBound(&polymap).visit_type_mut(&mut ty);
+                        ty
+                    }
+                };
+
+                let mut bounds = bounds.clone();
+
+                for bound in bounds.iter_mut() {
+                    MonomorphizeTraitBound(&polymap).visit_type_param_bound_mut(bound);
+                }
+
+                substituted.push((ty, bounds));
+            }
+        }
+
+        Some(substituted)
+    }
+
+    /// Returns `<#ty as Trait>::Item: Bounds` for each type that the generic of the projection has
+    /// been mapped to. The trait can be left out if the generic is bounded by exactly one trait.
+    fn get_projection_predicates(
+        &self,
+        where_cl: &WhereClause,
+        pred: &PredicateType,
+        projection: Result<Projection, ()>,
+    ) -> Vec<syn::WherePredicate> {
+        let Ok(Projection {
+            generic,
+            trait_path,
+            assoc,
+        }) = projection
+        else {
+            self.error.extend_spanned(
+                &pred.bounded_ty,
+                nested_projection_not_supported(&pred.bounded_ty),
+            );
+            return vec![];
+        };
+
+        let Some(trait_path) = trait_path.or_else(|| self.get_projection_trait(where_cl, &generic))
+        else {
+            self.error.extend_spanned(
+                &pred.bounded_ty,
+                projection_trait_ambiguous(&pred.bounded_ty, &generic),
+            );
+            return vec![];
+        };
+
+        let Some(pty_set) = self.types.get(&generic.get_unique_id()) else {
+            return vec![];
+        };
+
+        pty_set
+            .iter()
+            .map(|ty_id| &**ty_id)
+            .map(|ty| {
+                // Point unsatisfied bounds at the type they were asserted for.
+                let spanned_bounds = pred
+                    .bounds
+                    .to_token_stream()
+                    .into_iter()
+                    .map(|mut token| {
+                        token.set_span(ty.span());
+                        token
+                    })
+                    .collect::<TokenStream2>();
+                let lifetimes = &pred.lifetimes;
+
+                parse_quote!(#lifetimes <#ty as #trait_path>::#assoc: #spanned_bounds)
+            })
+            .collect()
+    }
+
+    /// Returns the trait that declares the associated types of `generic`, given that it's bounded
+    /// by exactly one trait, e.g. `Iterator` in `(T) where T: Iterator<Item = i32>`.
+    fn get_projection_trait(&self, where_cl: &WhereClause, generic: &Type) -> Option<syn::Path> {
+        let generic_id = generic.get_unique_id();
+
+        let mut traits = where_cl
+            .predicates
+            .iter()
+            .filter_map(|predicate| match predicate {
+                WherePredicate::Type(pred) if pred.bounded_ty.get_unique_id() == generic_id => {
+                    Some(pred.bounds.iter())
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|bound| match bound {
+                crate::factory::TypeParamBound::Trait(tb) => Some(tb),
+                _ => None,
+            });
+
+        match (traits.next(), traits.next()) {
+            (Some(tb), None) => {
+                let tb = &tb.ty;
+                let mut tb: SynTraitBound = parse_quote!(#tb);
+                RemoveBoundBindings.visit_trait_bound_mut(&mut tb);
+                Some(tb.path)
+            }
+            _ => None,
+        }
+    }
+
+    /// Qualifies the projections within `pred`, i.e. `Vec<T::Item>: From<U::Output>` becomes
+    /// `Vec<<T as Iterator>::Item>: From<<U as Add>::Output>`, so that their generics can be
+    /// substituted.
+    fn qualify_projections(&self, where_cl: &WhereClause, pred: &PredicateType) -> PredicateType {
+        let mut pred = pred.clone();
+        let mut qualify = QualifyProjections(|projection: &Type, generic: &Type| {
+            let trait_path = self.get_projection_trait(where_cl, generic);
+
+            if trait_path.is_none() {
+                self.error
+                    .extend_spanned(projection, projection_trait_ambiguous(projection, generic));
+            }
+
+            trait_path
+        });
+
+        qualify.visit_type_mut(&mut pred.bounded_ty);
+
+        for bound in pred.bounds.iter_mut() {
+            if let crate::factory::TypeParamBound::Trait(tb) = bound {
+                qualify.visit_type_mut(&mut tb.ty);
+            }
+        }
+
+        pred
+    }
+
+    /// Returns the first lifetime, other than `'static`, that the subject doesn't declare.
+    fn find_undeclared_lifetime<'l>(
+        &self,
+        mut lifetimes: impl Iterator<Item = &'l syn::Lifetime>,
+    ) -> Option<&'l syn::Lifetime> {
+        lifetimes.find(|lifetime| {
+            lifetime.ident != "static"
+                && !self
+                    .subject
+                    .generics
+                    .lifetimes()
+                    .any(|def| def.lifetime == **lifetime)
+        })
+    }
+
+    /// Predicates of types that only variants behind `#[cfg(..)]` have, e.g. `Thing: Trait` given
+    /// `#[cfg(feature = "thing")] V(Thing)`, can't stay in the where clause of the enum since
+    /// attributes aren't permitted there. Instead, they are moved to the where clause of an
+    /// associated fn that is configured away together with those variants.
+    fn take_conditional_predicates(&mut self) -> Option<ItemImpl> {
+        let mut unconditional = BTreeSet::new();
+        let mut conditional = vec![];
+
+        for variant in self.subject.get_variants() {
+            let cfgs = variant
+                .attrs
+                .iter()
+                .filter(|attr| attr.path.is_ident("cfg"))
+                .filter_map(|attr| attr.parse_args::<TokenStream2>().ok())
+                .collect();
+
+            let ids = variant
+                .fields
+                .iter()
+                .flat_map(|field| get_nested_type_ids(&field.ty));
+
+            match combine_cfgs("all", cfgs) {
+                Some(cfg) => conditional.push((cfg, ids.collect::<BTreeSet<_>>())),
+                None => unconditional.extend(ids),
+            }
+        }
+
+        if conditional.is_empty() {
+            return None;
+        }
+
+        let where_clause = self.subject.generics.where_clause.as_mut()?;
+        let mut gated: BTreeMap<String, (TokenStream2, Vec<syn::WherePredicate>)> = BTreeMap::new();
+
+        for predicate in std::mem::take(&mut where_clause.predicates) {
+            let syn::WherePredicate::Type(pred) = &predicate else {
+                where_clause.predicates.push(predicate);
+                continue;
+            };
+
+            // Each type that only conditional variants have needs one of them to be configured.
+            let cfgs = get_nested_type_ids(&pred.bounded_ty)
+                .difference(&unconditional)
+                .filter_map(|id| {
+                    let cfgs = conditional
+                        .iter()
+                        .filter(|(_, ids)| ids.contains(id))
+                        .map(|(cfg, _)| cfg.clone())
+                        .collect();
+
+                    combine_cfgs("any", cfgs)
+                })
+                .map(|cfg| (cfg.to_string(), cfg))
+                .collect::<BTreeMap<_, _>>();
+
+            match combine_cfgs("all", cfgs.into_values().collect()) {
+                Some(cfg) => gated
+                    .entry(cfg.to_string())
+                    .or_insert_with(|| (cfg, vec![]))
+                    .1
+                    .push(predicate),
+                None => where_clause.predicates.push(predicate),
+            }
+        }
+
+        if where_clause.predicates.is_empty() {
+            self.subject.generics.where_clause = None;
+        }
+
+        if gated.is_empty() {
+            return None;
+        }
+
+        let enum_ident = &self.subject.ident;
+        let (impl_generics, ty_generics, where_clause) = self.subject.generics.split_for_impl();
+
+        let assertions = gated
+            .into_values()
+            .enumerate()
+            .map(|(index, (cfg, predicates))| {
+                let ident = format_ident!("_penum_cfg_assertions_{}", index);
+
+                quote::quote!(
+                    #[cfg(#cfg)]
+                    #[allow(dead_code)]
+                    fn #ident() where #(#predicates),* {}
+                )
+            });
+
+        Some(parse_quote!(
+            impl #impl_generics #enum_ident #ty_generics #where_clause {
+                #(#assertions)*
+            }
+        ))
+    }
+
+    /// A generic that's part of the pattern, but only of fragments that no variant matches, is
+    /// never mapped to a type, so the bounds of its predicates can't be asserted. This is a
+    /// warning, unless the `strict` option is set.
+    fn report_never_bound(&self, ty: &Type, generic: &Ident) {
+        if self.expr.is_strict() {
+            self.error.extend(ty.span(), generic_never_bound(generic));
+        } else {
+            self.error.warn(ty.span(), generic_never_bound(generic));
+        }
+    }
+
+    pub(self) fn attach_assertions(mut self) -> (Subject, Vec<ItemImpl>, Diagnostic) {
+        let via_deref = self.expr.has_via_deref();
+        let defaults = self.expr.get_generic_defaults();
+
+        // Predicates of `Self`, e.g. `Self: Send`, can't be asserted on the enum itself, so they
+        // gate every impl that we generate instead.
+        let mut self_predicates: Vec<syn::WherePredicate> = vec![];
+
+        // Generics that no fragment uses have already been reported.
+        let pattern_generics = self.expr.get_generic_idents();
+
+        if let Some(where_cl) = self.expr.clause.as_ref() {
+            for predicate in where_cl.predicates.iter() {
+                match predicate {
+                    WherePredicate::Type(pred) if pred.bounded_ty.is_self() => {
+                        self_predicates.push(parse_quote!(#pred));
+                    }
+                    WherePredicate::Type(pred) => {
+                        // Lifetime bounds, e.g. `T: 'a`, have to be declared by the subject too.
+                        let lifetimes = pred.bounds.iter().filter_map(|bound| match bound {
+                            crate::factory::TypeParamBound::Lifetime(lifetime) => Some(lifetime),
+                            _ => None,
+                        });
+
+                        if let Some(lifetime) = self.find_undeclared_lifetime(lifetimes) {
+                            self.error.extend(
+                                lifetime.span(),
+                                lifetime_not_declared(lifetime, &self.subject.ident),
+                            );
+                            continue;
+                        }
+
+                        // Associated types of our pattern generics, e.g. `T::Item: Clone`, are
+                        // asserted for each type that the generic has been mapped to.
+                        if let Some(projection) = get_projection(&pred.bounded_ty) {
+                            let predicates =
+                                self.get_projection_predicates(where_cl, pred, projection);
+                            self.subject
+                                .generics
+                                .make_where_clause()
+                                .predicates
+                                .extend(predicates);
+                            continue;
+                        }
+
+                        // Projections within the predicate, e.g. `Vec<T::Item>: Clone`, are
+                        // qualified first so that their generic can be substituted as well.
+                        let pred = &self.qualify_projections(where_cl, pred);
+
+                        // Only the dispatched bounds, e.g. `^AsRef<str>`, are asserted on the
+                        // deref target of the field under `via_deref`.
+                        let deref_mask: Vec<bool> = pred
+                            .bounds
+                            .iter()
+                            .map(|bound| {
+                                via_deref && bound.get_dispatchable_trait_bound().is_some()
+                            })
+                            .collect();
+
+                        // Bounds that reference other pattern generics, e.g. `T` in
+                        // `U: Into<T>`, are asserted per variant with both sides substituted.
+                        if let Some(substituted) = self.get_substituted_bounds(pred) {
+                            for (ty, bounds) in substituted {
+                                push_bound_predicate(
+                                    &mut self.subject.generics,
+                                    &pred.lifetimes,
+                                    &ty,
+                                    bounds.iter().zip(deref_mask.iter().copied()),
+                                );
+                            }
+                            continue;
+                        }
+
+                        let id = pred.bounded_ty.get_unique_id();
+
+                        // Concrete types that aren't part of our pattern, e.g. `String` in
+                        // `(..) where String: AsRef<str>`, are asserted as they are, and
+                        // generics that no variant binds fall back on their default type.
+                        let tys: Vec<&Type> = match self.types.get(&id) {
+                            Some(pty_set) => pty_set.iter().map(|ty_id| &**ty_id).collect(),
+                            None if !pred.bounded_ty.is_generic()
+                                && !pred.bounded_ty.is_placeholder() =>
+                            {
+                                vec![&pred.bounded_ty]
+                            }
+                            None if pred.bounded_ty.is_generic() => {
+                                let generic = pred.bounded_ty.get_generic_ident();
+
+                                match defaults.get(&generic) {
+                                    Some(default) => vec![*default],
+                                    None => {
+                                        if pattern_generics.contains(&generic) {
+                                            self.report_never_bound(&pred.bounded_ty, &generic);
+                                        }
+                                        vec![]
+                                    }
+                                }
+                            }
+                            None => vec![],
+                        };
+
+                        for ty in tys {
+                            push_bound_predicate(
+                                &mut self.subject.generics,
+                                &pred.lifetimes,
+                                ty,
+                                pred.bounds.iter().zip(deref_mask.iter().copied()),
+                            );
+                        }
+                    }
+                    // Lifetime predicates, e.g. `'a: 'b`, are forwarded as they are, as long as
+                    // the subject declares each lifetime.
+                    WherePredicate::Lifetime(pred) => {
+                        let lifetimes = std::iter::once(&pred.lifetime).chain(pred.bounds.iter());
+
+                        if let Some(lifetime) = self.find_undeclared_lifetime(lifetimes) {
+                            self.error.extend(
+                                lifetime.span(),
+                                lifetime_not_declared(lifetime, &self.subject.ident),
+                            );
+                            continue;
                         }
+
+                        self.subject
+                            .generics
+                            .make_where_clause()
+                            .predicates
+                            .push(parse_quote!(#pred));
                     }
-                    WherePredicate::Lifetime(pred) => self
-                        .error
-                        .extend(pred.span(), "lifetime predicates are unsupported"),
                 }
             }
         }
 
+        // Both the pattern and the enum can express the same bound.
+        dedup_generic_bounds(&mut self.subject.generics);
+
+        let conditional = self.take_conditional_predicates();
+
+        // Our impls also need the bounds that constrain the enum's type parameters,
+        // otherwise we wouldn't be able to dispatch through them.
+        let generic_predicates = self.subject.get_generic_predicates();
+
+        if !generic_predicates.is_empty() || !self_predicates.is_empty() {
+            for implementation in self.impls.iter_mut() {
+                implementation
+                    .generics
+                    .make_where_clause()
+                    .predicates
+                    .extend(generic_predicates.iter().chain(&self_predicates).cloned());
+
+                dedup_generic_bounds(&mut implementation.generics);
+            }
+        }
+
+        self.impls.extend(conditional);
+
         (self.subject, self.impls, self.error)
     }
 }
@@ -507,19 +1776,19 @@ impl Penum<Assembled> {
 #[cfg(test)]
 mod tests {
     use proc_macro2::TokenStream;
-    use syn::{parse_quote, ItemTrait};
+    use syn::{
+        parse_quote, punctuated::Punctuated, token::Add, ItemImpl, ItemTrait, TypeParamBound,
+    };
 
     use crate::{
-        dispatch::T_SHM,
+        dispatch::{impl_key, I_SHM, T_SHM},
         factory::{PenumExpr, Subject},
         penum::{Penum, Stringify},
     };
 
     fn penum_assertion(attr: TokenStream, input: TokenStream, expect: TokenStream) {
-        let pattern: PenumExpr = parse_quote!( #attr );
-        let input: Subject = parse_quote!( #input );
-
-        let penum = Penum::new(pattern, input)
+        let penum = Penum::from_tokens(attr, input)
+            .expect("to parse")
             .assemble()
             .get_tokenstream()
             .to_string();
@@ -527,6 +1796,15 @@ mod tests {
         assert_eq!(penum, expect.to_string());
     }
 
+    fn register_impl(input: TokenStream) {
+        let item_impl: ItemImpl = parse_quote!(#input);
+        let (_, trait_path, _) = item_impl.trait_.as_ref().expect("a trait impl");
+        I_SHM.insert(
+            impl_key(trait_path, &item_impl.self_ty),
+            item_impl.get_string(),
+        );
+    }
+
     fn register_trait(input: TokenStream) {
         let item_trait: ItemTrait = parse_quote!(#input);
         // If we cannot find the trait the user wants to dispatch, we need to store it.
@@ -535,60 +1813,69 @@ mod tests {
 
     #[test]
     #[rustfmt::skip]
-    fn simple_expression() {
-        let attr = quote::quote!(
-            (T) where T: Trait
-        );
-        
+    fn impl_trait_in_named_fields() {
+        let attr = quote::quote!({ name: impl Copy + Clone, age?: impl Clone, .. } | { _: impl Copy });
+
         let input = quote::quote!(
             enum Enum {
-                V1(i32),
-                V2(usize),
-                V3(String)
+                V1 { name: u8, age: String, id: i32 },
+                V2 { name: i64 },
+                V3 { id: usize },
             }
         );
 
         let expect = quote::quote!(
-            enum Enum
-            where
-                usize: Trait,
-                String: Trait,
-                i32: Trait
-            {
-                V1(i32),
-                V2(usize),
-                V3(String)
+            enum Enum where u8: Copy, String: Clone, i64: Copy, usize: Copy {
+                V1 { name: u8, age: String, id: i32 },
+                V2 { name: i64 },
+                V3 { id: usize },
             }
         );
 
         penum_assertion(attr, input, expect);
     }
 
+    #[test]
+    fn impl_string_ignores_bound_order() {
+        let penum = Penum::from_tokens(
+            quote::quote!((impl Copy + Clone)),
+            quote::quote!(
+                enum Enum {
+                    V1(i32),
+                }
+            ),
+        )
+        .expect("to parse");
+
+        let copy_clone: Punctuated<TypeParamBound, Add> = parse_quote!(Copy + Clone);
+        let clone_copy: Punctuated<TypeParamBound, Add> = parse_quote!(Clone + Copy);
+        let clone_copy_clone: Punctuated<TypeParamBound, Add> = parse_quote!(Clone + Copy + Clone);
+
+        let impl_string = penum.create_impl_string(&copy_clone);
+
+        assert!(impl_string.is_some());
+        assert_eq!(impl_string, penum.create_impl_string(&clone_copy));
+        assert_eq!(impl_string, penum.create_impl_string(&clone_copy_clone));
+    }
+
     #[test]
     #[rustfmt::skip]
-    fn dispatch_std_trait() {
+    fn leave_out_maybe_sized_bounds_of_impl_types() {
         let attr = quote::quote!(
-            (T) where T: ^AsRef<str>
+            (impl ?Sized + AsRef<str>) | (impl ?Sized, ..)
         );
 
         let input = quote::quote!(
-            enum Enum {
-                V1(String),
+            enum Enum<'a> {
+                V1(&'a str),
+                V2(&'a [u8], u8),
             }
         );
 
         let expect = quote::quote!(
-            enum Enum where String: AsRef<str> {
-                V1(String),
-            }
-
-            impl AsRef<str> for Enum {
-                fn as_ref(&self) -> &str {
-                    match self {
-                        Enum::V1(val) => val.as_ref(),
-                        _ => ""
-                    }
-                }
+            enum Enum<'a> where &'a str: AsRef<str> {
+                V1(&'a str),
+                V2(&'a [u8], u8),
             }
         );
 
@@ -597,90 +1884,2681 @@ mod tests {
 
     #[test]
     #[rustfmt::skip]
-    fn dispatch_custom_trait() {
-        let blueprint = quote::quote!(
-            trait Abc {
-                type Input;
-                fn get(&self) -> &Self::Input;
-            }
-        );
-
+    fn keep_lifetime_bounds_of_impl_types() {
         let attr = quote::quote!(
-            (T) where T: ^Abc<Input = str>
+            (impl Clone + 'static) | (impl 'a + AsRef<str>, ..)
         );
 
         let input = quote::quote!(
-            enum Enum {
+            enum Enum<'a> {
                 V1(String),
-                V2(String)
+                V2(&'a str, u8),
             }
         );
 
         let expect = quote::quote!(
-            enum Enum where String: Abc<Input = str> {
+            enum Enum<'a> where String: Clone + 'static, &'a str: 'a + AsRef<str> {
                 V1(String),
-                V2(String)
-            }
-
-            impl Abc<Input = str> for Enum {
-                type Input = str;
-                fn get(&self) -> &Self::Input {
-                    match self {
-                        Enum::V1(val) => val.get(),
-                        Enum::V2(val) => val.get(),
-                        _ => panic!("Missing arm")
-                    }
-                }
+                V2(&'a str, u8),
             }
         );
 
-        register_trait(blueprint);
         penum_assertion(attr, input, expect);
     }
 
     #[test]
     #[rustfmt::skip]
-    fn dispatch_custom_trait_with_impl_expression() {
-        let blueprint = quote::quote!(
-            trait Abc {
-                type Input;
-                fn get(&self) -> &Self::Input;
-            }
-        );
-
+    fn substitute_generics_inside_bounds() {
         let attr = quote::quote!(
-            impl Abc<Input = str> for String
+            (T, U) where T: Copy, U: Into<T>
         );
 
         let input = quote::quote!(
             enum Enum {
-                V1(String, i32),
-                V2(i32, String)
+                V1(i64, i32),
+                V2(f64, f32),
             }
         );
 
+        // `U: Into<T>` is asserted per variant, i.e. never `i32: Into<f64>`.
         let expect = quote::quote!(
-            enum Enum where String: Abc<Input = str> {
-                V1(String, i32),
-                V2(i32, String)
-            }
-
-            impl Abc<Input = str> for Enum {
-                type Input = str;
-                fn get(&self) -> &Self::Input {
-                    match self {
-                        Enum::V1(val, ..) => val.get(),
-                        Enum::V2(_, val) => val.get(),
-                        _ => panic!("Missing arm")
-                    }
-                }
+            enum Enum where i64: Copy, f64: Copy, i32: Into<i64>, f32: Into<f64> {
+                V1(i64, i32),
+                V2(f64, f32),
             }
         );
 
-        register_trait(blueprint);
         penum_assertion(attr, input, expect);
     }
 
-    // TODO: Decide how variadics should be interpreted when we have concrete type bounds.
-    // Make sure to update `tests/test-concrete-bound.rs` if this later gets supported.
+    #[test]
+    fn emit_one_compile_error_per_mismatch() {
+        let penum = Penum::from_tokens(
+            quote::quote!((i32)),
+            quote::quote!(
+                enum Enum {
+                    V1(u8),
+                    V2(i32),
+                    V3(u16),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+    }
+
+    #[test]
+    fn match_prelude_types_regardless_of_path() {
+        let penum = Penum::from_tokens(
+            quote::quote!((String, Vec<T>) | { name: Option<String> }),
+            quote::quote!(
+                enum Enum {
+                    V1(std::string::String, ::std::vec::Vec<i32>),
+                    V2 {
+                        name: core::option::Option<alloc::string::String>,
+                    },
+                    V3(std::string::String, my::Vec<i32>),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 1);
+    }
+
+    #[test]
+    fn assemble_large_enum_with_shared_field_types() {
+        let tys: [syn::Type; 4] = [
+            parse_quote!(i32),
+            parse_quote!(String),
+            parse_quote!(std::string::String),
+            parse_quote!(bool),
+        ];
+
+        let variants = (0..200).map(|index| {
+            let ident = quote::format_ident!("V{index}");
+            let ty = &tys[index % tys.len()];
+            quote::quote!(#ident(#ty))
+        });
+
+        let penum = Penum::from_tokens(
+            quote::quote!((T) where T: Clone),
+            quote::quote!(
+                enum Enum {
+                    #(#variants),*
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // Every variant shares one of the field types, and `std::string::String` is the same type
+        // as `String`, so each distinct type is only asserted once.
+        assert_eq!(penum.matches("compile_error").count(), 0);
+        assert!(penum.contains("where i32 : Clone , String : Clone , bool : Clone {"));
+    }
+
+    #[test]
+    fn match_type_aliases_as_the_type_they_stand_for() {
+        let penum = Penum::from_tokens(
+            quote::quote!((Vec<u8>, Id) | { data: Bytes }, alias(Bytes = Vec<u8>, Id = u64)),
+            quote::quote!(
+                enum Enum {
+                    V1(Bytes, u64),
+                    V2 { data: Vec<u8> },
+                    V3(Vec<u8>, u32),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // Only `u32` in `V3` doesn't match, and `Id` is resolved before it's compared.
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(penum.contains("Found `u32` but expected `u64`"), "{penum}");
+    }
+
+    #[test]
+    fn resolve_type_aliases_within_other_aliases() {
+        let penum = Penum::from_tokens(
+            quote::quote!((Ids, Id), alias(Ids = Vec<Id>, Id = u64)),
+            quote::quote!(
+                enum Enum {
+                    V1(Vec<u64>, Id),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // The fields are spelled the way they were written.
+        assert_eq!(penum.matches("compile_error").count(), 0);
+        assert!(penum.contains("V1 (Vec < u64 > , Id)"), "{penum}");
+    }
+
+    #[test]
+    fn reject_type_aliases_that_refer_back_to_themselves() {
+        for attr in [
+            quote::quote!((A), alias(A = Vec<A>)),
+            quote::quote!((A), alias(A = B, B = Option<A>)),
+        ] {
+            let penum = Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(Vec<u8>),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string();
+
+            assert!(penum.contains("refers back to itself"), "{penum}");
+        }
+    }
+
+    #[test]
+    fn type_aliases_only_apply_to_their_own_expression() {
+        let _ = Penum::from_tokens(
+            quote::quote!((Bytes), alias(Bytes = Vec<u8>)),
+            quote::quote!(
+                enum E1 {
+                    V1(Vec<u8>),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream();
+
+        // `Bytes` is just another type here, so `T` has two different defaults.
+        let error = Penum::from_tokens(
+            quote::quote!((T = Bytes) | (T = Vec<u8>, i32)),
+            quote::quote!(
+                enum E2 {
+                    V1(Vec<u8>),
+                }
+            ),
+        )
+        .err()
+        .expect("to fail");
+
+        assert!(error
+            .to_string()
+            .contains("already has a different default type"));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_through_type_aliases() {
+        let attr = quote::quote!(
+            (T) where Name: ^AsRef<str>, alias(Name = String)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                V2(i32),
+            }
+        );
+
+        // `Name` is asserted the way the variant spells it.
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String),
+                V2(i32),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                        _ => ""
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn bind_generics_uniformly_across_variants() {
+        let assemble = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(i32, Vec<i32>),
+                        V2(u8, Vec<i32>),
+                        V3(i32, Vec<u8>),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        // Without `uniform`, every variant binds its own types.
+        let penum = assemble(quote::quote!((T, Vec<U>)));
+        assert_eq!(penum.matches("compile_error").count(), 0);
+
+        // `T` is `u8` in `V2`, and `U` is `u8` in `V3`.
+        let penum = assemble(quote::quote!(uniform, (T, Vec<U>)));
+        assert_eq!(penum.matches("compile_error").count(), 2);
+        assert!(
+            penum.contains("`T` is `u8` here, but `i32` in `V1`"),
+            "{penum}"
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn pattern_generics_stand_for_subject_generics_of_the_same_name() {
+        let attr = quote::quote!(
+            subject_generics, (T) | (Vec<T>) where T: Clone
+        );
+
+        let input = quote::quote!(
+            enum E<T> {
+                A(T),
+                B(Vec<T>),
+            }
+        );
+
+        // `(T)` only matches `T`, so `Vec<T>` isn't asserted, and `(Vec<T>)` is reachable.
+        let expect = quote::quote!(
+            enum E<T> where T: Clone {
+                A(T),
+                B(Vec<T>),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn subject_generics_only_apply_to_their_own_expression() {
+        let assemble = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum E<T> {
+                        A(T),
+                        B(i32),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        assert_eq!(
+            assemble(quote::quote!(subject_generics, (T) where T: Clone))
+                .matches("compile_error")
+                .count(),
+            1
+        );
+        assert_eq!(
+            assemble(quote::quote!((T) where T: Clone))
+                .matches("compile_error")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn pattern_generics_are_bound_per_variant_by_default() {
+        let attr = quote::quote!(
+            (T) where T: Clone
+        );
+
+        let input = quote::quote!(
+            enum E<T> {
+                A(T),
+                B(i32),
+            }
+        );
+
+        // Without `subject_generics`, `T` is a pattern generic of its own that also binds `i32`.
+        let expect = quote::quote!(
+            enum E<T> where T: Clone, i32: Clone {
+                A(T),
+                B(i32),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn forward_self_predicates_to_every_impl() {
+        let attr = quote::quote!(
+            impl_display_from(render), (T) where T: ^AsRef<str>, Self: Send + Sync
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String),
+            }
+
+            impl AsRef<str> for Enum where Self: Send + Sync {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                    }
+                }
+            }
+
+            impl std::fmt::Display for Enum where Self: Send + Sync {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{}", self.render())
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn warn_about_fragments_over_the_limit() {
+        let to_string = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(i32),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        let penum = to_string(quote::quote!(fragment_limit(2), (i32) | (u8) | (u16)));
+        assert_eq!(penum.matches("compile_error").count(), 0);
+        assert!(
+            penum.contains("contains 3 pattern fragments which exceeds the limit of 2"),
+            "{penum}"
+        );
+
+        let penum = to_string(quote::quote!(fragment_limit(3), (i32) | (u8) | (u16)));
+        assert!(!penum.contains("exceeds the limit"), "{penum}");
+    }
+
+    #[test]
+    fn report_unreachable_fragments_when_strict() {
+        let to_string = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum<T> {
+                        V1(i32),
+                        V2(T),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        let penum = to_string(quote::quote!(strict, (..) | (i32, ..)));
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(
+            penum.contains("unreachable pattern, `(i32 , ..)` is already matched by `(..)`"),
+            "{penum}"
+        );
+
+        let penum = to_string(quote::quote!(strict, (T, ..) | (T)));
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(
+            penum.contains("unreachable pattern, `(T)` is already matched by `(T , ..)`"),
+            "{penum}"
+        );
+
+        // Without `strict`, fragments that no variant matches aren't reported.
+        let penum = to_string(quote::quote!((T, ..) | (T)));
+        assert!(!penum.contains("unreachable pattern"), "{penum}");
+    }
+
+    #[test]
+    fn report_every_mismatch_of_a_variant() {
+        let penum = Penum::from_tokens(
+            quote::quote!((i32, u8, .., String, bool) | { id: u64, name: String }),
+            quote::quote!(
+                enum Enum {
+                    V1(u16, u16, char, char, u16, u16),
+                    V2 { id: u32, tag: String },
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // Four mismatched types for `V1`, and a mismatched type, a missing field and an
+        // unexpected field for `V2`.
+        assert_eq!(penum.matches("compile_error").count(), 7);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn match_trait_objects_regardless_of_bound_order() {
+        let attr = quote::quote!(
+            (Box<dyn Send + Display>)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Box<dyn Display + Send>),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum {
+                V1(Box<dyn Display + Send>),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn report_trait_object_mismatch_with_full_type() {
+        let error = Penum::from_tokens(
+            quote::quote!((Box<dyn Display>)),
+            quote::quote!(
+                enum Enum {
+                    V1(Box<dyn Debug>),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .attach_assertions()
+        .2
+        .into_error()
+        .expect("to fail");
+
+        assert_eq!(
+            error.to_string(),
+            "Found `Box<dyn Debug>` but expected `Box<dyn Display>`."
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn simple_expression() {
+        let attr = quote::quote!(
+            (T) where T: Trait
+        );
+        
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2(usize),
+                V3(String)
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum
+            where
+                i32: Trait,
+                usize: Trait,
+                String: Trait
+            {
+                V1(i32),
+                V2(usize),
+                V3(String)
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_std_trait() {
+        let attr = quote::quote!(
+            (T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_higher_ranked_trait_bounds() {
+        let blueprint = quote::quote!(
+            trait Greet<'a> {
+                fn greet(&self, name: &'a str) -> &'a str;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: ^for<'a> Greet<'a>, for<'b> T: Fn(&'b str) -> &'b str
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Hi),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where Hi: for<'a> Greet<'a>, for<'b> Hi: Fn(&'b str) -> &'b str {
+                V1(Hi),
+            }
+
+            impl<'a> Greet<'a> for Enum {
+                fn greet(&self, name: &'a str) -> &'a str {
+                    match self {
+                        Enum::V1(val) => val.greet(name),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_custom_trait() {
+        let blueprint = quote::quote!(
+            trait Abc {
+                type Input;
+                fn get(&self) -> &Self::Input;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: ^Abc<Input = str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                V2(String)
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: Abc<Input = str> {
+                V1(String),
+                V2(String)
+            }
+
+            impl Abc for Enum {
+                type Input = str;
+                fn get(&self) -> &Self::Input {
+                    match self {
+                        Enum::V1(val) => val.get(),
+                        Enum::V2(val) => val.get(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_custom_trait_with_inferred_binding() {
+        let blueprint = quote::quote!(
+            trait Inferred {
+                type Input;
+                fn get(&self) -> &Self::Input;
+            }
+        );
+
+        let implementation = quote::quote!(
+            impl Inferred for String {
+                type Input = str;
+                fn get(&self) -> &str {
+                    self
+                }
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: ^Inferred
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                V2(String)
+            }
+        );
+
+        // The binding is taken from the impl instead of `<String as Inferred>::Input`.
+        let expect = quote::quote!(
+            enum Enum where String: Inferred {
+                V1(String),
+                V2(String)
+            }
+
+            impl Inferred for Enum {
+                type Input = str;
+                fn get(&self) -> &Self::Input {
+                    match self {
+                        Enum::V1(val) => val.get(),
+                        Enum::V2(val) => val.get(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        register_impl(implementation);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_custom_trait_with_impl_expression() {
+        let blueprint = quote::quote!(
+            trait Abc {
+                type Input;
+                fn get(&self) -> &Self::Input;
+            }
+        );
+
+        let attr = quote::quote!(
+            impl Abc<Input = str> for String
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32),
+                V2(i32, String)
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: Abc<Input = str> {
+                V1(String, i32),
+                V2(i32, String)
+            }
+
+            impl Abc for Enum {
+                type Input = str;
+                fn get(&self) -> &Self::Input {
+                    match self {
+                        Enum::V1(val, ..) => val.get(),
+                        Enum::V2(_, val) => val.get(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_impl_expression_for_many_qualified_types() {
+        let blueprint = quote::quote!(
+            trait Describe {
+                fn describe(&self) -> String;
+            }
+        );
+
+        let attr = quote::quote!(
+            impl Describe for ::std::string::String, i32
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                V2(i32),
+                V3(bool)
+            }
+        );
+
+        // `String` is forwarded to even though it's spelled out as `::std::string::String`, and
+        // since both are the same type, it's asserted the way the variant spells it.
+        let expect = quote::quote!(
+            enum Enum where String: Describe, i32: Describe {
+                V1(String),
+                V2(i32),
+                V3(bool)
+            }
+
+            impl Describe for Enum {
+                fn describe(&self) -> String {
+                    match self {
+                        Enum::V1(val) => val.describe(),
+                        Enum::V2(val) => val.describe(),
+                        _ => "".to_string()
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn dispatch_std_trait_by_path_over_registered_trait() {
+        register_trait(quote::quote!(
+            trait LowerHex {
+                fn lower_hex(&self) -> String;
+            }
+        ));
+
+        let attr = quote::quote!((T) where T: ^std::fmt::LowerHex);
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+            }
+        );
+
+        let output = Penum::from_tokens(attr, input)
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string();
+
+        assert!(output.contains("fn fmt"));
+        assert!(!output.contains("fn lower_hex"));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_custom_trait_with_module_path() {
+        let blueprint = quote::quote!(
+            trait Scale<T> {
+                fn scale(&self, by: T) -> T;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: ^crate::my::Scale<i32>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: crate::my::Scale<i32> {
+                V1(i32),
+            }
+
+            impl crate::my::Scale<i32> for Enum {
+                fn scale(&self, by: i32) -> i32 {
+                    match self {
+                        Enum::V1(val) => val.scale(by),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dedup_bounds_implied_by_std_supertraits() {
+        let attr = quote::quote!(
+            (T, U) where T: Copy + Clone, U: PartialEq + Ord + PartialOrd + PartialEq<u8>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32, u8),
+            }
+        );
+
+        // `Ord` implies `PartialOrd` and, through `Eq`, `PartialEq`, but not `PartialEq<u8>`.
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, u8: Ord + PartialEq<u8> {
+                V1(i32, u8),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dedup_bounds_implied_by_pattern_and_enum() {
+        let blueprint = quote::quote!(
+            trait Describe: Display {
+                fn describe(&self) -> String;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: Display, T: ^Describe
+        );
+
+        let input = quote::quote!(
+            enum Enum<T: Display + Display> {
+                V1(T),
+            }
+        );
+
+        // `Display` is required by the enum and the pattern, but is already implied by its
+        // supertrait.
+        let expect = quote::quote!(
+            enum Enum<T> where T: Describe {
+                V1(T),
+            }
+
+            impl<T> Describe for Enum<T> where T: Describe {
+                fn describe(&self) -> String {
+                    match self {
+                        Enum::V1(val) => val.describe(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn assert_projected_bounds_for_each_mapped_type() {
+        let attr = quote::quote!(
+            (T) where T: IntoIterator, T::Item: Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Vec<i32>),
+                V2(Option<u8>),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where
+                Vec<i32>: IntoIterator,
+                Option<u8>: IntoIterator,
+                <Vec<i32> as IntoIterator>::Item: Copy,
+                <Option<u8> as IntoIterator>::Item: Copy
+            {
+                V1(Vec<i32>),
+                V2(Option<u8>),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn substitute_generics_within_projections() {
+        let attr = quote::quote!(
+            (T, U) where T: IntoIterator, Vec<T::Item>: Clone, U: From<<T as IntoIterator>::Item>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Vec<u8>, u16),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where
+                Vec<u8>: IntoIterator,
+                Vec< <Vec<u8> as IntoIterator>::Item>: Clone,
+                u16: From< <Vec<u8> as IntoIterator>::Item>
+            {
+                V1(Vec<u8>, u16),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn keep_explicit_discriminants_of_unit_variants() {
+        let blueprint = quote::quote!(
+            trait Mint {
+                fn mint(&self) -> Token;
+            }
+        );
+
+        let attr = quote::quote!(
+            unit | (T) where T: ^Mint
+        );
+
+        let input = quote::quote!(
+            #[repr(u8)]
+            enum Enum {
+                V1 = 1,
+                V2(u8) = 4,
+                V3 = 7,
+            }
+        );
+
+        let expect = quote::quote!(
+            #[repr(u8)]
+            enum Enum where u8: Mint {
+                V1 = 1,
+                V2(u8) = 4,
+                V3 = 7,
+            }
+
+            impl Mint for Enum {
+                fn mint(&self) -> Token {
+                    match self {
+                        Enum::V2(val) => val.mint(),
+                        Enum::V1 => panic!("Missing arm"),
+                        Enum::V3 => panic!("Missing arm"),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_through_subject_generic() {
+        let attr = quote::quote!(
+            (T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum<X> {
+                V1(X),
+                V2(X),
+            }
+        );
+
+        // `T` is mapped to the subject generic `X`, so the bound ends up on `X`.
+        let expect = quote::quote!(
+            enum Enum<X> where X: AsRef<str> {
+                V1(X),
+                V2(X),
+            }
+
+            impl<X> AsRef<str> for Enum<X> where X: AsRef<str> {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                        Enum::V2(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn match_named_fields_by_name() {
+        let attr = quote::quote!(
+            { age: usize, name: T } where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1 { name: String, age: usize },
+                V2 { age: usize, name: &'static str },
+            }
+        );
+
+        // `name` is dispatched through regardless of where it's declared.
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str>, &'static str: AsRef<str> {
+                V1 { name: String, age: usize },
+                V2 { age: usize, name: &'static str },
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1 { name, .. } => name.as_ref(),
+                        Enum::V2 { name, .. } => name.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn named_variadic_discards_like_variadic() {
+        let attr = quote::quote!(
+            (T, ..rest) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32, i32),
+                V2(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String, i32, i32),
+                V2(String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val, ..) => val.as_ref(),
+                        Enum::V2(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_fieldless_variants_with_default_arms() {
+        let attr = quote::quote!(
+            (T) | (i32, ..) | _ where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                V2(),
+                V3,
+                V4(i32, i32),
+            }
+        );
+
+        // `V4` isn't dispatched, so we still need the catch-all arm.
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String),
+                V2(),
+                V3,
+                V4(i32, i32),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                        Enum::V2() => "",
+                        Enum::V3 => "",
+                        _ => ""
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn unit_variants_count_as_matched() {
+        let assemble = |attr: TokenStream, input: TokenStream| {
+            Penum::from_tokens(attr, input)
+                .expect("to parse")
+                .assemble()
+                .get_tokenstream()
+                .to_string()
+        };
+
+        for attr in [
+            quote::quote!(strict, (T) | unit),
+            quote::quote!(strict, (T) | _),
+            quote::quote!(strict, (T) | Empty),
+        ] {
+            let penum = assemble(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(i32),
+                        Empty,
+                    }
+                ),
+            );
+
+            assert_eq!(penum.matches("compile_error").count(), 0, "{penum}");
+        }
+
+        let penum = assemble(
+            quote::quote!(strict, (T) | unit),
+            quote::quote!(
+                enum Enum {
+                    V1(i32),
+                }
+            ),
+        );
+
+        assert_eq!(penum.matches("compile_error").count(), 1);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_hash_with_discriminant() {
+        let attr = quote::quote!(
+            (T) | _ where T: ^Hash
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2(i32),
+                V3,
+            }
+        );
+
+        // The discriminant is hashed first so that `V1(1)` and `V2(1)` don't collide.
+        let expect = quote::quote!(
+            enum Enum where i32: Hash {
+                V1(i32),
+                V2(i32),
+                V3,
+            }
+
+            impl Hash for Enum {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    ::core::hash::Hash::hash(&::core::mem::discriminant(self), state);
+                    match self {
+                        Enum::V1(val) => val.hash(state),
+                        Enum::V2(val) => val.hash(state),
+                        Enum::V3 => (),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn impl_display_from_inherent_method() {
+        let attr = quote::quote!(
+            impl_display_from(render)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2,
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2,
+            }
+
+            impl std::fmt::Display for Enum {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    write!(f, "{}", self.render())
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn bind_generic_across_trailing_marker_fields() {
+        let attr = quote::quote!(
+            (T, ..) | (T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum E<T> {
+                A(T, PhantomData<u8>),
+                B(T),
+            }
+        );
+
+        // `T` should be bound once, even though it's matched by two different fragments.
+        let expect = quote::quote!(
+            enum E<T> where T: AsRef<str> {
+                A(T, PhantomData<u8>),
+                B(T),
+            }
+
+            impl<T> AsRef<str> for E<T> where T: AsRef<str> {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        E::A(val, ..) => val.as_ref(),
+                        E::B(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn name_of_index_for_fieldless_enum() {
+        let attr = quote::quote!(
+            name_of_index
+        );
+
+        let input = quote::quote!(
+            pub enum Enum {
+                V1,
+                V2,
+            }
+        );
+
+        let expect = quote::quote!(
+            pub enum Enum {
+                V1,
+                V2,
+            }
+
+            impl Enum {
+                pub fn name_of_index(index: usize) -> Option<&'static str> {
+                    match index {
+                        0usize => Some("V1"),
+                        1usize => Some("V2"),
+                        _ => None
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_via_deref() {
+        let blueprint = quote::quote!(
+            trait Shout {
+                fn shout(&self) -> String;
+            }
+        );
+
+        let attr = quote::quote!(
+            via_deref, (T) where T: ^Shout
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: std::ops::Deref, <String as std::ops::Deref>::Target: Shout {
+                V1(String),
+            }
+
+            impl Shout for Enum {
+                fn shout(&self) -> String {
+                    match self {
+                        Enum::V1(val) => (&**val).shout(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_via_deref_keeps_plain_bounds_on_the_field() {
+        let blueprint = quote::quote!(
+            trait Grow {
+                fn grow(&mut self, by: usize);
+            }
+        );
+
+        let attr = quote::quote!(
+            via_deref, (T) where T: ^Grow, T: Clone
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+            }
+        );
+
+        // Only the dispatched bound is asserted on the deref target, and `&mut self` borrows it
+        // mutably.
+        let expect = quote::quote!(
+            enum Enum where String: std::ops::Deref, <String as std::ops::Deref>::Target: Grow, String: Clone {
+                V1(String),
+            }
+
+            impl Grow for Enum {
+                fn grow(&mut self, by: usize) {
+                    match self {
+                        Enum::V1(val) => (&mut **val).grow(by),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn reject_by_value_methods_via_deref() {
+        register_trait(quote::quote!(
+            trait Consume {
+                fn consume(self) -> usize;
+            }
+        ));
+
+        let penum = Penum::from_tokens(
+            quote::quote!(via_deref, (T) where T: ^Consume),
+            quote::quote!(
+                enum Enum {
+                    V1(String),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(penum.contains("takes `self` by value"));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn fall_back_to_pattern_matching_in_structure() {
+        let attr = quote::quote!(
+            (i32, ..) | (T, ..) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(String, String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(i32, String),
+                V2(String, String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V2(val, ..) => val.as_ref(),
+                        _ => ""
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn report_errors_for_the_closest_pattern_in_structure() {
+        let penum = Penum::from_tokens(
+            quote::quote!((String, String, ..) | (i32, i32, i32)),
+            quote::quote!(
+                enum Enum {
+                    V1(u8, i32, i32),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // `(i32, i32, i32)` only mismatches `u8`, so it's reported over the first fragment.
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(penum.contains("but expected `i32`"), "{penum}");
+    }
+
+    #[test]
+    fn generate_predicates_and_impls_in_declaration_order() {
+        let to_string = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(String),
+                        V2(Box<str>),
+                        V3(Vec<u8>),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        let penum = to_string(quote::quote!((T) where T: ^AsRef<[u8]>, T: ^AsRef<str>));
+        let u8s = penum
+            .find("impl AsRef < [u8] >")
+            .expect("an impl of AsRef<[u8]>");
+        let str = penum
+            .find("impl AsRef < str >")
+            .expect("an impl of AsRef<str>");
+        assert!(u8s < str, "{penum}");
+
+        let penum = to_string(quote::quote!((T) where T: ^AsRef<str>, T: ^AsRef<[u8]>));
+        let u8s = penum
+            .find("impl AsRef < [u8] >")
+            .expect("an impl of AsRef<[u8]>");
+        let str = penum
+            .find("impl AsRef < str >")
+            .expect("an impl of AsRef<str>");
+        assert!(str < u8s, "{penum}");
+
+        // The types of each predicate follow the order of the variants.
+        assert!(
+            penum.starts_with(
+                "enum Enum where String : AsRef < str > , Box < str > : AsRef < str > , \
+                 Vec < u8 > : AsRef < str > , String : AsRef < [u8] >"
+            ),
+            "{penum}"
+        );
+    }
+
+    #[test]
+    fn report_clause_generics_that_no_variant_binds() {
+        let to_string = |attr| {
+            Penum::from_tokens(
+                attr,
+                quote::quote!(
+                    enum Enum {
+                        V1(i32),
+                    }
+                ),
+            )
+            .expect("to parse")
+            .assemble()
+            .get_tokenstream()
+            .to_string()
+        };
+
+        let message = "`U` isn't bound by any variant, so its bounds are never asserted";
+
+        // `U` is only part of a fragment that no variant matches.
+        let penum = to_string(quote::quote!((T) | { name: U } where U: Clone));
+        assert_eq!(penum.matches("compile_error").count(), 0);
+        assert!(penum.contains(message), "{penum}");
+
+        let penum = to_string(quote::quote!(strict, (T) | { name: U } where U: Clone));
+        assert!(
+            penum.contains(&format!("compile_error ! {{ \"{message}\" }}")),
+            "{penum}"
+        );
+    }
+
+    #[test]
+    fn suggest_pattern_generics_for_unused_clause_generics() {
+        let penum = Penum::from_tokens(
+            quote::quote!(strict, (T, U) | (Vec<ITEMS>) where T: Copy, Y: Copy, ITEM: Clone, Z: Copy),
+            quote::quote!(
+                enum Enum {
+                    V1(i32, u8),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // `T` already has bounds of its own, so `U` is the only one that `Y` and `Z` could mean.
+        assert_eq!(penum.matches("isn't used by any fragment").count(), 3);
+        assert_eq!(
+            penum
+                .matches("so it can't be bounded, did you mean `U`?")
+                .count(),
+            2,
+            "{penum}"
+        );
+        assert!(penum.contains("did you mean `ITEMS`?"), "{penum}");
+
+        // Both `T` and `U` are as close to `Y`, so neither is suggested.
+        let penum = Penum::from_tokens(
+            quote::quote!(strict, (T, U) where Y: Copy),
+            quote::quote!(
+                enum Enum {
+                    V1(i32, u8),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert!(
+            penum.contains(
+                "`Y` isn't used by any fragment of the pattern, so it can't be bounded\""
+            ),
+            "{penum}"
+        );
+
+        // Without `strict`, they are only warned about.
+        let penum = Penum::from_tokens(
+            quote::quote!((T) where Y: Copy),
+            quote::quote!(
+                enum Enum {
+                    V1(i32),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 0);
+        assert!(penum.contains("did you mean `T`?"), "{penum}");
+    }
+
+    #[test]
+    fn name_the_fragment_that_an_error_comes_from() {
+        let penum = Penum::from_tokens(
+            quote::quote!((String) | (i32, i32) | (T, .., T, T)),
+            quote::quote!(
+                enum Enum {
+                    V1(i32, u8),
+                    V2(),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+        assert!(
+            penum.contains("but expected `i32` — in fragment 2 `(i32 , i32)`"),
+            "{penum}"
+        );
+        assert!(
+            penum.contains("too few fields for pattern `(T , .. , T , T)`"),
+            "{penum}"
+        );
+    }
+
+    #[test]
+    fn hint_at_patterns_that_almost_matched() {
+        let penum = Penum::from_tokens(
+            quote::quote!((String, String, String, String, String) | (i32, i32, i32, i32)),
+            quote::quote!(
+                enum Enum {
+                    V1(u8, u8, u8),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        // Both fragments are too far off to point at a field, but one of them is a field away.
+        assert_eq!(penum.matches("compile_error").count(), 1);
+        assert!(
+            penum.contains("almost matched: expected 4 fields, found 3"),
+            "{penum}"
+        );
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_last_field_with_infixed_variadic() {
+        let attr = quote::quote!(
+            (i32, .., T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(i32, u8, u16, String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(i32, String),
+                V2(i32, u8, u16, String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(_, val) => val.as_ref(),
+                        Enum::V2(_, _, _, val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn inline_bound_on_concrete_type() {
+        let attr = quote::quote!(
+            (i32: Copy, ..) | (..) where String: AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32, String),
+                V2(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str>, i32: Copy {
+                V1(i32, String),
+                V2(String),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn variadic_bound_on_covered_fields() {
+        let attr = quote::quote!(
+            (String, ..rest: Copy) | { name: String, ..: Clone }
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32, u8),
+                V2 { name: String, id: Vec<u8> },
+                V3(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, u8: Copy, Vec<u8>: Clone {
+                V1(String, i32, u8),
+                V2 { name: String, id: Vec<u8> },
+                V3(String),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn range_bound_on_covered_fields() {
+        let attr = quote::quote!(
+            (String, 1..3: Copy)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32),
+                V2(String, u8, bool, char),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, u8: Copy, bool: Copy, char: Copy {
+                V1(String, i32),
+                V2(String, u8, bool, char),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn variant_selectors_limit_fragments() {
+        let attr = quote::quote!(
+            Foo(T) | Bar(U) where T: Copy, U: Clone
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                Foo(i32),
+                Bar(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, String: Clone {
+                Foo(i32),
+                Bar(String),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn glob_selectors_limit_fragments() {
+        let attr = quote::quote!(
+            Net*(T) | *Error(String, ..) where T: Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                NetRead(u8),
+                NetWrite(u16),
+                IoError(String, i32),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where u8: Copy, u16: Copy {
+                NetRead(u8),
+                NetWrite(u16),
+                IoError(String, i32),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn bind_generics_inside_containers() {
+        let attr = quote::quote!(
+            (Vec<T>) | (Option<(K, V)>, ..) where T: Display, K: Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Vec<i32>),
+                V2(Option<(u8, String)>, bool),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Display, u8: Copy {
+                V1(Vec<i32>),
+                V2(Option<(u8, String)>, bool),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn bind_lifetimes_of_reference_patterns() {
+        let attr = quote::quote!(
+            (&'a T) | { name: &str, id: &'static str } where T: Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum<'x> {
+                V1(&'x i32),
+                V2 { name: &'x str, id: &'static str },
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum<'x> where i32: Copy {
+                V1(&'x i32),
+                V2 { name: &'x str, id: &'static str },
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn forward_outlives_predicates() {
+        let attr = quote::quote!(
+            (T, U) where 'a: 'static, T: 'a, U: 'a + Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum<'a> {
+                V1(&'a str, &'a u8),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum<'a> where 'a: 'static, &'a str: 'a, &'a u8: 'a + Copy {
+                V1(&'a str, &'a u8),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn static_lifetime_has_to_match() {
+        let penum = Penum::from_tokens(
+            quote::quote!((&'static str)),
+            quote::quote!(
+                enum Enum<'a> {
+                    V1(&'a str),
+                    V2(&'static str),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 1);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn bind_array_and_slice_elements() {
+        let attr = quote::quote!(
+            ([T; N], [U; N]) | (&[T]) where T: Copy, U: Default
+        );
+
+        let input = quote::quote!(
+            enum Enum<'a> {
+                V1([i32; 3], [u8; 3]),
+                V2(&'a [u16]),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum<'a> where i32: Copy, u16: Copy, u8: Default {
+                V1([i32; 3], [u8; 3]),
+                V2(&'a [u16]),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_through_const_generic_enums() {
+        let attr = quote::quote!(
+            (T) where T: ^AsRef<[u8]>
+        );
+
+        let input = quote::quote!(
+            enum Enum<const N: usize> {
+                V1([u8; N]),
+                V2(Vec<u8>),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum<const N: usize> where [u8; N]: AsRef<[u8]>, Vec<u8>: AsRef<[u8]> {
+                V1([u8; N]),
+                V2(Vec<u8>),
+            }
+
+            impl<const N: usize> AsRef<[u8]> for Enum<N> where [u8; N]: AsRef<[u8]> {
+                fn as_ref(&self) -> &[u8] {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                        Enum::V2(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn match_const_generics_by_name_and_argument() {
+        let attr = quote::quote!(
+            subject_generics, ([u8; N]) | (Wrapper<M, M>)
+        );
+
+        let input = quote::quote!(
+            enum Enum<const N: usize> {
+                V1([u8; N]),
+                V2(Wrapper<3, 3>),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum<const N: usize> {
+                V1([u8; N]),
+                V2(Wrapper<3, 3>),
+            }
+        );
+
+        penum_assertion(attr.clone(), input, expect);
+
+        // With `subject_generics`, `N` stands for the const parameter of the enum, so it can't be
+        // bound to `3`.
+        let penum = Penum::from_tokens(
+            attr,
+            quote::quote!(
+                enum Enum<const N: usize> {
+                    V1([u8; 3]),
+                    V2(Wrapper<3, 4>),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn bind_generics_inside_nested_tuples() {
+        let attr = quote::quote!(
+            ((T, U), ..) | { pair: ((T, _), U) } where T: Copy, U: Clone
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1((i32, String), u8),
+                V2 { pair: ((u8, bool), Vec<u8>) },
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, u8: Copy, String: Clone, Vec<u8>: Clone {
+                V1((i32, String), u8),
+                V2 { pair: ((u8, bool), Vec<u8>) },
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn optional_named_fields() {
+        let attr = quote::quote!(
+            { name: T, age?: U } where T: Clone, U: Copy
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1 { name: String },
+                V2 { name: String, age: usize },
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: Clone, usize: Copy {
+                V1 { name: String },
+                V2 { name: String, age: usize },
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn optional_fields_are_still_typed() {
+        let penum = Penum::from_tokens(
+            quote::quote!({ name: String, age?: usize }),
+            quote::quote!(
+                enum Enum {
+                    V1 { name: String, age: u8 },
+                    V2 { name: String, id: usize },
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn wildcard_field_names() {
+        let attr = quote::quote!(
+            { _: T, id: u8 } where T: Clone
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1 { id: u8, name: String },
+                V2 { path: Vec<u8>, id: u8 },
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: Clone, Vec<u8>: Clone {
+                V1 { id: u8, name: String },
+                V2 { path: Vec<u8>, id: u8 },
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn shape_clauses_are_scoped_to_the_shape() {
+        crate::dispatch::S_SHM.insert("ScopedPair".to_string(), "(T, T) where T: PartialEq".to_string());
+
+        let attr = quote::quote!(ScopedPair | (T, ..));
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32, i32),
+                V2(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: PartialEq {
+                V1(i32, i32),
+                V2(String),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn fragment_clauses_are_scoped() {
+        let attr = quote::quote!(
+            (T, U) where T: Copy, U: Into<T> | { name: T } where T: Clone | (V) where V: Default
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i64, i32),
+                V2 { name: String },
+                V3(u8),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where u8: Default, i64: Copy, i32: Into<i64>, String: Clone {
+                V1(i64, i32),
+                V2 { name: String },
+                V3(u8),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn negated_fragments_reject_variants() {
+        let penum = Penum::from_tokens(
+            quote::quote!(!(String, ..) | !Raw * { .. } | (T, ..)),
+            quote::quote!(
+                enum Enum {
+                    V1(&'static str, u8),
+                    V2(String, u8),
+                    V3(u8),
+                    RawBytes { bytes: Vec<u8> },
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+        assert!(penum.contains("`V2` matches the negated pattern"));
+        assert!(penum.contains("`RawBytes` matches the negated pattern"));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn only_negated_fragments_accept_the_rest() {
+        let attr = quote::quote!(!(String, ..));
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(&'static str, u8),
+                V2 { name: String },
+                V3,
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum {
+                V1(&'static str, u8),
+                V2 { name: String },
+                V3,
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn placeholder_fields_fall_back_on_generic_defaults() {
+        let attr = quote::quote!((T = i32, U) where T: Copy);
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(_, String),
+                V2(u8, String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Copy, u8: Copy {
+                V1(i32, String),
+                V2(u8, String),
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn unbound_generics_fall_back_on_their_default() {
+        let attr = quote::quote!((T = i32) | unit where T: Default);
+
+        let input = quote::quote!(
+            enum Enum {
+                V1,
+                V2,
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Default {
+                V1,
+                V2,
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    fn discriminants_have_to_be_in_range() {
+        let penum = Penum::from_tokens(
+            quote::quote!(A = 0..=3 | * = _ as i8),
+            quote::quote!(
+                enum Enum {
+                    A = 4,
+                    B = 126,
+                    C,
+                    D,
+                    E = -1,
+                    F = 127,
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 3);
+        assert!(penum.contains("discriminant `4` of `A` is outside of `0 ..= 3`"));
+        assert!(penum.contains("discriminant `128` of `D` is outside of `_ as i8`"));
+        assert!(penum.contains("discriminant `127` of `F` is already used by `C`"));
+    }
+
+    #[test]
+    fn array_lengths_have_to_agree() {
+        let penum = Penum::from_tokens(
+            quote::quote!(([[T; N]; N]) | ([T; 2], ..)),
+            quote::quote!(
+                enum Enum {
+                    V1([[i32; 3]; 4]),
+                    V2([[i32; 2]; 2]),
+                    V3([i32; 2], u8),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 1);
+    }
+
+    #[test]
+    fn reject_containers_that_dont_unify() {
+        let penum = Penum::from_tokens(
+            quote::quote!((HashMap<T, T>)),
+            quote::quote!(
+                enum Enum {
+                    V1(HashMap<i32, u8>),
+                    V2(Vec<i32>),
+                    V3(HashMap<i32, i32>),
+                }
+            ),
+        )
+        .expect("to parse")
+        .assemble()
+        .get_tokenstream()
+        .to_string();
+
+        assert_eq!(penum.matches("compile_error").count(), 2);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_inline_bound_on_generic() {
+        let attr = quote::quote!(
+            (T: ^AsRef<str>, U: Copy) | (T: ^AsRef<str>)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32),
+                V2(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str>, i32: Copy {
+                V1(String, i32),
+                V2(String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val, ..) => val.as_ref(),
+                        Enum::V2(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_inline_bound_on_concrete_type() {
+        let attr = quote::quote!(
+            (String: ^AsRef<str>, ..) | (i32: Copy)
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String, i32),
+                V2(i32),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str>, i32: Copy {
+                V1(String, i32),
+                V2(i32),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val, ..) => val.as_ref(),
+                        _ => ""
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_methods_with_params() {
+        let blueprint = quote::quote!(
+            trait Store {
+                fn push(&mut self, x: u8);
+                fn same(&self, other: &Self) -> bool;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) | { items: T } where T: ^Store
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(Vec<u8>),
+                V2 { items: Vec<u8> },
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where Vec<u8>: Store {
+                V1(Vec<u8>),
+                V2 { items: Vec<u8> },
+            }
+
+            impl Store for Enum {
+                fn push(&mut self, x: u8) {
+                    match self {
+                        Enum::V1(val) => val.push(x),
+                        Enum::V2 { items } => items.push(x),
+                    }
+                }
+                fn same(&self, other: &Self) -> bool {
+                    match (self, other) {
+                        (Enum::V1(val), Enum::V1(other)) => val.same(other),
+                        (Enum::V2 { items }, Enum::V2 { items: other }) => items.same(other),
+                        _ => false
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_partial_eq_per_variant() {
+        let attr = quote::quote!(
+            (T) where T: ^PartialEq
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: PartialEq, String: PartialEq {
+                V1(i32),
+                V2(String),
+            }
+
+            impl PartialEq for Enum {
+                fn eq(&self, other: &Self) -> bool {
+                    match (self, other) {
+                        (Enum::V1(val), Enum::V1(other)) => val.eq(other),
+                        (Enum::V2(val), Enum::V2(other)) => val.eq(other),
+                        _ => {
+                            let ordinal = |value: &Self| match value {
+                                Enum::V1 { .. } => 0usize,
+                                Enum::V2 { .. } => 1usize,
+                            };
+                            ordinal(self).eq(&ordinal(other))
+                        }
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_ord_by_variant_then_value() {
+        let attr = quote::quote!(
+            (T) | _ where T: ^Ord
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+                V2(String),
+                V3,
+            }
+        );
+
+        // Values of the same variant are compared, otherwise the first declared variant is less.
+        let expect = quote::quote!(
+            enum Enum where i32: Ord, String: Ord {
+                V1(i32),
+                V2(String),
+                V3,
+            }
+
+            impl Ord for Enum {
+                fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                    match (self, other) {
+                        (Enum::V1(val), Enum::V1(other)) => val.cmp(other),
+                        (Enum::V2(val), Enum::V2(other)) => val.cmp(other),
+                        _ => {
+                            let ordinal = |value: &Self| match value {
+                                Enum::V1 { .. } => 0usize,
+                                Enum::V2 { .. } => 1usize,
+                                Enum::V3 { .. } => 2usize,
+                            };
+                            ordinal(self).cmp(&ordinal(other))
+                        }
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_through_struct_field() {
+        let attr = quote::quote!(
+            (_, T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            struct Tagged(u8, String);
+        );
+
+        let expect = quote::quote!(
+            struct Tagged(u8, String) where String: AsRef<str>;
+
+            impl AsRef<str> for Tagged {
+                fn as_ref(&self) -> &str {
+                    self.1.as_ref()
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn dispatch_only_marked_bounds_in_compound_bound() {
+        let attr = quote::quote!(
+            (T) where T: ^AsRef<str> + Clone
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> + Clone {
+                V1(String),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                    }
+                }
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn forward_assoc_const_of_first_type() {
+        let blueprint = quote::quote!(
+            trait Identify {
+                const ID: u32;
+                fn id(&self) -> u32;
+            }
+        );
+
+        let attr = quote::quote!(
+            (T) where T: ^Identify
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(i32),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where i32: Identify {
+                V1(i32),
+            }
+
+            impl Identify for Enum {
+                const ID: u32 = <i32 as Identify>::ID;
+                fn id(&self) -> u32 {
+                    match self {
+                        Enum::V1(val) => val.id(),
+                    }
+                }
+            }
+        );
+
+        register_trait(blueprint);
+        penum_assertion(attr, input, expect);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn gate_arms_and_assertions_of_cfg_variants() {
+        let attr = quote::quote!(
+            (T) where T: ^AsRef<str>
+        );
+
+        let input = quote::quote!(
+            enum Enum {
+                V1(String),
+                #[cfg(feature = "extra")]
+                V2(Extra),
+            }
+        );
+
+        let expect = quote::quote!(
+            enum Enum where String: AsRef<str> {
+                V1(String),
+                #[cfg(feature = "extra")]
+                V2(Extra),
+            }
+
+            impl AsRef<str> for Enum {
+                fn as_ref(&self) -> &str {
+                    match self {
+                        Enum::V1(val) => val.as_ref(),
+                        #[cfg(feature = "extra")]
+                        Enum::V2(val) => val.as_ref(),
+                    }
+                }
+            }
+
+            impl Enum where String: AsRef<str> {
+                #[cfg(feature = "extra")]
+                #[allow(dead_code)]
+                fn _penum_cfg_assertions_0() where Extra: AsRef<str> {}
+            }
+        );
+
+        penum_assertion(attr, input, expect);
+    }
 }
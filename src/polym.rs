@@ -1,18 +1,30 @@
 use std::{
-    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet},
+    collections::{hash_map::DefaultHasher, BTreeMap, HashSet},
     hash::{Hash, Hasher},
     ops::Deref,
 };
 
 use proc_macro2::Ident;
-use quote::{format_ident, ToTokens};
+use quote::ToTokens;
 use syn::{parse_quote, spanned::Spanned, Type};
 
+/// Maps each pattern type to the types it has been matched with. The matched types are kept in
+/// the order they were inserted, i.e. the declaration order of the variants and their fields, so
+/// that the predicates we generate from them don't depend on how the types hash.
+///
+/// The pairs that have been inserted are indexed by their hashes, so that we don't have to scan
+/// the matched types of a pattern type for every insert.
 #[derive(Default, Debug)]
-pub struct PolymorphicMap<K: Hash, V: Hash>(BTreeMap<K, BTreeSet<V>>);
-
-#[derive(Hash, Debug, Clone, Copy)]
-pub struct UniqueHashId<T: Hash>(pub T);
+pub struct PolymorphicMap<K: Hash, V: Hash>(BTreeMap<K, Vec<V>>, HashSet<(u64, u64)>);
+
+/// A value paired with its hash, which is computed once on construction. Types are compared and
+/// ordered a lot while assembling, so we don't want to re-hash them for every comparison.
+#[derive(Debug, Clone)]
+pub struct UniqueHashId<T: Hash> {
+    value: T,
+    hash: u64,
+    id: String,
+}
 
 /// Fix these later
 impl<K: Hash + Clone, V: Hash + Clone> PolymorphicMap<UniqueHashId<K>, UniqueHashId<V>>
@@ -21,55 +33,73 @@ where
     UniqueHashId<V>: Ord,
 {
     pub fn polymap_insert(&mut self, pty: UniqueHashId<K>, ity: UniqueHashId<V>) {
-        // First we check if pty (T) exists in
-        // polymorphicmap. If it exists, insert new
+        // First we check if pty (T) has already been
+        // matched with ity. If not, insert new
         // concrete type.
-        if let Some(set) = self.0.get_mut(&pty) {
-            set.insert(ity);
-        } else {
-            self.0.insert(pty, vec![ity].into_iter().collect());
+        if !self.1.insert((pty.hash, ity.hash)) {
+            return;
         }
+
+        self.0.entry(pty).or_default().push(ity);
     }
 }
 
 impl<K: Hash, V: Hash> Deref for PolymorphicMap<UniqueHashId<K>, UniqueHashId<V>> {
-    type Target = BTreeMap<UniqueHashId<K>, BTreeSet<UniqueHashId<V>>>;
+    type Target = BTreeMap<UniqueHashId<K>, Vec<UniqueHashId<V>>>;
 
     fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
-impl<T: Hash + Clone> UniqueHashId<T> {
-    pub fn new(value: &T) -> Self {
-        Self(value.clone())
-    }
-
-    pub fn get_unique_ident(&self) -> Ident
+impl<T: Hash> UniqueHashId<T> {
+    pub fn new(value: &T) -> Self
     where
-        T: Spanned + ToTokens,
+        T: Clone,
     {
+        Self::from_value(value.clone())
+    }
+
+    pub fn from_value(value: T) -> Self {
         let mut hasher = DefaultHasher::default();
-        self.hash(&mut hasher);
-        format_ident!("_{}", hasher.finish(), span = self.0.span())
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        Self {
+            value,
+            hash,
+            id: format!("_{hash}"),
+        }
     }
 
-    pub fn get_unique_string(&self) -> String {
+    /// Like `from_value`, but hashes `key` instead, for values that are equal in a looser sense
+    /// than their `Hash` impl, e.g. trait objects with their bounds in a different order.
+    pub fn from_hashed(value: T, key: &impl Hash) -> Self {
         let mut hasher = DefaultHasher::default();
-        self.hash(&mut hasher);
-        format!("_{}", hasher.finish())
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        Self {
+            value,
+            hash,
+            id: format!("_{hash}"),
+        }
+    }
+
+    pub fn get_unique_string(&self) -> String {
+        self.id.clone()
     }
 }
 
 impl From<Ident> for UniqueHashId<Type> {
     fn from(value: Ident) -> Self {
-        Self(parse_quote!(#value))
+        Self::from_value(parse_quote!(#value))
     }
 }
 
 impl<T: ToTokens + Hash + Spanned + Clone> From<&T> for UniqueHashId<T> {
     fn from(value: &T) -> Self {
-        Self(value.clone())
+        Self::new(value)
     }
 }
 
@@ -77,54 +107,115 @@ impl<T: Hash> Deref for UniqueHashId<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
+    }
+}
+
+impl<T: Hash> Hash for UniqueHashId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
     }
 }
 
 impl Default for UniqueHashId<Type> {
     fn default() -> Self {
-        Self(parse_quote!(_))
+        Self::from_value(parse_quote!(_))
     }
 }
 
-impl PartialEq for UniqueHashId<Type> {
+// Spans aren't part of the hash, so two types with identical text are always equal.
+impl<T: Hash> PartialEq for UniqueHashId<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.get_unique_ident() == other.get_unique_ident()
+        self.hash == other.hash
     }
 }
 
-impl PartialOrd for UniqueHashId<Type> {
+impl<T: Hash> PartialOrd for UniqueHashId<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.get_unique_ident()
-            .partial_cmp(&other.get_unique_ident())
+        Some(self.cmp(other))
     }
 }
 
-impl Ord for UniqueHashId<Type> {
+// Ordered by the string id to keep the same order as before it was cached.
+impl<T: Hash> Ord for UniqueHashId<T> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.get_unique_ident().cmp(&other.get_unique_ident())
+        self.id.cmp(&other.id)
     }
 }
 
-impl Eq for UniqueHashId<Type> {}
+impl<T: Hash> Eq for UniqueHashId<T> {}
 
 #[cfg(test)]
 mod tests {
+    use quote::ToTokens;
     use syn::{parse_quote, Type};
 
-    use crate::polym::UniqueHashId;
+    use crate::{
+        polym::{PolymorphicMap, UniqueHashId},
+        utils::TypeUtils,
+    };
 
     #[test]
     fn hash_type() {
         let ty1: Type = parse_quote!(&'a mut Typer<T, i32, Target = A<i32>>);
         let ty2: Type = parse_quote!(&'a mut Typer<T, usize, Target = A<i32>>);
 
-        let ty_string1 = UniqueHashId(&ty1).get_unique_string();
-        let ty_string2 = UniqueHashId(&ty2).get_unique_string();
+        let ty_string1 = UniqueHashId::from_value(&ty1).get_unique_string();
+        let ty_string2 = UniqueHashId::from_value(&ty2).get_unique_string();
 
         // If both are OK, then both must be different, making them
         // unique.
         assert_eq!("_8289286104171367827", ty_string1);
         assert_eq!("_2029180714094036370", ty_string2);
     }
+
+    #[test]
+    fn insert_matched_types_once_in_order() {
+        let mut map: PolymorphicMap<UniqueHashId<Type>, UniqueHashId<Type>> = Default::default();
+        let generic: Type = parse_quote!(T);
+
+        for ty in [parse_quote!(u8), parse_quote!(i32), parse_quote!(u8)] {
+            map.polymap_insert(UniqueHashId::new(&generic), UniqueHashId::<Type>::new(&ty));
+        }
+
+        let tys: Vec<String> = map[&UniqueHashId::new(&generic)]
+            .iter()
+            .map(|ty| ty.to_token_stream().to_string())
+            .collect();
+
+        assert_eq!(vec!["u8", "i32"], tys);
+    }
+
+    #[test]
+    fn equal_type_text_is_equal_regardless_of_span() {
+        let ty1: Type = syn::parse_str("Vec<i32>").unwrap();
+        let ty2: Type = parse_quote!(Vec<i32>);
+
+        let id1 = UniqueHashId::new(&ty1);
+        let id2 = UniqueHashId::new(&ty2);
+
+        assert_eq!(id1, id2);
+        assert_eq!(id1.get_unique_string(), id2.get_unique_string());
+        assert_ne!(id1, UniqueHashId::new(&parse_quote!(Vec<u32>)));
+    }
+
+    #[test]
+    fn prelude_types_are_equal_regardless_of_path() {
+        let ty: Type = parse_quote!(Vec<String>);
+
+        for other in [
+            parse_quote!(std::vec::Vec<String>),
+            parse_quote!(::std::vec::Vec<std::string::String>),
+            parse_quote!(alloc::vec::Vec<alloc::string::String>),
+        ] {
+            assert_eq!(ty.get_unique_id(), Type::get_unique_id(&other));
+        }
+
+        let other: Type = parse_quote!(my::vec::Vec<String>);
+        assert_ne!(ty.get_unique_id(), other.get_unique_id());
+
+        let ty: Type = parse_quote!(&str);
+        let other: Type = syn::parse_str("& str").unwrap();
+        assert_eq!(ty.get_unique_id(), other.get_unique_id());
+    }
 }
@@ -1,21 +1,68 @@
 use proc_macro::TokenStream;
 use quote::format_ident;
 use quote::ToTokens;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
 use syn::parse_macro_input;
+use syn::parse_quote;
+use syn::punctuated::Punctuated;
+use syn::Error;
+use syn::Expr;
+use syn::ExprLit;
+use syn::Fields;
+use syn::Ident;
+use syn::ImplItem;
+use syn::ItemImpl;
 use syn::ItemTrait;
+use syn::Lit;
+use syn::Token;
 use syn::Type;
+use syn::Variant;
 
+use crate::dispatch::impl_key;
+use crate::dispatch::E_SHM;
+use crate::dispatch::I_SHM;
+use crate::dispatch::P_SHM;
+use crate::dispatch::S_SHM;
 use crate::dispatch::T_SHM;
+use crate::error::Diagnostic;
+use crate::factory::get_arm_attrs;
+use crate::factory::variant_pattern;
 use crate::factory::PenumExpr;
 use crate::factory::Subject;
 use crate::penum::Penum;
+use crate::utils::bind_positional_args;
+use crate::utils::deref_mut_field_missing;
+use crate::utils::from_str_not_supported;
 use crate::utils::Stringify;
+use crate::utils::DEFAULT_VARIANT_SYMBOL;
+use crate::utils::ERROR_VARIANT_SYMBOL;
 
 pub fn penum_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
     // TODO: Make it bi-directional, meaning it's also possible to register enums and then do
     // the implementations when we tag a trait. (That is actually better).
     if attr.is_empty() {
         let output = input.clone();
+
+        // Impls are stored with their associated types only, which is all we need to infer the
+        // bindings of a dispatched bound.
+        if let Ok(item_impl) = syn::parse::<ItemImpl>(input.clone()) {
+            if let Some((_, trait_path, _)) = item_impl.trait_.as_ref() {
+                let self_ty = &item_impl.self_ty;
+                let assoc_types = item_impl.items.iter().filter_map(|item| match item {
+                    ImplItem::Type(item_ty) => Some(item_ty),
+                    _ => None,
+                });
+
+                I_SHM.insert(
+                    impl_key(trait_path, self_ty),
+                    quote::quote!(impl #trait_path for #self_ty { #(#assoc_types)* }).to_string(),
+                );
+
+                return output;
+            }
+        }
+
         let item_trait = parse_macro_input!(input as ItemTrait);
 
         // If we cannot find the trait the user wants to dispatch, we need to store it.
@@ -26,6 +73,13 @@ pub fn penum_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
         let expr = parse_macro_input!(attr as PenumExpr);
         let subject = parse_macro_input!(input as Subject);
 
+        let shape = expr.pattern_to_string();
+
+        // Options without a pattern don't claim a shape.
+        if !shape.is_empty() {
+            E_SHM.insert(subject.ident.get_string(), shape);
+        }
+
         let penum = Penum::new(expr, subject);
 
         // Loop through enum definition and match each variant with each
@@ -36,7 +90,10 @@ pub fn penum_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
 
 pub fn to_string_expand(input: TokenStream) -> TokenStream {
     let subject = parse_macro_input!(input as Subject);
-    let matching_arms = subject.variants_to_arms(|expr| quote::quote!(format!(#expr)));
+    let matching_arms = subject.variants_to_arms(|expr| {
+        let expr = bind_positional_args(expr);
+        quote::quote!(format!(#expr))
+    });
     let (subject, has_default) = subject.get_censored_subject_and_default_arm(None);
     let enum_name = &subject.ident;
 
@@ -58,7 +115,10 @@ pub fn to_string_expand(input: TokenStream) -> TokenStream {
 
 pub fn fmt_expand(input: TokenStream) -> TokenStream {
     let subject = parse_macro_input!(input as Subject);
-    let matching_arms = subject.variants_to_arms(|expr| quote::quote!(write!(f, #expr)));
+    let matching_arms = subject.variants_to_arms(|expr| {
+        let expr = bind_positional_args(expr);
+        quote::quote!(write!(f, #expr))
+    });
     let (subject, has_default) = subject
         .get_censored_subject_and_default_arm(Some(quote::quote!(write!(f, "{}", "".to_string()))));
     let enum_name = &subject.ident;
@@ -79,22 +139,30 @@ pub fn fmt_expand(input: TokenStream) -> TokenStream {
     .into()
 }
 
-pub fn into_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let ty = parse_macro_input!(attr as Type);
+pub fn debug_expand(input: TokenStream) -> TokenStream {
     let subject = parse_macro_input!(input as Subject);
-    let matching_arms = subject.variants_to_arms(|expr| quote::quote!(#expr));
+    let matching_arms = subject.variants_to_debug_arms(|expr| {
+        let expr = bind_positional_args(expr);
+        quote::quote!(write!(f, #expr))
+    });
     let (subject, has_default) =
-        subject.get_censored_subject_and_default_arm(Some(quote::quote!(Default::default())));
+        subject.get_censored_subject_and_default_arm(Some(quote::quote!(Ok(()))));
     let enum_name = &subject.ident;
 
+    // Every variant has an arm, so the fallback is only needed when there are no variants.
+    let fallback_arm = subject
+        .get_variants()
+        .is_empty()
+        .then(|| quote::quote!(_ => #has_default));
+
     quote::quote!(
         #subject
 
-        impl Into<#ty> for #enum_name {
-            fn into(self) -> #ty {
+        impl std::fmt::Debug for #enum_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 match self {
                     #matching_arms
-                    _ => #has_default
+                    #fallback_arm
                 }
             }
         }
@@ -103,6 +171,118 @@ pub fn into_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Builds the match arms from each variant discriminant, and the fallback arm for variants
+/// without one. The fallback arm is left out when every variant has a discriminant, so that
+/// it doesn't have to type-check. The returned subject has its discriminants removed.
+fn discriminant_arms(
+    subject: Subject,
+    default_else: proc_macro2::TokenStream,
+) -> (Subject, proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let matching_arms = subject.variants_to_arms(|expr| quote::quote!(#expr));
+    let is_exhaustive = subject.is_discriminant_exhaustive();
+    let (subject, has_default) = subject.get_censored_subject_and_default_arm(Some(default_else));
+    let fallback_arm = (!is_exhaustive).then(|| quote::quote!(_ => #has_default));
+
+    (subject, matching_arms, fallback_arm.to_token_stream())
+}
+
+pub fn into_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let IntoTypes(tys) = parse_macro_input!(attr as IntoTypes);
+    let subject = parse_macro_input!(input as Subject);
+    let (subject, matching_arms, fallback_arm) =
+        discriminant_arms(subject, quote::quote!(Default::default()));
+    let enum_name = &subject.ident;
+
+    // Every target gets its own impl with the same arms, so a discriminant that doesn't fit one
+    // of the targets is reported at the discriminant itself.
+    let impls = tys.iter().map(|ty| {
+        quote::quote!(
+            impl Into<#ty> for #enum_name {
+                fn into(self) -> #ty {
+                    match self {
+                        #matching_arms
+                        #fallback_arm
+                    }
+                }
+            }
+        )
+    });
+
+    quote::quote!(
+        #subject
+
+        #(#impls)*
+    )
+    .to_token_stream()
+    .into()
+}
+
+/// One or more comma separated target types, e.g. `u32, u64, String`.
+struct IntoTypes(Punctuated<Type, Token![,]>);
+
+impl Parse for IntoTypes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let tys = Punctuated::parse_terminated(input)?;
+
+        if tys.is_empty() {
+            return Err(input.error("expected at least one target type"));
+        }
+
+        Ok(Self(tys))
+    }
+}
+
+pub fn try_into_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let TryIntoTypes { ty, error_ty } = parse_macro_input!(attr as TryIntoTypes);
+    let mut subject = parse_macro_input!(input as Subject);
+
+    // Variants without a discriminant end up in the `Err` branch.
+    let error = subject
+        .take_variant_discriminant(ERROR_VARIANT_SYMBOL)
+        .map(|expr| expr.to_token_stream())
+        .unwrap_or_else(|| quote::quote!(Default::default()));
+
+    let (subject, matching_arms, fallback_arm) =
+        discriminant_arms(subject, quote::quote!(return Err(#error)));
+    let enum_name = &subject.ident;
+
+    quote::quote!(
+        #subject
+
+        impl TryInto<#ty> for #enum_name {
+            type Error = #error_ty;
+
+            fn try_into(self) -> Result<#ty, Self::Error> {
+                Ok(match self {
+                    #matching_arms
+                    #fallback_arm
+                })
+            }
+        }
+    )
+    .to_token_stream()
+    .into()
+}
+
+/// `Target` or `Target, Error`, where the error type defaults to `()`.
+struct TryIntoTypes {
+    ty: Type,
+    error_ty: Type,
+}
+
+impl Parse for TryIntoTypes {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        let error_ty = if input.parse::<Option<Token![,]>>()?.is_some() {
+            input.parse()?
+        } else {
+            parse_quote!(())
+        };
+
+        Ok(Self { ty, error_ty })
+    }
+}
+
 pub fn deref_expand(
     attr: TokenStream,
     input: TokenStream,
@@ -110,9 +290,8 @@ pub fn deref_expand(
 ) -> TokenStream {
     let ty = parse_macro_input!(attr as Type);
     let subject = parse_macro_input!(input as Subject);
-    let matching_arms = subject.variants_to_arms(|expr| quote::quote!(#expr));
-    let (subject, has_default) =
-        subject.get_censored_subject_and_default_arm(Some(quote::quote!(Default::default())));
+    let (subject, matching_arms, fallback_arm) =
+        discriminant_arms(subject, quote::quote!(Default::default()));
     let enum_name = &subject.ident;
     let extensions = extend.map(|extend| extend(&subject));
 
@@ -124,7 +303,7 @@ pub fn deref_expand(
             fn deref(&self) -> &Self::Target {
                 match self {
                     #matching_arms
-                    _ => #has_default
+                    #fallback_arm
                 }
             }
         }
@@ -135,6 +314,135 @@ pub fn deref_expand(
     .into()
 }
 
+pub fn deref_mut_expand(
+    attr: TokenStream,
+    input: TokenStream,
+    extend: Option<fn(&Subject, &Type) -> proc_macro2::TokenStream>,
+) -> TokenStream {
+    let ty = parse_macro_input!(attr as Type);
+    let mut subject = parse_macro_input!(input as Subject);
+
+    // Because we match on `&mut self`, every field is bound as `&mut`. Variants without a field
+    // to borrow mutably from, i.e. the ones without a discriminant or with a literal, deref to
+    // the field that `default` names instead, e.g. `f0` given `default = f0`.
+    let default = subject.take_variant_discriminant(DEFAULT_VARIANT_SYMBOL);
+    let default_field = match &default {
+        Some(Expr::Path(path)) => path.path.get_ident(),
+        _ => None,
+    };
+
+    let mut errors: Option<Error> = None;
+    let mut matching_arms = vec![];
+    let mut matching_mut_arms = vec![];
+
+    for variant in subject.get_variants() {
+        let attrs = get_arm_attrs(variant);
+        let pattern = variant_pattern(variant);
+
+        let literal = match &variant.discriminant {
+            Some((_, expr @ Expr::Lit(_))) => Some(expr),
+            Some((_, expr)) => {
+                let arm = quote::quote!(#attrs #pattern => { #expr },);
+                matching_arms.push(arm.clone());
+                matching_mut_arms.push(arm);
+                continue;
+            }
+            None => None,
+        };
+
+        let Some(field) = default_field.filter(|field| has_field(variant, field)) else {
+            let error = Error::new_spanned(
+                &variant.ident,
+                deref_mut_field_missing(&variant.ident, default_field),
+            );
+
+            match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+            continue;
+        };
+
+        let arm = quote::quote!(#attrs #pattern => #field,);
+
+        matching_arms.push(match literal {
+            Some(expr) => quote::quote!(#attrs #pattern => { #expr },),
+            None => arm.clone(),
+        });
+        matching_mut_arms.push(arm);
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error().into();
+    }
+
+    let (subject, _) = subject.get_censored_subject_and_default_arm(None);
+    let enum_name = &subject.ident;
+    let extensions = extend.map(|extend| extend(&subject, &ty));
+
+    quote::quote!(
+        #subject
+
+        impl std::ops::Deref for #enum_name {
+            type Target = #ty;
+            fn deref(&self) -> &Self::Target {
+                match self {
+                    #(#matching_arms)*
+                }
+            }
+        }
+
+        impl std::ops::DerefMut for #enum_name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                match self {
+                    #(#matching_mut_arms)*
+                }
+            }
+        }
+
+        #extensions
+    )
+    .to_token_stream()
+    .into()
+}
+
+/// Checks if a variant has a field that is bound as `field` in its arms, e.g. `f0` or `name`.
+fn has_field(variant: &Variant, field: &Ident) -> bool {
+    variant
+        .fields
+        .iter()
+        .enumerate()
+        .any(|(index, item)| match &item.ident {
+            Some(ident) => ident == field,
+            None => field == &format!("f{index}"),
+        })
+}
+
+pub fn as_mut_str(input: TokenStream) -> TokenStream {
+    deref_mut_expand(
+        quote::quote!(str).into(),
+        input,
+        Some(|subject, _| {
+            let enum_name = &subject.ident;
+
+            quote::quote!(
+                impl AsRef<str> for #enum_name {
+                    fn as_ref(&self) -> &str { &**self }
+                }
+
+                impl AsMut<str> for #enum_name {
+                    fn as_mut(&mut self) -> &mut str { &mut **self }
+                }
+
+                impl #enum_name {
+                    fn as_str(&self) -> &str  { &**self }
+                    fn as_mut_str(&mut self) -> &mut str { &mut **self }
+                }
+            )
+        }),
+    )
+}
+
 pub fn static_str(input: TokenStream) -> TokenStream {
     deref_expand(
         quote::quote!(str).into(),
@@ -156,6 +464,256 @@ pub fn static_str(input: TokenStream) -> TokenStream {
     )
 }
 
+pub fn from_str_expand(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let error_ty: Type = if attr.is_empty() {
+        parse_quote!(())
+    } else {
+        parse_macro_input!(attr as Type)
+    };
+    let mut subject = parse_macro_input!(input as Subject);
+
+    // Unknown strings end up in the `Err` branch.
+    let error = subject
+        .take_variant_discriminant(ERROR_VARIANT_SYMBOL)
+        .map(|expr| expr.to_token_stream())
+        .unwrap_or_else(|| quote::quote!(Default::default()));
+
+    let mut errors: Option<Error> = None;
+    let mut matching_arms = vec![];
+
+    // The `default` variant is removed from the subject, like in the other services.
+    let variants = subject.get_variants().iter();
+    for variant in variants.filter(|variant| variant.ident != DEFAULT_VARIANT_SYMBOL) {
+        let name = &variant.ident;
+
+        match (&variant.fields, &variant.discriminant) {
+            (
+                Fields::Unit,
+                Some((
+                    _,
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit), ..
+                    }),
+                )),
+            ) => {
+                let attrs = get_arm_attrs(variant);
+                matching_arms.push(quote::quote!(#attrs #lit => Ok(Self::#name),))
+            }
+            (_, discriminant) => {
+                let error = match discriminant {
+                    Some((_, expr)) if variant.fields.is_empty() => {
+                        Error::new_spanned(expr, from_str_not_supported(name))
+                    }
+                    _ => Error::new_spanned(variant, from_str_not_supported(name)),
+                };
+
+                match errors.as_mut() {
+                    Some(errors) => errors.combine(error),
+                    None => errors = Some(error),
+                }
+            }
+        }
+    }
+
+    if let Some(errors) = errors {
+        return errors.to_compile_error().into();
+    }
+
+    let (subject, _) = subject.get_censored_subject_and_default_arm(None);
+    let enum_name = &subject.ident;
+
+    quote::quote!(
+        #subject
+
+        impl std::str::FromStr for #enum_name {
+            type Err = #error_ty;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    #(#matching_arms)*
+                    _ => Err(#error)
+                }
+            }
+        }
+    )
+    .to_token_stream()
+    .into()
+}
+
+pub fn default_expand(input: TokenStream) -> TokenStream {
+    let subject = parse_macro_input!(input as Subject);
+
+    let Some(variant) = subject.get_variants().first() else {
+        return syn::Error::new(
+            subject.ident.span(),
+            "Expected to find at least one variant.",
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    let variant_ident = &variant.ident;
+    let constructor = match &variant.fields {
+        Fields::Named(named) => {
+            let fields = named.named.iter().map(|field| &field.ident);
+            quote::quote!(Self::#variant_ident { #(#fields: Default::default()),* })
+        }
+        Fields::Unnamed(unnamed) => {
+            let fields = unnamed
+                .unnamed
+                .iter()
+                .map(|_| quote::quote!(Default::default()));
+            quote::quote!(Self::#variant_ident ( #(#fields),* ))
+        }
+        Fields::Unit => quote::quote!(Self::#variant_ident),
+    };
+
+    // Each field of the first variant needs to implement `Default`, which is only interesting
+    // to assert when the enum is generic.
+    let mut generics = subject.generics.clone();
+    if !generics.params.is_empty() {
+        let where_clause = generics.make_where_clause();
+        for field in variant.fields.iter() {
+            let ty = &field.ty;
+            where_clause.predicates.push(parse_quote!(#ty: Default));
+        }
+    }
+
+    let enum_name = &subject.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote::quote!(
+        #subject
+
+        impl #impl_generics Default for #enum_name #ty_generics #where_clause {
+            fn default() -> Self {
+                #constructor
+            }
+        }
+    )
+    .to_token_stream()
+    .into()
+}
+
+pub fn variant_count_expand(input: TokenStream) -> TokenStream {
+    let subject = parse_macro_input!(input as Subject);
+
+    if subject.is_struct() {
+        return syn::Error::new(subject.ident.span(), "Expected an enum.")
+            .to_compile_error()
+            .into();
+    }
+
+    // Each name carries the `cfg` attributes of its variant, so that disabled variants are
+    // neither named nor counted.
+    let names = subject.get_variants().iter().map(|variant| {
+        let attrs = get_arm_attrs(variant);
+        let name = variant.ident.get_string();
+        quote::quote!(#attrs #name)
+    });
+
+    // Every variant discriminates to its own name, which `variants_to_arms` turns into arms.
+    let mut named = subject.clone();
+    for variant in named.data.variants.iter_mut() {
+        let name = variant.ident.get_string();
+        variant.discriminant = Some((Default::default(), parse_quote!(#name)));
+    }
+    let arms = named.variants_to_arms(|name| name.to_token_stream());
+
+    // An empty enum can only be matched without any arms when dereferenced.
+    let scrutinee = match subject.get_variants().is_empty() {
+        true => quote::quote!(*self),
+        false => quote::quote!(self),
+    };
+
+    let vis = &subject.vis;
+    let enum_name = &subject.ident;
+    let (impl_generics, ty_generics, where_clause) = subject.generics.split_for_impl();
+
+    quote::quote!(
+        #subject
+
+        impl #impl_generics #enum_name #ty_generics #where_clause {
+            #vis const VARIANT_COUNT: usize = Self::VARIANT_NAMES.len();
+            #vis const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+
+            #vis fn variant_name(&self) -> &'static str {
+                match #scrutinee {
+                    #arms
+                }
+            }
+        }
+    )
+    .to_token_stream()
+    .into()
+}
+
+pub fn family_expand(input: TokenStream) -> TokenStream {
+    let members = parse_macro_input!(input with Punctuated::<Ident, Token![,]>::parse_terminated);
+
+    let error = Diagnostic::default();
+    let mut claimed: Vec<(&Ident, String)> = vec![];
+
+    for member in members.iter() {
+        let Some(shape) = E_SHM.find(&member.get_string()) else {
+            error.extend(
+                member.span(),
+                format!("`{member}` cannot be found. Make sure the enum is tagged with a `#[penum(..)]` pattern, and is declared before the family."),
+            );
+            continue;
+        };
+
+        if let Some((other, _)) = claimed.iter().find(|(_, other)| *other == shape) {
+            error.extend(
+                member.span(),
+                format!("`{member}` claims the same shape as `{other}`: `{shape}`"),
+            );
+        }
+
+        claimed.push((member, shape));
+    }
+
+    error
+        .map(Error::to_compile_error)
+        .unwrap_or_default()
+        .into()
+}
+
+pub fn pattern_expand(input: TokenStream) -> TokenStream {
+    let NamedPattern { name, expr } = parse_macro_input!(input as NamedPattern);
+
+    P_SHM.insert(name.get_string(), expr.get_string());
+
+    TokenStream::new()
+}
+
+pub fn shape_expand(input: TokenStream) -> TokenStream {
+    let NamedPattern { name, expr } = parse_macro_input!(input as NamedPattern);
+
+    S_SHM.insert(name.get_string(), expr.get_string());
+
+    TokenStream::new()
+}
+
+/// A pattern with a name, e.g. `Shape = (T, U) where T: Trait`. The pattern is validated when
+/// it's registered, but stored as tokens so that it can be parsed again where it's used.
+struct NamedPattern {
+    name: Ident,
+    expr: proc_macro2::TokenStream,
+}
+
+impl Parse for NamedPattern {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let expr: proc_macro2::TokenStream = input.parse()?;
+
+        syn::parse2::<PenumExpr>(expr.clone())?;
+
+        Ok(Self { name, expr })
+    }
+}
+
 /// UNDER DEVELOPMENT
 pub fn lazy_string(input: TokenStream) -> TokenStream {
     let subject = parse_macro_input!(input as Subject);
@@ -1,13 +1,14 @@
 #![allow(unused)]
 use std::{
-    cell::UnsafeCell,
+    cell::{RefCell, UnsafeCell},
     collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet},
     hash::{Hash, Hasher},
+    iter::zip,
     ops::Deref,
     sync::Once,
 };
 
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, ToTokens};
 use syn::{
     braced,
@@ -16,17 +17,58 @@ use syn::{
     punctuated::Punctuated,
     spanned::Spanned,
     token::{self, Add},
-    Expr, Fields, Token, TraitBound, Type, TypeImplTrait, TypeParamBound, Variant, WhereClause,
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+    Expr, ExprPath, Fields, GenericArgument, GenericParam, Generics, Lifetime, PathArguments,
+    Signature, Token, TraitBound, Type, TypeImplTrait, TypeParamBound, TypePath, TypeTraitObject,
+    Variant, WhereClause, WherePredicate,
 };
 
 use crate::{
     error::Diagnostic,
-    factory::{PatComposite, PatFrag, Subject},
+    factory::{PatComposite, PatFrag, Subject, VariantSelector},
     polym::UniqueHashId,
 };
 
 pub const DEFAULT_VARIANT_SYMBOL: &str = "default";
+pub const ERROR_VARIANT_SYMBOL: &str = "error";
 pub const ABSTRACT_MACRO_EXPR_SYMBOL: &str = "implement";
+/// How far off, in arity and mismatched field types, a pattern can be from a variant while still
+/// being suggested as the closest pattern.
+pub const CLOSEST_PATTERN_DISTANCE: usize = 2;
+pub const PENUM_OPTIONS: &[&str] = &[
+    "impl_display_from",
+    "name_of_index",
+    "via_deref",
+    "strict",
+    "uniform",
+    "subject_generics",
+    "alias",
+    "fragment_limit",
+];
+
+/// Soft limit on the number of pattern fragments in one penum expression. Exceeding it only
+/// emits a warning. Can be configured with the `fragment_limit(N)` option.
+pub const DEFAULT_FRAGMENT_LIMIT: usize = 64;
+
+/// Used to check if the pattern is followed by an option, e.g. `, alias(Bytes = Vec<u8>)` in
+/// `(T), alias(Bytes = Vec<u8>)`, so that it isn't read as another type or predicate.
+pub fn peek_trailing_option(input: ParseStream) -> bool {
+    let fork = input.fork();
+
+    fork.parse::<Token![,]>().is_ok()
+        && matches!(
+            fork.parse::<Ident>(),
+            Ok(ident) if PENUM_OPTIONS.contains(&ident.to_string().as_str())
+        )
+}
+
+pub fn fragment_limit_exceeded(count: usize, limit: usize) -> String {
+    format!(
+        "penum expression contains {count} pattern fragments which exceeds the limit of {limit}. \
+        This can slow down compile times, consider using fewer fragments or raise the limit with `fragment_limit(N)`."
+    )
+}
 
 pub fn no_match_found(item: &impl ToTokens, pat: &str) -> String {
     format!(
@@ -36,21 +78,804 @@ pub fn no_match_found(item: &impl ToTokens, pat: &str) -> String {
     )
 }
 
+pub fn closest_pattern_mismatch(
+    item: &impl ToTokens,
+    pat: &impl ToTokens,
+    position: usize,
+    expected: &TokenStream,
+    found: &TokenStream,
+) -> String {
+    let describe = |tokens: &TokenStream| {
+        if tokens.is_empty() {
+            "nothing".to_string()
+        } else {
+            format!("`{tokens}`")
+        }
+    };
+
+    format!(
+        "`{}` doesn't match any pattern, closest pattern `{}` — field {} expected {}, found {}",
+        item.to_token_stream(),
+        pat.to_token_stream(),
+        position,
+        describe(expected),
+        describe(found)
+    )
+}
+
+pub fn almost_matched(pat: &impl ToTokens, expected: usize, found: usize) -> String {
+    format!(
+        "pattern `{}` almost matched: expected {} field{}, found {}",
+        pat.to_token_stream(),
+        expected,
+        if expected == 1 { "" } else { "s" },
+        found
+    )
+}
+
+pub fn in_fragment(error: String, position: usize, fragment: &impl ToTokens) -> String {
+    format!(
+        "{} — in fragment {} `{}`",
+        error.trim_end_matches('.'),
+        position,
+        fragment.to_token_stream()
+    )
+}
+
+pub fn missing_named_field(field: &Ident) -> String {
+    format!("missing field `{field}`")
+}
+
+pub fn unexpected_named_field(field: &Ident) -> String {
+    format!("unexpected field `{field}`")
+}
+
+pub fn type_mismatch(found: &Type, expected: &Type) -> String {
+    format!(
+        "Found `{}` but expected `{}`.",
+        get_type_string(found),
+        get_type_string(expected)
+    )
+}
+
+pub fn negated_type_matched(ty: &Type) -> String {
+    format!("field must not be `{}`", ty.get_string())
+}
+
+pub fn pattern_not_registered(name: &Ident) -> String {
+    format!("`{name}` is not a registered pattern. Make sure it's declared with `penum::pattern!` before it's used.")
+}
+
+pub fn shape_not_registered(name: &Ident) -> String {
+    format!("`{name}` is not a registered shape. Make sure it's declared with `penum::shape!` before it's used.")
+}
+
+pub fn variant_not_found(selector: &VariantSelector, subject: &Ident) -> String {
+    format!(
+        "`{selector}` doesn't match any variant of `{subject}`, so the pattern can never be matched"
+    )
+}
+
+pub fn generic_not_in_fragment(generic: &Ident, variant: &Ident) -> String {
+    format!("`{generic}` isn't part of the fragment that `{variant}` matches, so it can't be bounded by its clause")
+}
+
+pub fn generic_never_bound(generic: &Ident) -> String {
+    format!("`{generic}` isn't bound by any variant, so its bounds are never asserted")
+}
+
+pub fn generic_not_in_pattern(generic: &Ident, suggestion: Option<&Ident>) -> String {
+    let hint = suggestion
+        .map(|ident| format!(", did you mean `{ident}`?"))
+        .unwrap_or_default();
+
+    format!("`{generic}` isn't used by any fragment of the pattern, so it can't be bounded{hint}")
+}
+
+pub fn generic_not_uniform(generic: &Ident, ty: &Type, bound: &Type, variant: &Ident) -> String {
+    format!(
+        "`{generic}` is `{}` here, but `{}` in `{variant}`. Every variant has to bind it to the same type",
+        get_type_string(ty),
+        get_type_string(bound)
+    )
+}
+
+pub fn generic_declared_twice(generic: &Ident) -> String {
+    format!("`{generic}` is declared more than once")
+}
+
+pub fn generic_not_declared(generic: &Ident) -> String {
+    format!("`{generic}` isn't declared, add it to `for<..>` in front of the pattern")
+}
+
+pub fn generic_never_used(generic: &Ident) -> String {
+    format!("`{generic}` is declared but never used in the pattern")
+}
+
+pub fn variant_rejected(variant: &Ident, pat: &impl ToTokens) -> String {
+    format!(
+        "`{variant}` matches the negated pattern `{}`",
+        pat.to_token_stream()
+    )
+}
+
+pub fn discriminant_out_of_range(variant: &Ident, value: i128, range: &impl ToTokens) -> String {
+    format!(
+        "discriminant `{value}` of `{variant}` is outside of `{}`",
+        range.to_token_stream()
+    )
+}
+
+pub fn discriminant_already_used(variant: &Ident, value: i128, other: &Ident) -> String {
+    format!("discriminant `{value}` of `{variant}` is already used by `{other}`")
+}
+
+pub fn pattern_never_matched(pat: &impl ToTokens) -> String {
+    format!(
+        "pattern `{}` isn't matched by any variant",
+        pat.to_token_stream()
+    )
+}
+
+pub fn pattern_unreachable(pat: &impl ToTokens, shadowed_by: &impl ToTokens) -> String {
+    format!(
+        "unreachable pattern, `{}` is already matched by `{}`",
+        pat.to_token_stream(),
+        shadowed_by.to_token_stream()
+    )
+}
+
+pub fn from_str_not_supported(variant: &Ident) -> String {
+    format!(
+        "`{variant}` can't be parsed from a string, only unit variants with a string literal discriminant can"
+    )
+}
+
+pub fn too_few_fields(item: &impl ToTokens, minimum: usize, pat: &str) -> String {
+    format!(
+        "`{}` has too few fields for pattern `{}`, expected at least {}",
+        item.to_token_stream(),
+        pat,
+        minimum
+    )
+}
+
+pub fn too_many_fields(item: &impl ToTokens, maximum: usize, pat: &str) -> String {
+    format!(
+        "`{}` has too many fields for pattern `{}`, expected at most {}",
+        item.to_token_stream(),
+        pat,
+        maximum
+    )
+}
+
 pub fn maybe_bounds_not_permitted(trait_bound: &TraitBound) -> String {
     format!(
-        "`?{}` bounds are only permitted at the point where a type parameter is declared",
+        "`?{}` can't be relaxed, only `?Sized` can",
         trait_bound.path.get_string()
     )
 }
 
-pub fn lifetime_not_permitted() -> &'static str {
-    "Lifetime annotation not permitted"
+pub fn assoc_const_not_resolved(ident: &Ident, trait_path: &impl ToTokens) -> String {
+    format!(
+        "Associated const `{ident}` of `{}` cannot be forwarded without a matching variant. Give it a default value in the trait instead.",
+        trait_path.to_token_stream()
+    )
+}
+
+pub fn assoc_type_ambiguous(ident: &Ident, trait_path: &impl ToTokens) -> String {
+    format!(
+        "Cannot infer associated type `{ident}` of `{}` given that the impls of the dispatched types disagree on it. Bind it in the bound instead, e.g. `{ident} = ..`.",
+        trait_path.to_token_stream()
+    )
+}
+
+pub fn method_not_forwardable(trait_ident: &Ident, sig: &Signature) -> String {
+    let reason = if sig.receiver().is_none() {
+        "associated functions without `self` are unsupported"
+    } else {
+        "methods returning `Self` would return the inner value instead"
+    };
+
+    format!(
+        "Cannot auto-dispatch `{trait_ident}::{}` given that {reason}. Give it a default body in the trait instead.",
+        sig.ident
+    )
+}
+
+pub fn method_not_derefable(trait_ident: &Ident, sig: &Signature) -> String {
+    format!(
+        "Cannot dispatch `{trait_ident}::{}` through `via_deref` given that it takes `self` by value, which can't be moved out of the deref target. Give it a default body in the trait instead.",
+        sig.ident
+    )
+}
+
+pub fn alias_not_resolvable(alias: &Type) -> String {
+    format!(
+        "`{}` refers back to itself, so it cannot be resolved to another type.",
+        get_type_string(alias)
+    )
+}
+
+pub fn deref_mut_field_missing(variant: &Ident, field: Option<&Ident>) -> String {
+    match field {
+        Some(field) => format!(
+            "`{variant}` has no field `{field}` to borrow mutably from. Name one of its fields in its discriminant instead, e.g. `= f0`."
+        ),
+        None => format!(
+            "`{variant}` has no field to borrow mutably from. Name one in its discriminant, e.g. `= f0`, or name the field that such variants deref to with `default = f0`."
+        ),
+    }
+}
+
+pub fn lifetime_not_declared(lifetime: &Lifetime, subject: &Ident) -> String {
+    format!(
+        "`{lifetime}` is not declared on `{subject}`, so the lifetime predicate cannot be resolved"
+    )
+}
+
+/// Tuple fields are bound as `f0`, `f1`, .. so positional arguments in a format string are
+/// rewritten to refer to them by name, e.g. `"id {0} {}"` becomes `"id {f0} {f0}"`. Anything
+/// else than a string literal is returned as it is.
+pub fn bind_positional_args(expr: &Expr) -> Expr {
+    let Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit),
+        ..
+    }) = expr
+    else {
+        return expr.clone();
+    };
+
+    let value = lit.value();
+    let mut chars = value.chars().peekable();
+    let mut rewritten = String::with_capacity(value.len());
+    let mut implicit = 0;
+
+    while let Some(c) = chars.next() {
+        rewritten.push(c);
+
+        match c {
+            '{' if chars.peek() == Some(&'{') => rewritten.push(chars.next().unwrap()),
+            '{' => {
+                let mut index = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    index.push(digit);
+                }
+
+                match chars.peek() {
+                    Some('}' | ':') if index.is_empty() => {
+                        rewritten.push_str(&format!("f{implicit}"));
+                        implicit += 1;
+                    }
+                    Some('}' | ':') => rewritten.push_str(&format!("f{index}")),
+                    _ => rewritten.push_str(&index),
+                }
+            }
+            '}' if chars.peek() == Some(&'}') => rewritten.push(chars.next().unwrap()),
+            _ => (),
+        }
+    }
+
+    let lit = syn::LitStr::new(&rewritten, lit.span());
+    parse_quote!(#lit)
+}
+
+pub fn nested_projection_not_supported(ty: &Type) -> String {
+    format!(
+        "`{}` is a nested projection, only a single associated type can be bounded, e.g. `T::Item`",
+        ty.get_string()
+    )
+}
+
+pub fn projection_trait_ambiguous(ty: &Type, generic: &Type) -> String {
+    format!(
+        "Cannot tell which trait of `{generic}` declares `{ty}`. Bound `{generic}` by exactly one trait, or use `<{generic} as Trait>::..` instead.",
+        ty = ty.get_string(),
+        generic = generic.get_string(),
+    )
+}
+
+/// An associated type projected from a generic, e.g. `T::Item` or `<T as Iterator>::Item`.
+pub struct Projection {
+    /// The generic that we project from, e.g. `T`.
+    pub generic: Type,
+
+    /// The trait that declares the associated type, if it's fully-qualified.
+    pub trait_path: Option<syn::Path>,
+
+    /// The associated type, e.g. `Item`.
+    pub assoc: syn::PathSegment,
+}
+
+/// Returns the projection of a bounded type in our where clause if it's projected from a
+/// generic. Nested projections, e.g. `T::Item::Output`, return `Err`.
+pub fn get_projection(ty: &Type) -> Option<Result<Projection, ()>> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+
+    let segments = &type_path.path.segments;
+
+    match &type_path.qself {
+        Some(qself) if qself.ty.is_generic() => {
+            if segments.len() != qself.position + 1 {
+                return Some(Err(()));
+            }
+
+            let mut trait_path = type_path.path.clone();
+            let assoc = trait_path.segments.pop()?.into_value();
+            // Drop the trailing `::` that is left behind by the associated type.
+            trait_path.segments = trait_path.segments.into_iter().collect();
+
+            Some(Ok(Projection {
+                generic: (*qself.ty).clone(),
+                trait_path: Some(trait_path),
+                assoc,
+            }))
+        }
+        None if segments.len() > 1 => {
+            let first = segments.first()?;
+            let generic: Type = Type::Path(syn::TypePath {
+                qself: None,
+                path: first.ident.clone().into(),
+            });
+
+            if !first.arguments.is_empty() || !generic.is_generic() {
+                return None;
+            }
+
+            if segments.len() != 2 {
+                return Some(Err(()));
+            }
+
+            Some(Ok(Projection {
+                generic,
+                trait_path: None,
+                assoc: segments.last()?.clone(),
+            }))
+        }
+        _ => None,
+    }
 }
 
 pub fn create_unique_ident(value: &str, tag: &Ident, span: Span) -> Ident {
     format_ident!("_{}_{}", tag, value, span = span)
 }
 
+thread_local! {
+    /// The type and const parameters of the subject that is being assembled, e.g. `T` and `N` in
+    /// `enum E<T, const N: usize>`.
+    ///
+    /// NOTE: These are kept as strings, since an `Ident` can't outlive the expansion it's from.
+    static SUBJECT_GENERICS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+/// Sets the type and const parameters of the subject, which pattern generics of the same name
+/// stand for instead of being bound to the types of each variant, e.g. `T` in `(T) | (Vec<T>)`
+/// given `enum E<T>`. It's left empty unless the `subject_generics` option is given, and is
+/// cleared once the tokens of the subject have been generated.
+pub fn set_subject_generics(generics: Vec<Ident>) {
+    SUBJECT_GENERICS.with(|cell| {
+        *cell.borrow_mut() = generics.iter().map(Ident::to_string).collect();
+    });
+}
+
+fn is_subject_generic(ident: &Ident) -> bool {
+    SUBJECT_GENERICS.with(|cell| cell.borrow().iter().any(|generic| ident == generic))
+}
+
+/// The type aliases of a penum expression, e.g. `Bytes = Vec<u8>`, each paired with the type it
+/// stands for. Aliases within a target are resolved up front, e.g. `Ids = Vec<Id>` stands for
+/// `Vec<u64>` given `Id = u64`, so every alias is only resolved once.
+#[derive(Default, Debug)]
+pub struct TypeAliases(Vec<(Type, Type)>);
+
+impl TypeAliases {
+    /// Resolves the aliases in the order they depend on each other, and rejects aliases that
+    /// refer back to themselves, e.g. `A = Vec<A>` or `A = B, B = A`.
+    pub fn new(aliases: Vec<(Type, Type)>) -> syn::Result<Self> {
+        let mut resolved = Self::default();
+        let mut pending = aliases;
+
+        while !pending.is_empty() {
+            // An alias can be resolved once none of the pending aliases are left in its target.
+            let (ready, blocked): (Vec<_>, Vec<_>) =
+                pending.iter().cloned().partition(|(_, target)| {
+                    !pending
+                        .iter()
+                        .any(|(alias, _)| contains_type(target, alias))
+                });
+
+            if let (None, Some((alias, _))) = (ready.first(), blocked.first()) {
+                return Err(syn::Error::new(alias.span(), alias_not_resolvable(alias)));
+            }
+
+            for (alias, mut target) in ready {
+                resolved.resolve(&mut target);
+                resolved.0.push((alias, target));
+            }
+
+            pending = blocked;
+        }
+
+        Ok(resolved)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Replaces every alias within `ty` with the type it stands for, e.g. `Vec<Bytes>` becomes
+    /// `Vec<Vec<u8>>`.
+    pub fn resolve(&self, ty: &mut Type) {
+        ResolveTypeAliases(self).visit_type_mut(ty);
+    }
+
+    /// Like `resolve`, but leaves `ty` itself as it is and only resolves the types within it,
+    /// e.g. `Bytes` in the trait `AsRef<Bytes>`.
+    pub fn resolve_nested(&self, ty: &mut Type) {
+        visit_mut::visit_type_mut(&mut ResolveTypeAliases(self), ty);
+    }
+
+    fn find(&self, ty: &Type) -> Option<&Type> {
+        let ty = ty.get_string();
+        self.0
+            .iter()
+            .find(|(alias, _)| alias.get_string() == ty)
+            .map(|(_, target)| target)
+    }
+}
+
+struct ResolveTypeAliases<'a>(&'a TypeAliases);
+
+impl VisitMut for ResolveTypeAliases<'_> {
+    fn visit_type_mut(&mut self, node: &mut Type) {
+        match self.0.find(node) {
+            // Targets are already resolved, so they aren't visited again.
+            Some(target) => *node = target.clone(),
+            None => visit_mut::visit_type_mut(self, node),
+        }
+    }
+}
+
+/// Checks if `ty` is, or contains, `other`, e.g. `A` in `Vec<A>`.
+fn contains_type(ty: &Type, other: &Type) -> bool {
+    struct FindType(String, bool);
+
+    impl<'ast> Visit<'ast> for FindType {
+        fn visit_type(&mut self, node: &'ast Type) {
+            self.1 |= node.get_string() == self.0;
+            visit::visit_type(self, node);
+        }
+    }
+
+    let mut find = FindType(other.get_string(), false);
+    find.visit_type(ty);
+    find.1
+}
+
+/// Paths of the std types that are in the prelude, which are compared as if they were written
+/// without their path, e.g. `std::string::String` as `String`.
+const PRELUDE_PATHS: &[&str] = &[
+    "std::boxed::Box",
+    "std::string::String",
+    "std::vec::Vec",
+    "std::option::Option",
+    "std::result::Result",
+    "alloc::boxed::Box",
+    "alloc::string::String",
+    "alloc::vec::Vec",
+    "core::option::Option",
+    "core::result::Result",
+];
+
+/// Returns the number of leading segments that name the module of a prelude type, e.g. two for
+/// `::std::string::String`, and zero for any other path.
+fn get_prelude_prefix_len(path: &syn::Path) -> usize {
+    let idents = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+
+    let prefix_has_arguments = path
+        .segments
+        .iter()
+        .rev()
+        .skip(1)
+        .any(|segment| !segment.arguments.is_empty());
+
+    if !prefix_has_arguments && PRELUDE_PATHS.contains(&idents.as_str()) {
+        path.segments.len() - 1
+    } else {
+        0
+    }
+}
+
+/// Sorts the bounds of trait objects and adds any missing `dyn`, i.e. `Send + dyn Display`
+/// becomes `dyn Display + Send`, and strips the path of prelude types, i.e. `std::string::String`
+/// becomes `String`.
+struct NormalizeType;
+
+impl VisitMut for NormalizeType {
+    fn visit_type_trait_object_mut(&mut self, node: &mut TypeTraitObject) {
+        visit_mut::visit_type_trait_object_mut(self, node);
+
+        let mut bounds = std::mem::take(&mut node.bounds)
+            .into_iter()
+            .collect::<Vec<_>>();
+        bounds.sort_by_key(|bound| bound.get_string());
+
+        node.dyn_token = Some(Default::default());
+        node.bounds = bounds.into_iter().collect();
+    }
+
+    fn visit_type_path_mut(&mut self, node: &mut TypePath) {
+        visit_mut::visit_type_path_mut(self, node);
+
+        let prefix_len = get_prelude_prefix_len(&node.path);
+
+        if node.qself.is_none() && prefix_len > 0 {
+            node.path.leading_colon = None;
+            node.path.segments = std::mem::take(&mut node.path.segments)
+                .into_iter()
+                .skip(prefix_len)
+                .collect();
+        }
+    }
+}
+
+/// Qualifies the associated types of our generics with the trait that declares them, i.e.
+/// `Vec<T::Item>` becomes `Vec<<T as Iterator>::Item>`, so that `T` can be substituted like any
+/// other type. The trait is looked up by the closure, which is given the projection and its
+/// generic.
+pub struct QualifyProjections<F>(pub F);
+
+impl<F: FnMut(&Type, &Type) -> Option<syn::Path>> VisitMut for QualifyProjections<F> {
+    fn visit_type_mut(&mut self, node: &mut Type) {
+        visit_mut::visit_type_mut(self, node);
+
+        if let Some(Ok(Projection {
+            generic,
+            trait_path: None,
+            assoc,
+        })) = get_projection(node)
+        {
+            if let Some(trait_path) = (self.0)(node, &generic) {
+                *node = parse_quote!(<#generic as #trait_path>::#assoc);
+            }
+        }
+    }
+}
+
+/// Prints a type the way it's usually written, e.g. `Box<dyn Display>` instead of the spaced out
+/// `Box < dyn Display >` we get from its tokens.
+pub fn get_type_string(ty: &Type) -> String {
+    ty.get_string()
+        .replace(" < ", "<")
+        .replace(" <", "<")
+        .replace(" >", ">")
+        .replace(" , ", ", ")
+        .replace(" ;", ";")
+        .replace(" :: ", "::")
+        .replace(":: ", "::")
+        .replace("& ", "&")
+}
+
+/// Unifies a pattern type with the type of a field, binding the pattern generics that it
+/// contains to the matching parts of the field type, e.g. `T` to `i32` given `Vec<T>` and
+/// `Vec<i32>`. Returns false if the types don't line up, or if a generic would be bound to two
+/// different types, e.g. `HashMap<T, T>` and `HashMap<i32, u8>`.
+pub fn unify_types(pattern: &Type, item: &Type, bindings: &mut BTreeMap<Ident, Type>) -> bool {
+    if let Some(ident) = as_generic_ident(pattern) {
+        return match bindings.get(ident) {
+            Some(bound) => bound.get_unique_id() == item.get_unique_id(),
+            None => {
+                bindings.insert(ident.clone(), item.clone());
+                true
+            }
+        };
+    }
+
+    match (pattern, item) {
+        (Type::Paren(pattern), _) => unify_types(&pattern.elem, item, bindings),
+        (_, Type::Paren(item)) => unify_types(pattern, &item.elem, bindings),
+        (Type::Infer(_), _) => true,
+        (Type::Path(pattern), Type::Path(item))
+            if pattern.qself.is_none() && item.qself.is_none() =>
+        {
+            // Prelude types are unified without their path, e.g. `Vec<T>` and `std::vec::Vec<i32>`.
+            let pattern_segments = pattern
+                .path
+                .segments
+                .iter()
+                .skip(get_prelude_prefix_len(&pattern.path));
+            let item_segments = item
+                .path
+                .segments
+                .iter()
+                .skip(get_prelude_prefix_len(&item.path));
+
+            pattern_segments.len() == item_segments.len()
+                && zip(pattern_segments, item_segments).all(|(pattern, item)| {
+                    pattern.ident == item.ident
+                        && unify_path_arguments(&pattern.arguments, &item.arguments, bindings)
+                })
+        }
+        (Type::Reference(pattern), Type::Reference(item)) => {
+            pattern.mutability.is_some() == item.mutability.is_some()
+                && unify_lifetimes(pattern.lifetime.as_ref(), item.lifetime.as_ref())
+                && unify_types(&pattern.elem, &item.elem, bindings)
+        }
+        (Type::Slice(pattern), Type::Slice(item)) => {
+            unify_types(&pattern.elem, &item.elem, bindings)
+        }
+        (Type::Array(pattern), Type::Array(item)) => {
+            unify_array_lengths(&pattern.len, &item.len, bindings)
+                && unify_types(&pattern.elem, &item.elem, bindings)
+        }
+        (Type::Tuple(pattern), Type::Tuple(item)) => {
+            pattern.elems.len() == item.elems.len()
+                && zip(&pattern.elems, &item.elems)
+                    .all(|(pattern, item)| unify_types(pattern, item, bindings))
+        }
+        _ => pattern.get_unique_id() == item.get_unique_id(),
+    }
+}
+
+fn unify_path_arguments(
+    pattern: &PathArguments,
+    item: &PathArguments,
+    bindings: &mut BTreeMap<Ident, Type>,
+) -> bool {
+    match (pattern, item) {
+        (PathArguments::None, PathArguments::None) => true,
+        (PathArguments::AngleBracketed(pattern), PathArguments::AngleBracketed(item)) => {
+            pattern.args.len() == item.args.len()
+                && zip(&pattern.args, &item.args).all(|(pattern, item)| match (pattern, item) {
+                    (GenericArgument::Type(pattern), GenericArgument::Type(item)) => {
+                        unify_types(pattern, item, bindings)
+                    }
+                    (GenericArgument::Lifetime(pattern), GenericArgument::Lifetime(item)) => {
+                        unify_lifetimes(Some(pattern), Some(item))
+                    }
+                    // A const generic in the pattern, e.g. `N` in `Buf<N>`, is parsed as a type,
+                    // but binds to const arguments, e.g. `3` in `Buf<3>`, like array lengths do.
+                    (GenericArgument::Type(Type::Path(pattern)), GenericArgument::Const(item))
+                        if pattern.qself.is_none() =>
+                    {
+                        let pattern = Expr::Path(ExprPath {
+                            attrs: vec![],
+                            qself: None,
+                            path: pattern.path.clone(),
+                        });
+
+                        unify_array_lengths(&pattern, item, bindings)
+                    }
+                    (pattern, item) => pattern.get_string() == item.get_string(),
+                })
+        }
+        (pattern, item) => pattern.get_string() == item.get_string(),
+    }
+}
+
+/// An uppercase length in an array pattern, e.g. `N` in `[T; N]`, binds to the length of the
+/// item, or to a const argument, e.g. `3` in `Buf<3>`. The length is kept as a verbatim type so that it can share bindings with generics.
+fn unify_array_lengths(pattern: &Expr, item: &Expr, bindings: &mut BTreeMap<Ident, Type>) -> bool {
+    let Some(ident) = as_const_generic_ident(pattern) else {
+        return pattern.get_string() == item.get_string();
+    };
+
+    let length = Type::Verbatim(item.to_token_stream());
+
+    match bindings.get(ident) {
+        Some(bound) => bound.get_string() == length.get_string(),
+        None => {
+            bindings.insert(ident.clone(), length);
+            true
+        }
+    }
+}
+
+/// Returns the ident of an expression that is a single const generic, e.g. `N`.
+fn as_const_generic_ident(expr: &Expr) -> Option<&Ident> {
+    let Expr::Path(ExprPath {
+        qself: None, path, ..
+    }) = expr
+    else {
+        return None;
+    };
+
+    let ident = path.get_ident()?;
+    let name = ident.to_string();
+    (name.to_uppercase() == name && !is_subject_generic(ident)).then_some(ident)
+}
+
+/// Lifetimes in a pattern bind to the lifetime of the item, so `&'a str` and `&str` match
+/// `&'b str`. Only `'static` has to be the same.
+fn unify_lifetimes(pattern: Option<&Lifetime>, item: Option<&Lifetime>) -> bool {
+    match pattern {
+        Some(pattern) if pattern.ident == "static" => {
+            item.is_some_and(|item| item.ident == "static")
+        }
+        _ => true,
+    }
+}
+
+/// Returns the ids of a type and every type nested within it, e.g. `Vec<Thing>` and `Thing`.
+pub fn get_nested_type_ids(ty: &Type) -> BTreeSet<UniqueHashId<Type>> {
+    struct NestedTypes(BTreeSet<UniqueHashId<Type>>);
+
+    impl<'ast> Visit<'ast> for NestedTypes {
+        fn visit_type(&mut self, node: &'ast Type) {
+            self.0.insert(node.get_unique_id());
+            visit::visit_type(self, node);
+        }
+    }
+
+    let mut nested = NestedTypes(BTreeSet::new());
+    nested.visit_type(ty);
+    nested.0
+}
+
+/// Returns the tuple indices that lead to a pattern generic, e.g. `[1]` for `U` in `(T, U)`.
+/// References are looked through since both field access and method calls auto-deref, so `T`
+/// in `&'a T` is found at `[]`.
+pub fn find_generic_path(pattern: &Type, ident: &Ident) -> Option<Vec<usize>> {
+    match pattern {
+        _ if as_generic_ident(pattern) == Some(ident) => Some(Vec::new()),
+        Type::Paren(pattern) => find_generic_path(&pattern.elem, ident),
+        Type::Reference(pattern) => find_generic_path(&pattern.elem, ident),
+        Type::Tuple(pattern) => pattern.elems.iter().enumerate().find_map(|(index, elem)| {
+            let mut path = find_generic_path(elem, ident)?;
+            path.insert(0, index);
+            Some(path)
+        }),
+        _ => None,
+    }
+}
+
+/// Returns every pattern generic that a type mentions, e.g. `T` and `U` in `Result<T, Vec<U>>`,
+/// including the ones in front of an associated type, e.g. `T` in `T::Item`.
+pub fn find_generics(ty: &Type) -> Vec<&Ident> {
+    struct Generics<'ast>(Vec<&'ast Ident>);
+
+    impl<'ast> Visit<'ast> for Generics<'ast> {
+        fn visit_type_path(&mut self, node: &'ast TypePath) {
+            if let Some(segment) = node
+                .path
+                .segments
+                .first()
+                .filter(|segment| node.qself.is_none() && segment.arguments.is_empty())
+            {
+                let name = segment.ident.to_string();
+
+                if name.to_uppercase() == name {
+                    self.0.push(&segment.ident);
+                }
+            }
+
+            visit::visit_type_path(self, node);
+        }
+    }
+
+    let mut generics = Generics(vec![]);
+    generics.visit_type(ty);
+    generics.0
+}
+
+/// Returns the ident of a type that is a single pattern generic, e.g. `T`.
+fn as_generic_ident(ty: &Type) -> Option<&Ident> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+
+    let ident = path.get_ident()?;
+    let name = ident.to_string();
+    (name.to_uppercase() == name && !is_subject_generic(ident)).then_some(ident)
+}
+
 // NOTE: I will eventually clean this mess up
 pub trait Stringify: ToTokens {
     fn get_string(&self) -> String {
@@ -63,12 +888,15 @@ impl<T> Stringify for T where T: ToTokens {}
 pub trait TypeUtils {
     fn is_generic(&self) -> bool;
     fn is_placeholder(&self) -> bool;
+    fn is_dispatchable_inline(&self) -> bool;
     #[allow(dead_code)]
     fn some_generic(&self) -> Option<String>;
     #[allow(dead_code)]
     fn get_generic_ident(&self) -> Ident;
     fn get_unique_id(&self) -> UniqueHashId<Type>;
     fn get_type_impl_trait(&self) -> Option<&TypeImplTrait>;
+    fn is_self(&self) -> bool;
+    fn is_same_path(&self, other: &Type) -> bool;
 }
 
 impl TypeUtils for Type {
@@ -80,15 +908,26 @@ impl TypeUtils for Type {
         }
     }
 
+    fn is_self(&self) -> bool {
+        matches!(self, Type::Path(TypePath { qself: None, path }) if path.is_ident("Self"))
+    }
+
+    /// Only a single uppercase ident is a generic, e.g. `T`, so `&T` and `(T, U)` are types that
+    /// contain generics instead.
     fn is_generic(&self) -> bool {
-        let pat_ty_string = self.to_token_stream().to_string();
-        !self.is_placeholder() && pat_ty_string.to_uppercase().eq(&pat_ty_string)
+        as_generic_ident(self).is_some()
     }
 
     fn is_placeholder(&self) -> bool {
         matches!(self, Type::Infer(_))
     }
 
+    /// Placeholders and impl expressions stand for different types in every variant, so they
+    /// can't carry dispatched inline bounds, e.g. `(_: ^Trait)`.
+    fn is_dispatchable_inline(&self) -> bool {
+        !self.is_placeholder() && self.get_type_impl_trait().is_none()
+    }
+
     fn some_generic(&self) -> Option<String> {
         self.is_placeholder()
             .then(|| {
@@ -103,18 +942,289 @@ impl TypeUtils for Type {
         format_ident!("{}", self.get_string(), span = self.span())
     }
 
+    /// Trait objects are compared regardless of the order of their bounds, so `dyn Display + Send`
+    /// and `dyn Send + Display` get the same id, and prelude types regardless of their path, so
+    /// `std::string::String` and `String` do as well.
     fn get_unique_id(&self) -> UniqueHashId<Type> {
-        UniqueHashId::new(self)
+        let mut normalized = self.clone();
+        NormalizeType.visit_type_mut(&mut normalized);
+        UniqueHashId::from_hashed(self.clone(), &normalized)
     }
+
+    /// Used to check if two type paths could name the same type, i.e. if the shorter one is a
+    /// suffix of the longer one, e.g. `String` and `::std::string::String`.
+    ///
+    /// NOTE: We can't resolve paths, so `a::Foo` and `b::Foo` are considered the same as well.
+    fn is_same_path(&self, other: &Type) -> bool {
+        let (Type::Path(lhs), Type::Path(rhs)) = (self, other) else {
+            return false;
+        };
+
+        if lhs.qself.is_some() || rhs.qself.is_some() {
+            return false;
+        }
+
+        zip(
+            lhs.path.segments.iter().rev(),
+            rhs.path.segments.iter().rev(),
+        )
+        .all(|(lhs, rhs)| lhs.get_string() == rhs.get_string())
+    }
+}
+
+/// Returns the generics of `generics` that are referenced in `tokens`, e.g. `T` in `Into<T>`.
+pub fn get_referenced_generics(
+    tokens: &impl ToTokens,
+    generics: &BTreeSet<Ident>,
+) -> BTreeSet<Ident> {
+    fn walk(stream: TokenStream, generics: &BTreeSet<Ident>, found: &mut BTreeSet<Ident>) {
+        for token in stream {
+            match token {
+                proc_macro2::TokenTree::Ident(ident) if generics.contains(&ident) => {
+                    found.insert(ident);
+                }
+                proc_macro2::TokenTree::Group(group) => walk(group.stream(), generics, found),
+                _ => {}
+            }
+        }
+    }
+
+    let mut found = BTreeSet::new();
+    walk(tokens.to_token_stream(), generics, &mut found);
+    found
+}
+
+/// Removes duplicated bounds from both the generic parameters and the where clause. Bounds
+/// that already are declared on a type parameter are also removed from the where clause, and so
+/// are bounds that a supertrait of another bound of the same type implies, e.g. `Clone` in
+/// `T: Copy + Clone`. Only the supertraits of std traits and traits tagged with `#[penum]` are
+/// known.
+///
+/// ```text
+/// impl<T: Display + Display> .. where T: Display + Trait
+///      becomes
+/// impl<T: Display> .. where T: Trait
+/// ```
+pub fn dedup_generic_bounds(generics: &mut Generics) {
+    dedup_literal_bounds(generics);
+    dedup_implied_bounds(generics);
+}
+
+fn dedup_literal_bounds(generics: &mut Generics) {
+    let mut declared: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(type_param) = param {
+            let seen = declared.entry(type_param.ident.to_string()).or_default();
+            type_param.bounds = std::mem::take(&mut type_param.bounds)
+                .into_iter()
+                .filter(|bound| seen.insert(bound.get_string()))
+                .collect();
+        }
+    }
+
+    let Some(where_clause) = generics.where_clause.as_mut() else {
+        return;
+    };
+
+    let mut seen_predicates = BTreeSet::new();
+
+    where_clause.predicates = std::mem::take(&mut where_clause.predicates)
+        .into_iter()
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(mut pred_ty) if pred_ty.lifetimes.is_none() => {
+                let seen = declared.entry(pred_ty.bounded_ty.get_string()).or_default();
+                pred_ty.bounds = std::mem::take(&mut pred_ty.bounds)
+                    .into_iter()
+                    .filter(|bound| seen.insert(bound.get_string()))
+                    .collect();
+
+                (!pred_ty.bounds.is_empty()).then_some(WherePredicate::Type(pred_ty))
+            }
+            predicate => seen_predicates
+                .insert(predicate.get_string())
+                .then_some(predicate),
+        })
+        .collect();
+}
+
+/// Removes the bounds that are implied by the supertraits of another bound of the same type. The
+/// bounds of a type are gathered from both its generic parameter and the where clause.
+fn dedup_implied_bounds(generics: &mut Generics) {
+    // Only plain bounds, e.g. `Clone` but not `PartialEq<u8>`, can be implied by a supertrait.
+    fn plain_name(bound: &TypeParamBound) -> Option<String> {
+        let TypeParamBound::Trait(trait_bound) = bound else {
+            return None;
+        };
+
+        let segment = trait_bound.path.segments.last()?;
+
+        (trait_bound.lifetimes.is_none()
+            && matches!(trait_bound.modifier, syn::TraitBoundModifier::None)
+            && segment.arguments.is_empty())
+        .then(|| segment.ident.to_string())
+    }
+
+    fn trait_name(bound: &TypeParamBound) -> Option<String> {
+        match bound {
+            TypeParamBound::Trait(trait_bound) => trait_bound
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident.to_string()),
+            TypeParamBound::Lifetime(_) => None,
+        }
+    }
+
+    let mut bounds_of: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for param in generics.params.iter() {
+        if let GenericParam::Type(type_param) = param {
+            bounds_of
+                .entry(type_param.ident.to_string())
+                .or_default()
+                .extend(type_param.bounds.iter().filter_map(trait_name));
+        }
+    }
+
+    for predicate in generics
+        .where_clause
+        .iter()
+        .flat_map(|wc| wc.predicates.iter())
+    {
+        if let WherePredicate::Type(pred_ty) = predicate {
+            if pred_ty.lifetimes.is_none() {
+                bounds_of
+                    .entry(pred_ty.bounded_ty.get_string())
+                    .or_default()
+                    .extend(pred_ty.bounds.iter().filter_map(trait_name));
+            }
+        }
+    }
+
+    // Every supertrait that the bounds of a type imply, followed transitively.
+    let implied: BTreeMap<String, BTreeSet<String>> = bounds_of
+        .into_iter()
+        .map(|(ty, bounds)| {
+            let mut implied = BTreeSet::new();
+            let mut pending = bounds
+                .iter()
+                .flat_map(|name| crate::dispatch::get_supertraits(name))
+                .collect::<Vec<_>>();
+
+            while let Some(name) = pending.pop() {
+                if implied.insert(name.clone()) {
+                    pending.extend(crate::dispatch::get_supertraits(&name));
+                }
+            }
+
+            (ty, implied)
+        })
+        .filter(|(_, implied)| !implied.is_empty())
+        .collect();
+
+    if implied.is_empty() {
+        return;
+    }
+
+    let is_implied = |ty: &String, bound: &TypeParamBound| {
+        plain_name(bound).is_some_and(|name| implied.get(ty).is_some_and(|set| set.contains(&name)))
+    };
+
+    for param in generics.params.iter_mut() {
+        if let GenericParam::Type(type_param) = param {
+            let ty = type_param.ident.to_string();
+            type_param.bounds = std::mem::take(&mut type_param.bounds)
+                .into_iter()
+                .filter(|bound| !is_implied(&ty, bound))
+                .collect();
+        }
+    }
+
+    let Some(where_clause) = generics.where_clause.as_mut() else {
+        return;
+    };
+
+    where_clause.predicates = std::mem::take(&mut where_clause.predicates)
+        .into_iter()
+        .filter_map(|predicate| match predicate {
+            WherePredicate::Type(mut pred_ty) if pred_ty.lifetimes.is_none() => {
+                let ty = pred_ty.bounded_ty.get_string();
+                pred_ty.bounds = std::mem::take(&mut pred_ty.bounds)
+                    .into_iter()
+                    .filter(|bound| !is_implied(&ty, bound))
+                    .collect();
+
+                (!pred_ty.bounds.is_empty()).then_some(WherePredicate::Type(pred_ty))
+            }
+            predicate => Some(predicate),
+        })
+        .collect();
 }
 
 pub trait TraitBoundUtils {
     fn get_unique_trait_bound_id(&self) -> String;
+    fn is_maybe_sized(&self) -> bool;
 }
 
 impl TraitBoundUtils for TraitBound {
     /// We use this when we want to create an "impl" string. It's
     fn get_unique_trait_bound_id(&self) -> String {
-        UniqueHashId(self).get_unique_string()
+        UniqueHashId::from_value(self).get_unique_string()
+    }
+
+    /// Checks if the bound is `?Sized`, the only bound that can be relaxed.
+    fn is_maybe_sized(&self) -> bool {
+        matches!(self.modifier, syn::TraitBoundModifier::Maybe(_))
+            && self
+                .path
+                .segments
+                .last()
+                .is_some_and(|segment| segment.ident == "Sized")
+    }
+}
+
+/// The number of single character edits it takes to turn `a` into `b`, i.e. the Levenshtein
+/// distance.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Returns the candidate closest in name to `ident`, as long as it's within a third of its
+/// length, e.g. `Item` for `Iten`. Nothing is returned when several candidates are equally close,
+/// e.g. both `T` and `U` for `Y`, since we can't tell which one was meant.
+pub fn find_similar_ident<'a>(
+    ident: &Ident,
+    candidates: impl IntoIterator<Item = &'a Ident>,
+) -> Option<&'a Ident> {
+    let name = ident.to_string();
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    let mut closest: Vec<(usize, &Ident)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_string()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+
+    closest.sort_by_key(|(distance, _)| *distance);
+
+    match closest.as_slice() {
+        [(_, candidate)] => Some(candidate),
+        [(best, candidate), (next, _), ..] if best < next => Some(candidate),
+        _ => None,
     }
 }
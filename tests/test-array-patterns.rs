@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Debug;
+
+#[penum( ([T; N], [U; N]) | (&'static [T]) | ([u8; 4], ..) where T: Debug + Copy, U: Default )]
+enum Buffer {
+    Pair([i32; 3], [u8; 3]),
+    Words(&'static [&'static str]),
+    Ip([u8; 4], u16),
+}
+
+fn main() {
+    let pair = Buffer::Pair([1, 2, 3], [0; 3]);
+    let words = Buffer::Words(&["a", "b"]);
+    let ip = Buffer::Ip([127, 0, 0, 1], 8080);
+
+    if let Buffer::Pair(left, right) = pair {
+        assert_eq!(left.len(), right.len());
+    }
+
+    if let Buffer::Words(words) = words {
+        assert_eq!(format!("{words:?}"), r#"["a", "b"]"#);
+    }
+
+    let _ = ip;
+}
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// `_` catches every variant that `(i32, ..)` rejects.
+#[penum( (i32, ..) | _ where String: ^AsRef<str> )]
+enum Foo {
+    Bar(i32, u8),
+    Baz(String),
+    Bez { name: String },
+    Unit,
+}
+
+// `_` is always tried last, so `Bar` is still matched by `(i32, ..)` and its `String` is left to
+// the variadic.
+#[penum( _ | (i32, ..) where String: ^AsRef<str> )]
+enum Leading {
+    Bar(i32, String),
+    Baz(String),
+}
+
+fn main() {
+    assert_eq!(Foo::Baz("baz".into()).as_ref(), "baz");
+    assert_eq!(Foo::Bez { name: "bez".into() }.as_ref(), "bez");
+    assert_eq!(Foo::Bar(1, 2).as_ref(), "");
+    assert_eq!(Foo::Unit.as_ref(), "");
+
+    assert_eq!(Leading::Bar(1, "bar".into()).as_ref(), "");
+    assert_eq!(Leading::Baz("baz".into()).as_ref(), "baz");
+}
@@ -0,0 +1,25 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+struct OnlyWhenEnabled;
+
+// `OnlyWhenEnabled` doesn't implement `AsRef<str>`, but since its variant is
+// configured away, neither its arm nor its assertion should be kept.
+#[penum( (T) where T: ^AsRef<str> )]
+enum Foo {
+    Bar(String),
+    #[cfg(any())]
+    Ber(OnlyWhenEnabled),
+}
+
+#[penum( unit | (T) where T: ^AsRef<str> )]
+enum Unit {
+    Value(String),
+    #[cfg(any())]
+    Gone,
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar("bar".into()).as_ref());
+}
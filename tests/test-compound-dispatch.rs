@@ -0,0 +1,25 @@
+#![allow(dead_code)]
+extern crate penum;
+use std::fmt::{Debug, Error, Formatter};
+
+use penum::penum;
+
+#[penum( (T) where T: ^AsRef<str> + std::fmt::Display )]
+enum Foo {
+    Bar(String),
+    Ber(&'static str),
+}
+
+#[penum( (T) | { name: T } where T: ^AsRef<str> + ^Debug )]
+enum Ber {
+    Bar(String),
+    Ber { name: &'static str },
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar("bar".to_string()).as_ref());
+    assert_eq!("ber", Foo::Ber("ber").as_ref());
+
+    assert_eq!("bar", Ber::Bar("bar".to_string()).as_ref());
+    assert_eq!("\"ber\"", format!("{:?}", Ber::Ber { name: "ber" }));
+}
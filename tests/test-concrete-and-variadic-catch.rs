@@ -1,15 +1,23 @@
 #![allow(dead_code)]
 extern crate penum;
+use penum::penum;
 
-/// TODO: THIS IS A BUG
-/// It should be possible to have a pattern containing conrete types that is ordered like this.
-/// That is because we can do an identify check..
-///
-// #[penum( (i32, ..) | (..) )]
-// enum Foo {
-//     Bar(f32, i32),
-//     Ber(String, Vec<String>),
-//     Bur(),
-// }
+// `(i32, ..)` matches `Bar` and `Ber` in shape, but only `Bar` in structure, so `Ber` and `Bur`
+// should fall back to `(..)`.
+#[penum( (i32, ..) | (..) )]
+enum Foo {
+    Bar(i32, f32),
+    Ber(String, Vec<String>),
+    Bur(),
+}
 
-fn main() {}
+#[penum( (i32, ..) | (T, ..) where T: ^AsRef<str> )]
+enum Fallback {
+    Bar(i32, String),
+    Ber(String, i32),
+}
+
+fn main() {
+    let ber = Fallback::Ber("ber".to_string(), 10);
+    assert_eq!("ber", ber.as_ref());
+}
@@ -2,11 +2,7 @@
 extern crate penum;
 use penum::penum;
 
-/// THIS IS A BUG
-///
-/// Foo should have where String: AsRef<str> bound...............
-///
-/// UPDATE: Should variadic be interpreted as a rest placeholder?
+// Concrete types in the where clause are asserted even when they're only matched by a variadic.
 #[penum( (..) where String: AsRef<str> )]
 enum Foo {
     Bar(f32, i32),
@@ -14,4 +10,19 @@ enum Foo {
     Bur(),
 }
 
-fn main() {}
+// Inline bounds assert the matched type at that position.
+#[penum( (i32: Copy, ..) | { name: String: Clone + AsRef<str> } )]
+enum Inline {
+    Bar(i32, String),
+    Ber(i32),
+    Bur { name: String },
+}
+
+#[penum( (T: Copy, _: Clone) where T: ^AsRef<str> )]
+enum Generic {
+    Bar(&'static str, String),
+}
+
+fn main() {
+    assert_eq!("bar", Generic::Bar("bar", String::new()).as_ref());
+}
@@ -0,0 +1,39 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Size {
+    fn size(&self) -> usize;
+}
+
+impl Size for [u8; 4] {
+    fn size(&self) -> usize {
+        4
+    }
+}
+
+impl Size for Vec<u8> {
+    fn size(&self) -> usize {
+        self.len()
+    }
+}
+
+// `[u8; N]: Size` only holds for some `N`, so the impl is bounded by it too.
+#[penum( (T) where T: ^Size )]
+enum Buf<const N: usize> {
+    A([u8; N]),
+    B(Vec<u8>),
+}
+
+// `N` stands for the const parameter of the enum.
+#[penum( ([T; N]) where T: Copy )]
+enum Arr<const N: usize> {
+    A([u8; N]),
+    B([i32; N]),
+}
+
+fn main() {
+    let buf: Buf<4> = Buf::A([1, 2, 3, 4]);
+    assert_eq!(4, buf.size());
+}
@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+trait Trait {}
+impl Trait for i64 {}
+impl Trait for f64 {}
+
+// `T` in `Into<T>` is substituted with the type of the same variant.
+#[penum( (T, U) | { name: T, other: U } where T: Trait, U: Into<T> )]
+enum Foo {
+    Bar(i64, i32),
+    Bor(f64, f32),
+    Ber { name: i64, other: u8 },
+}
+
+fn main() {}
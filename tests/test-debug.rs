@@ -0,0 +1,36 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::debug]
+enum Event {
+    Click(i32, i32) = "click at {0}x{1}",
+    Key { code: u32 } = "key {code}",
+    Scroll(i32),
+    Resize { width: u32, height: u32 },
+    Close,
+}
+
+#[penum::debug]
+enum Nothing {
+    default = Ok(()),
+}
+
+fn main() {
+    assert_eq!("click at 1x2", format!("{:?}", Event::Click(1, 2)));
+    assert_eq!("key 13", format!("{:?}", Event::Key { code: 13 }));
+
+    // Variants without a discriminant are written like `#[derive(Debug)]` would.
+    assert_eq!("Scroll(-3)", format!("{:?}", Event::Scroll(-3)));
+    assert_eq!(
+        "Resize { width: 10, height: 20 }",
+        format!(
+            "{:?}",
+            Event::Resize {
+                width: 10,
+                height: 20
+            }
+        )
+    );
+    assert_eq!("Close", format!("{:?}", Event::Close));
+    assert_eq!("Scroll(\n    1,\n)", format!("{:#?}", Event::Scroll(1)));
+}
@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( for<T: ^AsRef<str>, U> (T, U) | { name: T, id: U } where U: Copy )]
+enum Pair {
+    Front(String, i32),
+    Back { name: &'static str, id: u8 },
+}
+
+#[penum( for<T: Clone> { items: Vec<T>, .. } | _ )]
+enum List {
+    Names { items: Vec<String>, len: usize },
+    Empty,
+}
+
+fn main() {
+    let front = Pair::Front("front".to_string(), 1);
+    let back = Pair::Back {
+        name: "back",
+        id: 2,
+    };
+
+    assert_eq!("front", front.as_ref());
+    assert_eq!("back", back.as_ref());
+}
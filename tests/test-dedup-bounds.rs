@@ -0,0 +1,36 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Display;
+
+#[penum]
+trait Describe: Display {
+    fn describe(&self) -> String;
+}
+
+impl Describe for i32 {
+    fn describe(&self) -> String {
+        format!("i32 {self}")
+    }
+}
+
+#[penum( (T) | (T, ..) where T: Display, T: ^Describe )]
+enum Foo<T: Display + Display> {
+    Bar(T),
+    Ber(i32, usize),
+}
+
+impl<T: Describe> Display for Foo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("foo")
+    }
+}
+
+fn main() {
+    let bar: Foo<i32> = Foo::Bar(10);
+    let ber: Foo<i32> = Foo::Ber(20, 0);
+
+    assert_eq!("i32 10", bar.describe());
+    assert_eq!("i32 20", ber.describe());
+}
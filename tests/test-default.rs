@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::default]
+#[derive(Debug, PartialEq)]
+enum Foo {
+    Bar(i32, String),
+    Ber { name: String },
+    Bur,
+}
+
+#[penum::default]
+#[derive(Debug, PartialEq)]
+enum Named {
+    Bar { age: u32, name: String },
+    Ber(i32),
+}
+
+#[penum::default]
+#[derive(Debug, PartialEq)]
+enum Unit {
+    Bar,
+    Ber(i32),
+}
+
+#[penum::default]
+#[derive(Debug, PartialEq)]
+enum Generic<T> {
+    Bar(T),
+    Ber,
+}
+
+fn main() {
+    assert_eq!(Foo::Bar(0, String::new()), Foo::default());
+    assert_eq!(
+        Named::Bar {
+            age: 0,
+            name: String::new()
+        },
+        Named::default()
+    );
+    assert_eq!(Unit::Bar, Unit::default());
+    assert_eq!(Generic::Bar(0u8), Generic::default());
+}
@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::deref_mut(Vec<i32>)]
+enum Foo {
+    Bar(Vec<i32>) = f0,
+    Ber { items: Vec<i32> } = items,
+    Bur(u8, Vec<i32>) = f1,
+    Byr(Vec<i32>),
+    default = f0,
+}
+
+#[penum::as_mut_str]
+enum Name {
+    Bar(String) = f0,
+    Ber { name: String } = name,
+    Bur(String) = "bur",
+    Byr(String),
+    default = f0,
+}
+
+fn main() {
+    let mut foo = Foo::Bar(vec![1]);
+    foo.push(2);
+    assert_eq!(&[1, 2], &foo[..]);
+
+    let mut foo = Foo::Ber { items: vec![] };
+    foo.push(3);
+    assert_eq!(3, foo.iter().sum::<i32>());
+
+    let mut foo = Foo::Bur(0, vec![4]);
+    foo[0] = 5;
+    assert_eq!(&[5], &foo[..]);
+
+    let mut name = Name::Bar("bar".to_string());
+    name.make_ascii_uppercase();
+    assert_eq!("BAR", name.as_str());
+
+    let mut name = Name::Ber {
+        name: "ber".to_string(),
+    };
+    name.as_mut().make_ascii_uppercase();
+    assert_eq!("BER", name.as_ref());
+
+    let mut foo = Foo::Byr(vec![6]);
+    foo.push(7);
+    assert_eq!(&[6, 7], &foo[..]);
+
+    let mut name = Name::Bur("field".to_string());
+    assert_eq!("bur", &*name);
+    assert_eq!("field", name.as_mut_str());
+
+    let mut name = Name::Byr("byr".to_string());
+    name.make_ascii_uppercase();
+    assert_eq!("BYR", &*name);
+}
@@ -0,0 +1,24 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( * = _ as u8 )]
+#[repr(u8)]
+enum Opcode {
+    Nop,
+    Load = 0x10,
+    Store,
+    Halt = 0xff,
+}
+
+#[penum( Min = ..0 | Zero = 0 | * = 1.. )]
+enum Sign {
+    Min = -1,
+    Zero,
+    Plus,
+}
+
+fn main() {
+    assert_eq!(0x11, Opcode::Store as u8);
+    assert_eq!(1, Sign::Plus as i32);
+}
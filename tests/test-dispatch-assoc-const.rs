@@ -0,0 +1,32 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Identify {
+    const ID: u32;
+    const NAME: &'static str = "identify";
+
+    fn id(&self) -> u32;
+}
+
+impl Identify for i32 {
+    const ID: u32 = 7;
+
+    fn id(&self) -> u32 {
+        *self as u32 + Self::ID
+    }
+}
+
+#[penum( (T) | { value: T } where T: ^Identify )]
+enum Foo {
+    Bar(i32),
+    Ber { value: i32 },
+}
+
+fn main() {
+    assert_eq!(7, Foo::ID);
+    assert_eq!("identify", Foo::NAME);
+    assert_eq!(8, Foo::Bar(1).id());
+    assert_eq!(9, Foo::Ber { value: 2 }.id());
+}
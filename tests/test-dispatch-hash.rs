@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[penum( (T) | _ where T: ^Hash )]
+enum Key {
+    Id(u32),
+    Index(u32),
+    Name(String),
+    Any,
+    None,
+}
+
+fn hash_of(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn main() {
+    assert_eq!(hash_of(&Key::Id(1)), hash_of(&Key::Id(1)));
+    assert_ne!(hash_of(&Key::Id(1)), hash_of(&Key::Index(1)));
+    assert_ne!(hash_of(&Key::Any), hash_of(&Key::None));
+    assert_eq!(
+        hash_of(&Key::Name("a".to_string())),
+        hash_of(&Key::Name("a".to_string()))
+    );
+}
@@ -0,0 +1,60 @@
+#![allow(dead_code)]
+extern crate penum;
+use std::fmt::Display;
+
+use penum::penum;
+
+#[penum]
+trait Store {
+    fn push(&mut self, x: u8);
+    fn total(&self, offset: u32) -> u32;
+    fn into_total(self) -> u32;
+    fn describe<D: Display>(&self, prefix: D, times: usize) -> String;
+    fn same_len(&self, other: &Self) -> bool;
+}
+
+impl Store for Vec<u8> {
+    fn push(&mut self, x: u8) {
+        Vec::push(self, x)
+    }
+
+    fn total(&self, offset: u32) -> u32 {
+        self.iter().map(|&x| x as u32).sum::<u32>() + offset
+    }
+
+    fn into_total(self) -> u32 {
+        self.total(0)
+    }
+
+    fn describe<D: Display>(&self, prefix: D, times: usize) -> String {
+        format!("{prefix}{}", self.len()).repeat(times)
+    }
+
+    fn same_len(&self, other: &Self) -> bool {
+        self.len() == other.len()
+    }
+}
+
+#[penum( (T) | { items: T } where T: ^Store )]
+enum Bag {
+    Small(Vec<u8>),
+    Large { items: Vec<u8> },
+}
+
+fn main() {
+    let mut bag = Bag::Small(vec![1, 2]);
+    bag.push(3);
+
+    assert_eq!(16, bag.total(10));
+    assert_eq!("n3n3", bag.describe("n", 2));
+    assert!(bag.same_len(&Bag::Small(vec![0, 0, 0])));
+    assert!(!bag.same_len(&Bag::Large {
+        items: vec![0, 0, 0]
+    }));
+    assert_eq!(6, bag.into_total());
+
+    let mut large = Bag::Large { items: vec![] };
+    large.push(4);
+    assert!(large.same_len(&Bag::Large { items: vec![7] }));
+    assert_eq!(4, large.into_total());
+}
@@ -0,0 +1,58 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+mod my {
+    use penum::penum;
+
+    #[penum]
+    pub trait Scale<T> {
+        fn scale(&self, by: T) -> T;
+    }
+
+    impl Scale<i32> for i32 {
+        fn scale(&self, by: i32) -> i32 {
+            self * by
+        }
+    }
+}
+
+mod ops {
+    use penum::penum;
+
+    // Shares its name with `std::ops::Not`, but since it's referred to through
+    // a module path we should pick the registered trait.
+    #[penum]
+    pub trait Not {
+        fn not(&self) -> bool;
+    }
+
+    impl Not for bool {
+        fn not(&self) -> bool {
+            !*self
+        }
+    }
+}
+
+#[penum( (T) where T: ^crate::my::Scale<i32> )]
+enum Foo {
+    Bar(i32),
+    Ber(i32),
+}
+
+#[penum( (T) where T: ^crate::ops::Not )]
+enum Flag {
+    On(bool),
+    Off(bool),
+}
+
+fn main() {
+    use crate::my::Scale;
+    use crate::ops::Not;
+
+    let bar = Foo::Bar(10);
+    assert_eq!(20, bar.scale(2));
+
+    let on = Flag::On(true);
+    assert!(!on.not());
+}
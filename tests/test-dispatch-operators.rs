@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+use std::ops::{Add, Neg, ShrAssign};
+
+use my::Sub;
+
+mod my {
+    use penum::penum;
+
+    // Overrides the seeded `std::ops::Sub` by name.
+    #[penum]
+    pub trait Sub {
+        fn sub(&self) -> i32;
+    }
+
+    impl Sub for i32 {
+        fn sub(&self) -> i32 {
+            self - 1
+        }
+    }
+}
+
+#[penum( (T) where T: ^Add<i32, Output = i32>, T: ^Neg<Output = i32>, T: ^ShrAssign<u8> )]
+enum Foo {
+    Bar(i32),
+    Ber(i32),
+}
+
+#[penum( (T) where T: ^Sub )]
+enum Ber {
+    Bar(i32),
+}
+
+fn main() {
+    assert_eq!(3, Foo::Bar(1) + 2);
+    assert_eq!(-4, -Foo::Ber(4));
+
+    let mut foo = Foo::Bar(8);
+    foo >>= 2;
+    assert!(matches!(foo, Foo::Bar(2)));
+
+    assert_eq!(9, Ber::Bar(10).sub());
+}
@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::cmp::Ordering;
+
+#[penum( (T) | _ where T: ^PartialEq + ^Eq + ^PartialOrd + ^Ord )]
+enum Rank {
+    Low(i32),
+    High(String),
+    Top,
+    Bottom,
+}
+
+fn main() {
+    assert_eq!(Ordering::Less, Rank::Low(1).cmp(&Rank::Low(2)));
+    assert_eq!(Ordering::Equal, Rank::Low(2).cmp(&Rank::Low(2)));
+
+    // Different variants are ordered by declaration, regardless of their values.
+    assert!(Rank::Low(100) < Rank::High("a".to_string()));
+    assert!(Rank::High("z".to_string()) < Rank::Top);
+    assert!(Rank::Top < Rank::Bottom);
+    assert_eq!(Some(Ordering::Equal), Rank::Top.partial_cmp(&Rank::Top));
+    assert!(Rank::Top == Rank::Top);
+
+    let mut ranks = vec![Rank::Bottom, Rank::Low(3), Rank::Top, Rank::Low(1)];
+    ranks.sort();
+    assert!(ranks == vec![Rank::Low(1), Rank::Low(3), Rank::Top, Rank::Bottom]);
+}
@@ -0,0 +1,22 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) | { name: T } where T: ^PartialEq )]
+enum Foo {
+    Bar(i32),
+    Baz(i32),
+    Bez { name: String },
+}
+
+fn main() {
+    assert!(Foo::Bar(1) == Foo::Bar(1));
+    assert!(Foo::Bar(1) != Foo::Bar(2));
+
+    // Different variants are never equal, even when they hold equal values.
+    assert!(Foo::Bar(1) != Foo::Baz(1));
+
+    let bez = Foo::Bez { name: "bez".into() };
+    assert!(bez == Foo::Bez { name: "bez".into() });
+    assert!(bez != Foo::Bar(1));
+}
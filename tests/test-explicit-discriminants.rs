@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+pub struct Token(u8);
+
+#[penum]
+trait Mint {
+    fn mint(&self) -> Token;
+}
+
+impl Mint for u8 {
+    fn mint(&self) -> Token {
+        Token(*self)
+    }
+}
+
+#[penum(unit)]
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum Color {
+    Red = 1,
+    Green = 4,
+}
+
+// Unit variants are matched by `unit` (or `_`), and fall back to a panicking arm when
+// dispatched.
+#[penum( unit | (T) where T: ^Mint )]
+#[repr(u8)]
+enum Coin {
+    Copper = 1,
+    Silver(u8) = 4,
+    Gold = 7,
+}
+
+fn tag(coin: &Coin) -> u8 {
+    // SAFETY: `Coin` is `repr(u8)`, so its tag is stored as the first `u8`.
+    unsafe { *(coin as *const Coin as *const u8) }
+}
+
+fn main() {
+    assert_eq!(Color::Red as u8, 1);
+    assert_eq!(Color::Green as u8, 4);
+
+    assert_eq!(Coin::Silver(3).mint().0, 3);
+    assert_eq!(tag(&Coin::Silver(3)), 4);
+    assert_eq!(tag(&Coin::Gold), 7);
+}
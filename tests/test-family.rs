@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) where T: Copy )]
+enum Single {
+    Bar(i32),
+    Ber(u8),
+}
+
+#[penum( (T, U) where T: Copy, U: Clone )]
+enum Pair {
+    Bar(i32, String),
+}
+
+#[penum( { name: T } where T: Clone )]
+enum Named {
+    Bar { name: String },
+}
+
+penum::family!(Single, Pair, Named);
+
+fn main() {}
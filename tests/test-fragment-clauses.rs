@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// `String` isn't `Copy`, but it's only bounded by the clause of the second fragment.
+#[penum( (T, String) where T: Copy | { name: T } where T: AsRef<str> | (V, V) where V: Default )]
+enum Node {
+    Leaf(u8, String),
+    Named { name: String },
+    Pair(Vec<u8>, Vec<u8>),
+}
+
+fn main() {
+    let _ = Node::Leaf(1, "leaf".to_string());
+    let _ = Node::Named {
+        name: "named".to_string(),
+    };
+}
@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+extern crate penum;
+use std::str::FromStr;
+
+#[penum::from_str]
+#[derive(Debug, PartialEq)]
+enum Color {
+    Red = "red",
+    Green = "green",
+}
+
+#[penum::from_str(String)]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Circle = "circle",
+    Square = "square",
+    error = "unknown shape".to_string(),
+}
+
+fn main() {
+    assert_eq!(Ok(Color::Red), "red".parse());
+    assert_eq!(Ok(Color::Green), Color::from_str("green"));
+    assert_eq!(Err(()), "blue".parse::<Color>());
+
+    assert_eq!(Ok(Shape::Square), "square".parse());
+    assert_eq!(Err("unknown shape".to_string()), "oval".parse::<Shape>());
+}
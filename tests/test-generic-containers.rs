@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[penum( (Vec<T>) | (HashMap<K, V>, ..) | (Option<&'static T>) where T: Display, K: Eq + std::hash::Hash, V: Clone )]
+enum Store {
+    List(Vec<i32>),
+    Names(Vec<String>),
+    Map(HashMap<String, u8>, usize),
+    Maybe(Option<&'static str>),
+}
+
+fn main() {
+    let _ = Store::List(vec![1, 2, 3]);
+}
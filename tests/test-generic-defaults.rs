@@ -0,0 +1,25 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Placeholder fields are given the default type of their generic.
+#[penum( (T = String, ..) where T: ^AsRef<str> )]
+enum Label {
+    Owned(_, u8),
+    Borrowed(&'static str, u8),
+}
+
+// A generic that no variant binds is asserted with its default type.
+#[penum( (T = i32) | unit where T: Default )]
+enum Empty {
+    A,
+    B,
+}
+
+fn main() {
+    let owned = Label::Owned("owned".to_string(), 1);
+    let borrowed = Label::Borrowed("borrowed", 2);
+
+    assert_eq!("owned", owned.as_ref());
+    assert_eq!("borrowed", borrowed.as_ref());
+}
@@ -14,11 +14,13 @@ impl Trait for i32 {
 }
 
 #[penum( unit | () | (T, ..) where T: ^Trait )]
-enum Foo<T: Trait> {
-    // FIXME: should work without specifying T: Trait twice
+enum Foo<T> {
     Bar(T),
     Ber(i32, usize),
     Bur(),
 }
 
-fn main() {}
+fn main() {
+    assert_eq!(20, Foo::Bar(1).grab());
+    assert_eq!(20, Foo::<i32>::Ber(1, 2).grab());
+}
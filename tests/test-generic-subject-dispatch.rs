@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+use std::fmt::{Display, Error, Formatter};
+
+// `T` is mapped to the subject generic `X`, which gets the `Display` bound.
+#[penum( (T) where T: ^Display )]
+enum Wrapper<X> {
+    A(X),
+    B(X),
+}
+
+// Pattern generics don't have to line up with the names of the subject generics.
+#[penum( (T, U) where T: ^Display )]
+enum Pair<U, T> {
+    A(U, T),
+}
+
+fn main() {
+    assert_eq!(Wrapper::A(1).to_string(), "1");
+    assert_eq!(Wrapper::B("b").to_string(), "b");
+    assert_eq!(Pair::A("a", 2).to_string(), "a");
+}
@@ -0,0 +1,38 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Greet<'a> {
+    fn greet(&self, name: &'a str) -> &'a str;
+}
+
+struct Echo;
+
+impl<'a> Greet<'a> for Echo {
+    fn greet(&self, name: &'a str) -> &'a str {
+        name
+    }
+}
+
+// Higher-ranked bounds can be dispatched, which gives `impl<'a> Greet<'a> for Foo`.
+#[penum( (T) where T: ^for<'a> Greet<'a> )]
+enum Foo {
+    Bar(Echo),
+}
+
+// The lifetimes can be declared in front of the predicate too.
+#[penum( (T) where for<'a> T: Fn(&'a str) -> &'a str )]
+enum Ber {
+    Bar(fn(&str) -> &str),
+}
+
+// Or inside of an `impl Trait`.
+#[penum( (impl for<'a> Fn(&'a str) -> &'a str) )]
+enum Bir {
+    Bar(fn(&str) -> &str),
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar(Echo).greet("bar"));
+}
@@ -0,0 +1,45 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum(impl_display_from(render))]
+enum Foo {
+    Bar(i32),
+    Ber { name: String },
+    Bur,
+}
+
+impl Foo {
+    fn render(&self) -> String {
+        match self {
+            Foo::Bar(num) => format!("bar {num}"),
+            Foo::Ber { name } => format!("ber {name}"),
+            Foo::Bur => "bur".to_string(),
+        }
+    }
+}
+
+#[penum( impl_display_from(name), (T) where T: ^AsRef<str> )]
+enum Named<T> {
+    Tag(T),
+}
+
+impl<T: AsRef<str>> Named<T> {
+    fn name(&self) -> &str {
+        self.as_ref()
+    }
+}
+
+fn main() {
+    assert_eq!("bar 10", Foo::Bar(10).to_string());
+    assert_eq!(
+        "ber penum",
+        Foo::Ber {
+            name: "penum".into()
+        }
+        .to_string()
+    );
+    assert_eq!("bur", format!("{}", Foo::Bur));
+
+    assert_eq!("tag", Named::Tag("tag").to_string());
+}
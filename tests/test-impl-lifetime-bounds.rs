@@ -0,0 +1,20 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Lifetime bounds are asserted together with the trait bounds of an impl.
+#[penum( (impl std::fmt::Display + 'static) )]
+enum Foo {
+    Bar(String),
+    Ber(i32),
+}
+
+#[penum( (impl 'a + AsRef<str>, ..) )]
+enum Bir<'a> {
+    Bar(&'a str, u8),
+}
+
+fn main() {
+    let _ = Foo::Bar(String::new());
+    let _ = Bir::Bar("bar", 1);
+}
@@ -0,0 +1,42 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for String {
+    fn describe(&self) -> String {
+        format!("string {self}")
+    }
+}
+
+impl Describe for i32 {
+    fn describe(&self) -> String {
+        format!("number {self}")
+    }
+}
+
+#[penum( impl Describe for String, impl Describe for i32 )]
+enum Value {
+    Text(String),
+    Number(i32),
+    Flag(bool),
+}
+
+#[penum( impl Describe for String + impl AsRef<str> for String )]
+enum Named {
+    Text(String),
+}
+
+fn main() {
+    assert_eq!("string hi", Value::Text("hi".to_string()).describe());
+    assert_eq!("number 7", Value::Number(7).describe());
+    assert_eq!("", Value::Flag(true).describe());
+
+    let named = Named::Text("name".to_string());
+    assert_eq!("string name", named.describe());
+    assert_eq!("name", named.as_ref());
+}
@@ -0,0 +1,41 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for String {
+    fn describe(&self) -> String {
+        format!("string {self}")
+    }
+}
+
+impl Describe for i32 {
+    fn describe(&self) -> String {
+        format!("number {self}")
+    }
+}
+
+#[penum( impl Describe for ::std::string::String, i32 )]
+enum Value {
+    Text(String),
+    Number(i32),
+    Flag(bool),
+}
+
+#[penum( impl Describe for { std::string::String, i32 } )]
+enum Braced {
+    Text(String),
+    Number(i32),
+}
+
+fn main() {
+    assert_eq!("string hi", Value::Text("hi".to_string()).describe());
+    assert_eq!("number 7", Value::Number(7).describe());
+    assert_eq!("", Value::Flag(true).describe());
+
+    assert_eq!("number 1", Braced::Number(1).describe());
+}
@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// `?Sized` is accepted in impl positions, but only `AsRef<str>` is asserted.
+#[penum( (impl ?Sized + AsRef<str>) )]
+enum Foo<'a> {
+    Bar(&'a str),
+    Ber(Box<str>),
+}
+
+fn main() {
+    let _ = Foo::Bar("bar");
+}
@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Display;
+
+#[penum( { name: impl Display, .. } | (impl Display, impl Copy) )]
+enum Entry {
+    User { name: String, age: u8 },
+    Group { name: &'static str },
+    Id(char, u32),
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( _(3) | _{2} | unit )]
+enum Foo {
+    Bar(i32, String, usize),
+    Ber { name: String, age: usize },
+    Bur,
+}
+
+#[penum( _(2) where String: ^AsRef<str> )]
+enum Bar {
+    Tup(String, String),
+    Tap(String, i32),
+}
+
+fn main() {
+    let bar = Bar::Tup("hello".to_string(), "world".to_string());
+    assert_eq!("hello", bar.as_ref());
+}
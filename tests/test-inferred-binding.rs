@@ -0,0 +1,37 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Abc {
+    type Input: ?Sized;
+    fn get(&self) -> &Self::Input;
+}
+
+#[penum]
+impl Abc for String {
+    type Input = str;
+    fn get(&self) -> &str {
+        self
+    }
+}
+
+#[penum]
+impl Abc for Box<str> {
+    type Input = str;
+    fn get(&self) -> &str {
+        self
+    }
+}
+
+// `Input = str` is inferred from the impls above.
+#[penum( (T) where T: ^Abc )]
+enum Foo {
+    Bar(String),
+    Bur(Box<str>),
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar("bar".to_string()).get());
+    assert_eq!("bur", Foo::Bur("bur".into()).get());
+}
@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (i32, .., String) | (.., u8) )]
+enum Foo {
+    Bar(i32, String),
+    Ber(i32, f32, Vec<u8>, String),
+    Bur(u8),
+    Byr(String, u8),
+}
+
+#[penum( (T, .., U) where T: ^AsRef<str>, U: Copy )]
+enum Ends {
+    Bar(String, i32),
+    Ber(String, f32, usize),
+}
+
+#[penum( (.., T) where T: ^AsRef<str> )]
+enum Last {
+    Bar(i32, String),
+    Ber(i32, f32, &'static str),
+}
+
+fn main() {
+    assert_eq!("ber", Ends::Ber("ber".to_string(), 1.0, 2).as_ref());
+    assert_eq!("bar", Last::Bar(10, "bar".to_string()).as_ref());
+    assert_eq!("ber", Last::Ber(10, 1.0, "ber").as_ref());
+}
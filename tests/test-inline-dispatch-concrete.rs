@@ -0,0 +1,20 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (String: ^AsRef<str>, ..) | { path: String: ^AsRef<str>, size: u64: Copy } )]
+enum Source {
+    Inline(String, usize),
+    File { path: String, size: u64 },
+}
+
+fn main() {
+    let inline = Source::Inline("fn main() {}".to_string(), 12);
+    let file = Source::File {
+        path: "src/main.rs".to_string(),
+        size: 12,
+    };
+
+    assert_eq!("fn main() {}", inline.as_ref());
+    assert_eq!("src/main.rs", file.as_ref());
+}
@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T: ^AsRef<str>, U: Copy) | { name: T } )]
+enum Label {
+    Pair(String, i32),
+    Named { name: &'static str },
+}
+
+fn main() {
+    let pair = Label::Pair("pair".to_string(), 1);
+    let named = Label::Named { name: "named" };
+
+    assert_eq!("pair", pair.as_ref());
+    assert_eq!("named", named.as_ref());
+}
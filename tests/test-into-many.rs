@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::into(u32, u64, i64)]
+enum Level {
+    Low = 1,
+    High(u8) = (f0 * 10).into(),
+    Unknown,
+    default = 0,
+}
+
+fn main() {
+    let low: u32 = Level::Low.into();
+    assert_eq!(low, 1);
+
+    let high: u64 = Level::High(5).into();
+    assert_eq!(high, 50);
+
+    let unknown: i64 = Level::Unknown.into();
+    assert_eq!(unknown, 0);
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) | (T, U) where T: 'a, T: AsRef<str>, U: 'b )]
+enum Foo<'a, 'b> {
+    Bar(&'a str),
+    Ber(&'a str, &'b u8),
+}
+
+// `&'a str: 'b` only holds because of `'a: 'b`.
+#[penum( (T) where 'a: 'b, T: 'b )]
+enum Ber<'a, 'b> {
+    Bar(&'a str),
+    Ber(&'b str),
+}
+
+fn main() {
+    let ber: Ber<'static, 'static> = Ber::Bar("bar");
+    assert!(matches!(ber, Ber::Bar("bar")));
+}
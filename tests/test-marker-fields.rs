@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::marker::PhantomData;
+
+#[penum( (T, ..) | (T) where T: ^AsRef<str> )]
+enum Foo<T> {
+    Bar(T, PhantomData<u8>),
+    Ber(T),
+}
+
+#[penum( (T, ..) | (T) where T: ^AsRef<str> )]
+enum Tagged<T, M> {
+    Bar(T, PhantomData<M>),
+    Ber(T, PhantomData<(M, M)>),
+    Bur(T),
+}
+
+fn main() {
+    let bar: Foo<&str> = Foo::Bar("bar", PhantomData);
+    let ber: Foo<&str> = Foo::Ber("ber");
+
+    assert_eq!("bar", bar.as_ref());
+    assert_eq!("ber", ber.as_ref());
+
+    let tagged: Tagged<String, i32> = Tagged::Ber("tagged".to_string(), PhantomData);
+    assert_eq!("tagged", tagged.as_ref());
+}
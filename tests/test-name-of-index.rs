@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum(name_of_index)]
+enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+#[penum(name_of_index, unit)]
+pub enum Level {
+    Low = 10,
+    High = 20,
+}
+
+fn main() {
+    assert_eq!(Some("Red"), Color::name_of_index(0));
+    assert_eq!(Some("Green"), Color::name_of_index(1));
+    assert_eq!(Some("Blue"), Color::name_of_index(2));
+    assert_eq!(None, Color::name_of_index(3));
+
+    assert_eq!(Some("High"), Level::name_of_index(1));
+    assert_eq!(None, Level::name_of_index(usize::MAX));
+}
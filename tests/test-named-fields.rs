@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Named fields are matched by name, so their order doesn't matter.
+#[penum( { age: usize, name: T } where T: ^AsRef<str> )]
+enum Person {
+    Adult { name: String, age: usize },
+    Child { age: usize, name: &'static str },
+}
+
+// With a variadic, other fields may come in any order as well.
+#[penum( { id: u32, .. } )]
+enum Entity {
+    User { name: String, id: u32 },
+    Group { id: u32 },
+}
+
+fn main() {
+    let adult = Person::Adult {
+        name: "Alice".to_string(),
+        age: 30,
+    };
+    let child = Person::Child {
+        age: 7,
+        name: "Bob",
+    };
+
+    assert_eq!("Alice", adult.as_ref());
+    assert_eq!("Bob", child.as_ref());
+}
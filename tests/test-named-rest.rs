@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( { id: u64, label?: T, ..=2: Copy } where T: ^AsRef<str> )]
+enum Event {
+    Click { x: i32, id: u64, y: i32 },
+    Key { id: u64, label: String, code: u32 },
+    Tick { id: u64 },
+}
+
+fn main() {
+    let key = Event::Key {
+        id: 1,
+        label: "enter".to_string(),
+        code: 13,
+    };
+
+    assert_eq!("enter", key.as_ref());
+    assert_eq!("", Event::Tick { id: 2 }.as_ref());
+}
@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+penum::shape! { Pair = (T, T) where T: PartialEq }
+penum::shape! { Labeled = (T, ..) | { label: T, .. } where T: ^AsRef<str> }
+
+#[penum(Pair)]
+enum Range {
+    Ints(i32, i32),
+    Chars(char, char),
+}
+
+#[penum(strict, Pair)]
+enum Names {
+    Full(String, String),
+}
+
+#[penum(Labeled)]
+enum Button {
+    Text(String, u8),
+    Icon { label: &'static str, size: u16 },
+}
+
+// Names that aren't registered still select a unit variant.
+#[penum(Empty)]
+enum Unit {
+    Empty,
+}
+
+fn main() {
+    let text = Button::Text("ok".to_string(), 1);
+    let icon = Button::Icon {
+        label: "cancel",
+        size: 16,
+    };
+
+    assert_eq!("ok", text.as_ref());
+    assert_eq!("cancel", icon.as_ref());
+}
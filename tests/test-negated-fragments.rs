@@ -0,0 +1,26 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// No variant is allowed to start with an owned string.
+#[penum( !(String, ..) )]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(i64),
+    Punct { ch: char },
+    Eof,
+}
+
+#[penum( !(Vec<u8>) | (T, ..) where T: ^AsRef<str> )]
+enum Frame {
+    Text(String),
+    Label(&'static str, u16),
+}
+
+fn main() {
+    let text = Frame::Text("hello".to_string());
+    let label = Frame::Label("id", 1);
+
+    assert_eq!("hello", text.as_ref());
+    assert_eq!("id", label.as_ref());
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (!String, ..) | { name: !String } )]
+enum Foo {
+    Bar(i32, String),
+    Baz(&'static str),
+    Bez { name: u8 },
+}
+
+// Variants whose field is excluded by the first fragment are caught by the second.
+#[penum( (!String) | (String) where String: ^AsRef<str> )]
+enum Text {
+    Owned(String),
+    Other(u8),
+}
+
+fn main() {
+    assert_eq!("a", Text::Owned("a".to_string()).as_ref());
+}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Projections within a bounded type are substituted for each type of `T`.
+#[penum( (T) where T: Iterator, Vec<T::Item>: Clone, Option<<T as Iterator>::Item>: Send )]
+enum Foo {
+    Bar(std::vec::IntoIter<i32>),
+    Ber(std::option::IntoIter<String>),
+}
+
+// So are projections within the bounds of another generic.
+#[penum( (T, U) where T: Iterator, U: From<T::Item> )]
+enum Wid {
+    Bar(std::vec::IntoIter<u8>, u16),
+}
+
+fn main() {
+    let _ = Foo::Bar(vec![1].into_iter());
+    let _ = Wid::Bar(vec![1].into_iter(), 2);
+}
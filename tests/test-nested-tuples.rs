@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Area {
+    fn area(&self) -> u32;
+    fn same_area(&self, other: &Self) -> bool;
+}
+
+impl Area for u32 {
+    fn area(&self) -> u32 {
+        self * self
+    }
+
+    fn same_area(&self, other: &Self) -> bool {
+        self.area() == other.area()
+    }
+}
+
+#[penum( ((_, T), ..) | { size: (T, _), .. } where T: ^Area )]
+enum Shape {
+    Square((&'static str, u32), bool),
+    Tile { id: u8, size: (u32, u32) },
+}
+
+#[penum( ((T, _)) where T: ^Area )]
+struct Cell((u32, char));
+
+fn main() {
+    let square = Shape::Square(("square", 3), true);
+    let tile = Shape::Tile {
+        id: 1,
+        size: (4, 2),
+    };
+
+    assert_eq!(9, square.area());
+    assert_eq!(16, tile.area());
+    assert!(square.same_area(&Shape::Square(("other", 3), false)));
+
+    let cell = Cell((5, 'x'));
+    assert_eq!(25, cell.area());
+    assert!(!cell.same_area(&Cell((6, 'x'))));
+}
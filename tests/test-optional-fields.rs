@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( { name: T, meta?: Option<u32> } where T: ^AsRef<str> )]
+enum Entry {
+    File {
+        name: String,
+    },
+    Dir {
+        name: &'static str,
+        meta: Option<u32>,
+    },
+}
+
+fn main() {
+    let file = Entry::File {
+        name: "main.rs".to_string(),
+    };
+    let dir = Entry::Dir {
+        name: "src",
+        meta: Some(3),
+    };
+
+    assert_eq!("main.rs", file.as_ref());
+    assert_eq!("src", dir.as_ref());
+}
@@ -0,0 +1,20 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Lifetime predicates are forwarded to the enum as they are.
+#[penum( (T) where 'a: 'static, T: 'a )]
+enum Foo<'a> {
+    Bar(&'a str),
+}
+
+// Lifetime bounds can be mixed with trait bounds.
+#[penum( (T, ..) where T: 'a + Copy )]
+enum Ber<'a> {
+    Bar(&'a u8, &'a str),
+}
+
+fn main() {
+    let _ = Foo::Bar("bar");
+    let _ = Ber::Bar(&1, "bar");
+}
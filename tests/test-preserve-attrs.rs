@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+#![deny(deprecated)]
+extern crate penum;
+use std::str::FromStr;
+
+// Variants and fields keep their attributes, and arms of a configured away variant are removed
+// together with it.
+#[penum::to_string]
+enum Foo {
+    /// The first variant.
+    #[deprecated]
+    Bar = "bar",
+    #[cfg(any())]
+    Ber(Missing) = "ber",
+    Bur(#[allow(unused)] i32) = "bur",
+}
+
+#[penum::into(String)]
+enum Bar {
+    #[cfg(any())]
+    Ber(Missing) = "ber".to_string(),
+    Bur(i32) = format!("{f0}"),
+}
+
+#[penum::from_str]
+#[derive(Debug, PartialEq)]
+enum Color {
+    #[cfg(any())]
+    Blue = "blue",
+    Red = "red",
+}
+
+#[penum::debug]
+enum Baz {
+    #[cfg(any())]
+    Ber(Missing),
+    Bur(i32),
+}
+
+fn main() {
+    #[allow(deprecated)]
+    let bar = Foo::Bar;
+
+    assert_eq!("bar", bar.to_string());
+    assert_eq!("bur", Foo::Bur(1).to_string());
+
+    let string: String = Bar::Bur(1).into();
+    assert_eq!("1", string);
+
+    assert_eq!(Ok(Color::Red), Color::from_str("red"));
+    assert_eq!(Err(()), Color::from_str("blue"));
+
+    assert_eq!("Bur(1)", format!("{:?}", Baz::Bur(1)));
+}
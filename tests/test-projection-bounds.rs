@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// `T::Item` is resolved through the only trait that bounds `T`.
+#[penum( (T) where T: IntoIterator, T::Item: Copy )]
+enum Foo {
+    Bar(Vec<i32>),
+    Baz(Option<u8>),
+}
+
+// Fully-qualified projections work for generic subjects too.
+#[penum( (T) where <T as IntoIterator>::Item: Clone )]
+enum Ber<X> {
+    Bar(Vec<X>),
+}
+
+// Bindings on the trait are left out of the projection.
+#[penum( (T) where T: IntoIterator<Item = u8>, T::Item: Copy )]
+enum Bir {
+    Bar(Vec<u8>),
+}
+
+fn main() {
+    let _ = Foo::Bar(vec![1]);
+    let _ = Ber::Bar(vec![String::new()]);
+    let _ = Bir::Bar(vec![1]);
+}
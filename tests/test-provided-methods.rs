@@ -0,0 +1,44 @@
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Describe {
+    fn describe(&self) -> String;
+
+    // Associated functions and methods returning `Self` can't be dispatched, but
+    // with a default body they are left to the trait.
+    fn kind() -> &'static str {
+        "describe"
+    }
+
+    fn fresh(&self) -> Self
+    where
+        Self: Sized + Default,
+    {
+        Self::default()
+    }
+}
+
+impl Describe for i32 {
+    fn describe(&self) -> String {
+        format!("i32: {self}")
+    }
+}
+
+impl Describe for String {
+    fn describe(&self) -> String {
+        format!("string: {self}")
+    }
+}
+
+#[penum( (T) where T: ^Describe )]
+enum Value {
+    Int(i32),
+    Text(String),
+}
+
+fn main() {
+    assert_eq!("i32: 1", Value::Int(1).describe());
+    assert_eq!("string: a", Value::Text("a".to_string()).describe());
+    assert_eq!("describe", <Value as Describe>::kind());
+}
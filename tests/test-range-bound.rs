@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, 1..3: Copy + Default) | { name: T, ..=2: Clone } where T: ToString )]
+enum Reading {
+    Single(String, f32),
+    Triple(String, u8, u16, u32),
+    Named { name: &'static str, unit: String },
+}
+
+fn main() {
+    let _ = Reading::Triple("triple".to_string(), 1, 2, 3);
+}
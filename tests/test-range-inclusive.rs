@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, 1..=2) where T: ^AsRef<str> )]
+enum Foo {
+    Ber(String, u8),
+    Bur(String, u8, u16),
+}
+
+#[penum( (..=1) | { name: T, 0..0 } | (T, T, 2..) where T: Clone )]
+enum Any {
+    Bar(),
+    Ber(i32),
+    Byr { name: String },
+    Bur(i32, i32, u8, u16),
+}
+
+fn main() {
+    assert_eq!("bur", Foo::Bur("bur".to_string(), 1, 2).as_ref());
+}
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, ..2) where T: ^AsRef<str> )]
+enum Foo {
+    Bar(String),
+    Ber(String, u8),
+    Bur(String, u8, u16),
+}
+
+#[penum( (..2) | { name: T, ..1 } where T: Clone )]
+enum Any {
+    Bar(),
+    Ber(i32),
+    Bur(i32, u8),
+    Byr { name: String },
+    Bor { name: String, age: usize },
+}
+
+fn main() {
+    assert_eq!("bur", Foo::Bur("bur".to_string(), 1, 2).as_ref());
+}
@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (&'a T) | { name: &str, tag: &'static str } where T: ^AsRef<str> )]
+enum Token<'src> {
+    Word(&'src String),
+    Symbol(&'src str),
+    Named { name: &'src str, tag: &'static str },
+}
+
+fn main() {
+    let word = "word".to_string();
+
+    assert_eq!("word", Token::Word(&word).as_ref());
+    assert_eq!("+", Token::Symbol("+").as_ref());
+}
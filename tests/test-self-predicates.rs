@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::rc::Rc;
+
+// `AsRef<str>` is only implemented for the enums that are `Send`.
+#[penum( (T) where T: ^AsRef<str>, Self: Send )]
+enum Name<T> {
+    Owned(T),
+    Borrowed(T),
+}
+
+fn name<N: AsRef<str>>(name: &N) -> &str {
+    name.as_ref()
+}
+
+fn main() {
+    assert_eq!(name(&Name::Owned(String::from("a"))), "a");
+
+    // `Name<Rc<str>>` isn't `Send`, so it doesn't implement `AsRef<str>`.
+    let _ = Name::Borrowed(Rc::<str>::from("b"));
+}
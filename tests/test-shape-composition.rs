@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+penum::shape! { Pair = (T, T) where T: PartialEq }
+penum::shape! { Named = { name: T, .. } where T: ^AsRef<str> }
+
+#[penum(Pair | (T, ..) | _)]
+enum Mixed {
+    Ints(i32, i32),
+    Single(i32),
+    Empty,
+}
+
+#[penum(Named | (T, ..) where T: Clone)]
+enum Person {
+    Adult { name: String, age: u8 },
+    Child(String),
+}
+
+fn main() {
+    let adult = Person::Adult {
+        name: "Ada".to_string(),
+        age: 36,
+    };
+    let child = Person::Child("Tom".to_string());
+
+    assert_eq!("Ada", adult.as_ref());
+    assert_eq!("Tom", child.as_ref());
+}
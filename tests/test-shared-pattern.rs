@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+penum::pattern!(Pair = (T, U) where T: Copy);
+penum::pattern!(Named = (T) | { name: T } where T: ^AsRef<str>);
+
+#[penum( use = Pair )]
+enum Foo {
+    Bar(i32, String),
+    Baz(u8, Vec<u8>),
+}
+
+#[penum( strict, use = Pair )]
+enum Other {
+    Bor(char, ()),
+}
+
+#[penum( use = Named )]
+enum Text {
+    Owned(String),
+    Borrowed { name: &'static str },
+}
+
+fn main() {
+    assert_eq!("owned", Text::Owned("owned".to_string()).as_ref());
+    assert_eq!("borrowed", Text::Borrowed { name: "borrowed" }.as_ref());
+}
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Every fragment is matched by at least one variant.
+#[penum( strict, (T) | (T, U) | { name: T } )]
+enum Foo {
+    Bar(i32),
+    Ber(i32, String),
+    Bur { name: String },
+}
+
+// Options can be separated from the pattern by a `;` as well.
+#[penum( strict; (T) | {..} )]
+enum Baz {
+    Bar(i32),
+    Bur { name: String },
+}
+
+// Without `strict`, unmatched fragments are still fine.
+#[penum( (T) | (T, U) | { name: T } )]
+enum Bar {
+    Bar(i32),
+}
+
+fn main() {
+    let _ = Foo::Bar(1);
+    let _ = Bar::Bar(1);
+    let _ = Baz::Bar(1);
+}
@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum("(T, ..) | { name: T } where T: ^AsRef<str>")]
+enum Foo {
+    Bar(String, i32),
+    Baz { name: &'static str },
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar("bar".to_string(), 1).as_ref());
+    assert_eq!("baz", Foo::Baz { name: "baz" }.as_ref());
+}
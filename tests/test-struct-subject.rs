@@ -0,0 +1,64 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Store {
+    fn push(&mut self, x: u8);
+    fn total(&self) -> u32;
+    fn same_len(&self, other: &Self) -> bool;
+}
+
+impl Store for Vec<u8> {
+    fn push(&mut self, x: u8) {
+        Vec::push(self, x)
+    }
+
+    fn total(&self) -> u32 {
+        self.iter().map(|&x| x as u32).sum()
+    }
+
+    fn same_len(&self, other: &Self) -> bool {
+        self.len() == other.len()
+    }
+}
+
+#[penum( (_, T) where T: ^Store )]
+struct Tagged(&'static str, Vec<u8>);
+
+#[penum( { items: T, .. } where T: ^Store )]
+struct Named<U>
+where
+    U: Copy,
+{
+    items: Vec<u8>,
+    tag: U,
+}
+
+#[penum( (T) where T: ^AsRef<str> )]
+pub struct Name(String);
+
+#[penum(unit)]
+struct Unit;
+
+fn main() {
+    let mut tagged = Tagged("tag", vec![1]);
+    tagged.push(2);
+    assert_eq!(3, tagged.total());
+    assert!(tagged.same_len(&Tagged("other", vec![0, 0])));
+
+    let mut named = Named {
+        items: vec![],
+        tag: 1,
+    };
+    named.push(4);
+    assert_eq!(4, named.total());
+    assert!(!named.same_len(&Named {
+        items: vec![],
+        tag: 2
+    }));
+
+    assert_eq!("name", Name("name".to_string()).as_ref());
+
+    let _ = Unit;
+}
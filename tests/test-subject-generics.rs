@@ -0,0 +1,24 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// With `subject_generics`, `T` stands for the enum's own `T`, so `(T)` doesn't match
+// `B(Vec<T>)`, and `Clone` is only asserted for `T`.
+#[penum( subject_generics, (T) | (Vec<T>) where T: Clone )]
+enum Cloned<T> {
+    A(T),
+    B(Vec<T>),
+}
+
+// `N` stands for the enum's own `N` too.
+#[penum( subject_generics, ([u8; N]) | (Vec<u8>) )]
+enum Buf<const N: usize> {
+    A([u8; N]),
+    B(Vec<u8>),
+}
+
+fn main() {
+    let _ = Cloned::A(1);
+    let _ = Cloned::<i32>::B(vec![]);
+    let _ = Buf::<4>::A([0; 4]);
+}
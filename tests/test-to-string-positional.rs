@@ -0,0 +1,31 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::to_string]
+enum Id {
+    User { name: String } = "user {name}",
+    Num(u32) = "id {0}",
+    Pair(u32, u32) = "{} and {}",
+    Swap(u32, u32) = "{1}:{0:>3}",
+    Escaped(u32) = "{{0}} is {0}",
+}
+
+#[penum::fmt]
+enum Code {
+    Num(u32) = "code {0:?}",
+    Pair(u32, &'static str) = "{1}-{0}",
+}
+
+fn main() {
+    let user = Id::User {
+        name: "bob".to_string(),
+    };
+    assert_eq!(user.to_string(), "user bob");
+    assert_eq!(Id::Num(7).to_string(), "id 7");
+    assert_eq!(Id::Pair(1, 2).to_string(), "1 and 2");
+    assert_eq!(Id::Swap(1, 2).to_string(), "2:  1");
+    assert_eq!(Id::Escaped(3).to_string(), "{0} is 3");
+
+    assert_eq!(format!("{}", Code::Num(4)), "code 4");
+    assert_eq!(format!("{}", Code::Pair(4, "x")), "x-4");
+}
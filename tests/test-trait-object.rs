@@ -0,0 +1,14 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Display;
+
+// Trait objects are compared by their bounds, in any order.
+#[penum( (Box<dyn Display + Send>) | { value: Box<dyn Send + Display> } )]
+enum Foo {
+    Bar(Box<dyn Display + Send>),
+    Baz { value: Box<dyn Display + Send> },
+}
+
+fn main() {}
@@ -0,0 +1,41 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::try_into(i32, String)]
+enum Foo {
+    Bar = 1,
+    Ber(i32) = f0 * 2,
+    Bur { num: i32 } = num,
+    Byr,
+    Bor(&'static str),
+    error = "not a number".to_string(),
+}
+
+#[penum::try_into(String)]
+enum Ber {
+    Bar(&'static str) = f0.to_string(),
+    Ber,
+}
+
+fn main() {
+    let bar: Result<i32, String> = Foo::Bar.try_into();
+    assert_eq!(Ok(1), bar);
+
+    let ber: Result<i32, String> = Foo::Ber(10).try_into();
+    assert_eq!(Ok(20), ber);
+
+    let bur: Result<i32, String> = Foo::Bur { num: 5 }.try_into();
+    assert_eq!(Ok(5), bur);
+
+    let byr: Result<i32, String> = Foo::Byr.try_into();
+    assert_eq!(Err("not a number".to_string()), byr);
+
+    let bor: Result<i32, String> = Foo::Bor("10").try_into();
+    assert!(bor.is_err());
+
+    let string: Result<String, ()> = Ber::Bar("bar").try_into();
+    assert_eq!(Ok("bar".to_string()), string);
+
+    let empty: Result<String, ()> = Ber::Ber.try_into();
+    assert_eq!(Err(()), empty);
+}
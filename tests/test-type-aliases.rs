@@ -0,0 +1,27 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+type Bytes = Vec<u8>;
+type Id = u64;
+
+// `Bytes` and `Vec<u8>` are the same type, and so are `Id` and `u64`.
+#[penum( (Id, Bytes) | { data: Vec<u8> }, alias(Bytes = Vec<u8>, Id = u64) )]
+enum Packet {
+    Raw(u64, Vec<u8>),
+    Framed { data: Bytes },
+}
+
+#[penum( (T) where Bytes: ^AsRef<[u8]>, alias(Bytes = Vec<u8>) )]
+enum Payload {
+    Data(Vec<u8>),
+    Code(i32),
+}
+
+fn main() {
+    let _ = Packet::Raw(1, vec![]);
+    let _ = Packet::Framed { data: vec![1] };
+
+    let data = Payload::Data(vec![1, 2]);
+    assert_eq!(data.as_ref(), &[1, 2]);
+}
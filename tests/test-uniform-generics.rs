@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Every variant binds `T` to `i32`.
+#[penum( uniform, (T, ..) | { id: T } where T: ^Into<i64> )]
+enum Foo {
+    Bar(i32, String),
+    Ber(i32),
+    Bur { id: i32 },
+}
+
+fn main() {
+    let bar = Foo::Bar(1, String::new());
+    let _ = bar;
+}
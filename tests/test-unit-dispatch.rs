@@ -0,0 +1,29 @@
+#![allow(dead_code)]
+#![deny(unreachable_patterns)]
+extern crate penum;
+use penum::penum;
+
+// Fieldless variants get an arm of their own, so the match is exhaustive without a catch-all.
+#[penum( (T) | _ where T: ^AsRef<str> )]
+enum Foo {
+    Bar(String),
+    Ber(),
+    Bur,
+}
+
+// Variants that aren't dispatched are still caught by the catch-all arm.
+#[penum( (T) | (i32, ..) | _ where T: ^AsRef<str> )]
+enum Mixed {
+    Bar(String),
+    Ber(i32, i32),
+    Bur,
+}
+
+fn main() {
+    assert_eq!("bar", Foo::Bar("bar".to_string()).as_ref());
+    assert_eq!("", Foo::Ber().as_ref());
+    assert_eq!("", Foo::Bur.as_ref());
+
+    assert_eq!("", Mixed::Ber(1, 2).as_ref());
+    assert_eq!("", Mixed::Bur.as_ref());
+}
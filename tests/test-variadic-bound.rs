@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( (String, ..rest: Copy) | { name: String, ..: Clone + Default } )]
+enum Record {
+    Short(String),
+    Pair(String, i32, u8),
+    Named {
+        name: String,
+        tags: Vec<String>,
+        id: u64,
+    },
+}
+
+fn main() {
+    let _ = Record::Pair("pair".to_string(), 1, 2);
+}
@@ -0,0 +1,28 @@
+#![allow(dead_code)]
+extern crate penum;
+
+#[penum::variant_count]
+pub enum Foo<T> {
+    Bar(T, i32),
+    Ber {
+        name: T,
+    },
+    Bur,
+    #[cfg(any())]
+    Byr,
+}
+
+#[penum::variant_count]
+enum Empty {}
+
+fn main() {
+    assert_eq!(3, Foo::<String>::VARIANT_COUNT);
+    assert_eq!(&["Bar", "Ber", "Bur"], Foo::<String>::VARIANT_NAMES);
+
+    assert_eq!("Bar", Foo::Bar("a", 1).variant_name());
+    assert_eq!("Ber", Foo::Ber { name: "a" }.variant_name());
+    assert_eq!("Bur", Foo::<()>::Bur.variant_name());
+
+    assert_eq!(0, Empty::VARIANT_COUNT);
+    assert!(Empty::VARIANT_NAMES.is_empty());
+}
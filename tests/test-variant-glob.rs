@@ -0,0 +1,17 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( Net*(T) | *Error(String, ..) | Halt where T: ^AsRef<[u8]> )]
+enum Instruction {
+    NetRead(Vec<u8>),
+    NetWrite(&'static [u8]),
+    IoError(String, i32),
+    ParseError(String),
+    Halt,
+}
+
+fn main() {
+    assert_eq!(&[1, 2], Instruction::NetRead(vec![1, 2]).as_ref());
+    assert_eq!(&[3], Instruction::NetWrite(&[3]).as_ref());
+}
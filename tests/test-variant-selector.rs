@@ -0,0 +1,18 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+// Each fragment only applies to the variant with the same name, so `Total` has to hold an `i32`
+// while `Label` can hold anything that can be read as a `str`.
+#[penum( Total(i32, ..) | Label(T) | _ where T: ^AsRef<str> )]
+enum Cell {
+    Total(i32, String),
+    Label(String),
+    Empty,
+}
+
+fn main() {
+    assert_eq!("label", Cell::Label("label".to_string()).as_ref());
+    assert_eq!("", Cell::Total(1, "total".to_string()).as_ref());
+    assert_eq!("", Cell::Empty.as_ref());
+}
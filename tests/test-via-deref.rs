@@ -0,0 +1,40 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Shout {
+    fn shout(&self) -> String;
+}
+
+impl Shout for str {
+    fn shout(&self) -> String {
+        self.to_uppercase()
+    }
+}
+
+#[penum( via_deref, (T) | { name: T } where T: ^Shout )]
+enum Foo {
+    Bar(String),
+    Ber { name: String },
+    Bur(Box<str>),
+}
+
+#[penum( via_deref, (T) where T: ^Shout )]
+enum Generic<T> {
+    Bar(T),
+}
+
+fn main() {
+    assert_eq!("BAR", Foo::Bar("bar".to_string()).shout());
+    assert_eq!(
+        "BER",
+        Foo::Ber {
+            name: "ber".to_string()
+        }
+        .shout()
+    );
+    assert_eq!("BUR", Foo::Bur("bur".into()).shout());
+
+    assert_eq!("GENERIC", Generic::Bar("generic".to_string()).shout());
+}
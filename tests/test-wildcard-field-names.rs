@@ -0,0 +1,24 @@
+#![allow(dead_code)]
+extern crate penum;
+use penum::penum;
+
+#[penum( { _: T, id: u8, .. } where T: ^AsRef<str> )]
+enum Node {
+    File { id: u8, path: String, size: u64 },
+    Link { target: &'static str, id: u8 },
+}
+
+fn main() {
+    let file = Node::File {
+        id: 1,
+        path: "src/lib.rs".to_string(),
+        size: 512,
+    };
+    let link = Node::Link {
+        target: "lib.rs",
+        id: 2,
+    };
+
+    assert_eq!("src/lib.rs", file.as_ref());
+    assert_eq!("lib.rs", link.as_ref());
+}
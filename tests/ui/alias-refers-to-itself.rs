@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (A), alias(A = Vec<A>) )]
+enum Foo {
+    Bar(Vec<u8>),
+}
+
+fn main() {}
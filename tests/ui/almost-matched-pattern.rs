@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (i32, i32, i32, i32) | { name: String } )]
+enum Foo {
+    Bar(u8, u8, u8),
+}
+
+fn main() {}
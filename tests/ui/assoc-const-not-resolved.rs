@@ -0,0 +1,17 @@
+extern crate penum;
+use penum::penum;
+
+#[penum]
+trait Identify {
+    const ID: u32;
+
+    fn id(&self) -> u32;
+}
+
+#[penum( unit | () where i32: ^Identify )]
+enum Foo {
+    Bar,
+    Ber(),
+}
+
+fn main() {}
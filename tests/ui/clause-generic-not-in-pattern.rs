@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( strict, (T, U) where Y: std::fmt::Display )]
+enum Foo {
+    Bar(i32, String),
+}
+
+fn main() {}
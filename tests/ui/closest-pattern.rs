@@ -0,0 +1,13 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (i32, T) | { name: String, age: T } where T: Copy )]
+enum Foo {
+    Bar(i32, u8),
+    Ber(i32, u8, u16),
+    Bur { name: String },
+    Bor { age: u8, id: u8, name: String },
+    Byr(u8, u8, u8, u8),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use penum::penum;
+
+#[penum( (T, U) where U: Into<T> )]
+enum Foo {
+    Bar(i64, i32),
+    Bor(i32, i64),
+}
+
+fn main() {}
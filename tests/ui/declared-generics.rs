@@ -0,0 +1,24 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( for<T, U> (T, V) )]
+enum Undeclared {
+    Bar(i32, i32),
+}
+
+#[penum( for<T, U> (T, T) )]
+enum Unused {
+    Bar(i32, i32),
+}
+
+#[penum( for<T, T> (T) )]
+enum Twice {
+    Bar(i32),
+}
+
+#[penum( for<T> (T) where U: Copy )]
+enum InClause {
+    Bar(i32),
+}
+
+fn main() {}
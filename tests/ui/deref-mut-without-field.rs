@@ -0,0 +1,15 @@
+#[penum::deref_mut(Vec<i32>)]
+enum Foo {
+    Bar(Vec<i32>) = f0,
+    Ber = "ber",
+    Bir,
+}
+
+#[penum::as_mut_str]
+enum Name {
+    Bar(String) = f0,
+    Ber { name: String },
+    default = f0,
+}
+
+fn main() {}
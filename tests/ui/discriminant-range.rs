@@ -0,0 +1,12 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( Low = 0..10 | * = _ as u8 )]
+enum Level {
+    Low = 10,
+    High = 256,
+    Max = 255,
+    Top,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) where T: Copy )]
+enum Foo {
+    Bar(i32),
+}
+
+#[penum( (T) where T: Copy )]
+enum Bar {
+    Ber(u8),
+}
+
+penum::family!(Foo, Bar);
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, U) where T: Copy, V: Clone | { name: V } )]
+enum Node {
+    Pair(i32, String),
+    Named { name: String },
+}
+
+fn main() {}
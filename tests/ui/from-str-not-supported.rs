@@ -0,0 +1,11 @@
+extern crate penum;
+
+#[penum::from_str]
+enum Color {
+    Red = "red",
+    Green(u8) = "green",
+    Blue = 3,
+    Black,
+}
+
+fn main() {}
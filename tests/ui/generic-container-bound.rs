@@ -0,0 +1,12 @@
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Display;
+
+#[penum( (Vec<T>) where T: Display )]
+enum Foo {
+    Bar(Vec<i32>),
+    Baz(Vec<Vec<u8>>),
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+extern crate penum;
+use penum::penum;
+
+use std::fmt::Display;
+
+#[penum( { name: impl Display, .. } )]
+enum Entry {
+    User { name: String, age: u8 },
+    Bytes { name: Vec<u8> },
+}
+
+fn main() {}
@@ -4,7 +4,7 @@ use penum::penum;
 
 trait Trait {}
 
-#[penum[ (impl ?Sized) ]]
+#[penum[ (impl ?Send) ]]
 enum Must {
     Static(usize),
 }
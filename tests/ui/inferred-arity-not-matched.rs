@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( _(3) )]
+enum Foo {
+    Bar(i32, String, usize),
+    Ber(i32, String),
+}
+
+fn main() {}
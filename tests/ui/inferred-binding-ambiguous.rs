@@ -0,0 +1,31 @@
+use penum::penum;
+
+#[penum]
+trait Abc {
+    type Input: ?Sized;
+    fn get(&self) -> &Self::Input;
+}
+
+#[penum]
+impl Abc for String {
+    type Input = str;
+    fn get(&self) -> &str {
+        self
+    }
+}
+
+#[penum]
+impl Abc for Vec<u8> {
+    type Input = [u8];
+    fn get(&self) -> &[u8] {
+        self
+    }
+}
+
+#[penum( (T) where T: ^Abc )]
+enum Foo {
+    Bar(String),
+    Bur(Vec<u8>),
+}
+
+fn main() {}
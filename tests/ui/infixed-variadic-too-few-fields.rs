@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, .., U) where T: Copy, U: Copy )]
+enum Foo {
+    Bar(i32, u8),
+    Ber(i32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (String: Copy, ..) )]
+enum Foo {
+    Bar(String, i32),
+}
+
+fn main() {}
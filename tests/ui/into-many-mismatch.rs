@@ -0,0 +1,9 @@
+extern crate penum;
+
+#[penum::into(u32, String)]
+enum Level {
+    Low = 1,
+    High = "high".to_string(),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+
+use penum::penum;
+
+#[penum[ (T) where T: 'b ]]
+enum Must<'a> {
+    Borrowed(&'a str),
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use penum::penum;
+
+#[penum]
+trait Spawn {
+    fn spawn() -> Self;
+    fn fork(&self) -> Self;
+    fn name(&self) -> String;
+}
+
+impl Spawn for i32 {
+    fn spawn() -> Self {
+        0
+    }
+    fn fork(&self) -> Self {
+        *self
+    }
+    fn name(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[penum( (T) where T: ^Spawn )]
+enum Foo {
+    Bar(i32),
+}
+
+fn main() {}
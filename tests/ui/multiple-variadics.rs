@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, .., U, ..) where T: Copy, U: Copy )]
+enum Foo {
+    Bar(i32, u8),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( name_of_index )]
+enum Foo {
+    Bar,
+    Ber(i32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( { age: usize, name: String } )]
+enum Person {
+    Adult { name: String, years: usize },
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( !(String, ..) | !Owned*{ .. } )]
+enum Message {
+    Borrowed(&'static str),
+    Owned(String, u8),
+    OwnedBytes { bytes: Vec<u8> },
+}
+
+fn main() {}
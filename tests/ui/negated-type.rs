@@ -0,0 +1,15 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (!String, ..) | { name: !String } )]
+enum Foo {
+    Bar(String, i32),
+    Baz { name: String },
+}
+
+#[penum( (!T) )]
+enum Generic {
+    Bar(i32),
+}
+
+fn main() {}
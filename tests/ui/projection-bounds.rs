@@ -0,0 +1,24 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) where T: IntoIterator, T::Item: Copy )]
+enum Foo {
+    Bar(Vec<String>),
+}
+
+#[penum( (T) where T::Item: Copy )]
+enum Ber {
+    Bar(Vec<u8>),
+}
+
+#[penum( (T) where T: IntoIterator, T::Item::Output: Copy )]
+enum Bir {
+    Bar(Vec<u8>),
+}
+
+#[penum( (T) where <T as IntoIterator>::Item::Output: Copy )]
+enum Bor {
+    Bar(Vec<u8>),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, 3..1) where T: Copy )]
+enum Foo {
+    Bar(i32, u8, u16),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, 2..3) where T: Copy )]
+enum Foo {
+    Bar(i32, u8, u16),
+    Ber(i32, u8),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T, ..2) where T: Copy )]
+enum Foo {
+    Bar(i32, u8, u16),
+    Ber(i32, u8, u16, u32),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( strict, (T) | { name: U } where U: Clone )]
+enum Foo {
+    Bar(i32),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( strict, (T) | (T, U) | { name: T } | (T, U, V) )]
+enum Foo {
+    Bar(i32),
+    Ber(i32, String),
+}
+
+fn main() {}
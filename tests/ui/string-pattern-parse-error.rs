@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum("(T,, U) where T: Copy")]
+enum Foo {
+    Bar(i32, i32),
+}
+
+fn main() {}
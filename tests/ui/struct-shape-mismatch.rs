@@ -0,0 +1,7 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (T) where T: Copy )]
+struct Foo(i32, u8);
+
+fn main() {}
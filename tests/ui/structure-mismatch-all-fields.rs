@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (i32, .., String) | { a: i32, b: u8 } )]
+enum Foo {
+    Bar(u8, u8, u8),
+    Baz { a: u16, b: u16 },
+}
+
+fn main() {}
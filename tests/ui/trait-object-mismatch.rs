@@ -0,0 +1,9 @@
+use penum::penum;
+
+#[penum( (Box<dyn Display>) )]
+enum Foo {
+    Bar(Box<dyn Display>),
+    Baz(Box<dyn Debug>),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( uniform, (T, ..) | { id: T } )]
+enum Foo {
+    Bar(i32, String),
+    Ber(u8),
+    Bur { id: i64 },
+}
+
+fn main() {}
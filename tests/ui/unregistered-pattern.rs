@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( use = Missing )]
+enum Foo {
+    Bar(i32, String),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( (String, ..rest: Copy) )]
+enum Foo {
+    Bar(String, i32, Vec<u8>),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+extern crate penum;
+use penum::penum;
+
+#[penum( Bar(T) | Baz { name: T } | _ where T: Clone )]
+enum Foo {
+    Bar(i32),
+    Bez { name: String },
+}
+
+fn main() {}